@@ -0,0 +1,191 @@
+//! Compare ways of looking up the `Product` for each abbreviation in a `.idx` file.
+//!
+//! Run with `cargo bench -p hypergrib_grib_tables --bench abbrev_lookup`.
+
+use std::{collections::HashMap, hint::black_box};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use hypergrib_grib_tables::{center_and_table_versions::CenterAndTableVersions, AbbrevToProduct};
+
+/// Generates two lookups from abbreviation to index in `ABBREVS`: `match_index`, a `match` over
+/// every abbreviation (which is what `AbbrevToProduct` uses), and `PHF_INDEX`, a `phf::Map`.
+macro_rules! lookups {
+    ($($abbrev:literal)*) => {
+        const ABBREVS: &[&str] = &[$($abbrev),*];
+
+        fn match_index(abbrev: &str) -> Option<usize> {
+            match abbrev {
+                $($abbrev => Some(const { index_of($abbrev) }),)*
+                _ => None,
+            }
+        }
+
+        static PHF_INDEX: phf::Map<&'static str, usize> = phf::phf_map! {
+            $($abbrev => const { index_of($abbrev) },)*
+        };
+    };
+}
+
+// Every abbreviation in the compiled-in master tables and NCEP's main local table.
+lookups! {
+    "TMP" "VTMP" "POT" "EPOT" "TMAX" "TMIN" "DPT" "DEPR" "LAPR" "TMPA" "LHTFL" "SHTFL" "SPFH"
+    "RH" "MIXR" "PWAT" "VAPP" "SATD" "EVP" "PRATE" "APCP" "NCPCP" "ACPCP" "SNOD" "SRWEQ"
+    "WEASD" "SNOC" "SNOL" "SNOM" "WDIR" "WIND" "UGRD" "VGRD" "STRM" "VPOT" "MNTSF" "SGCVV"
+    "VVEL" "DZDT" "ABSV" "ABSD" "RELV" "RELD" "PVORT" "PRES" "PRMSL" "PTEND" "ICAHT" "GP"
+    "HGT" "DIST" "HSTDV" "PRESA" "GPA" "TOZNE" "O3MR" "TCIOZ" "MASSDEN" "COLMD" "MASSMR"
+    "AEMFLX" "ANPMFLX" "ANPEMFLX" "SDDMFLX" "SWDMFLX" "AREMFLX" "SADEN" "ATMTK" "AOTK"
+    "SSALBK" "ASYSFK" "FFLDG" "FFLDRO" "RSSC" "ESCT" "SWEPON" "BGRUN" "SSRUN" "CPPOP" "PPOSP"
+    "POP" "SNOHF" "TTRAD" "CRAIN" "CFRZR" "CICEP" "CSNOW" "CPRAT" "FRAIN" "RIME" "TCOLR"
+    "TCOLS" "VWSH" "MFLX" "USTM" "VSTM" "MSLET" "5WAVH" "U-GWD" "V-GWD" "HPBL" "5WAVA" "MSLMA"
+    "PLPL" "OZCON" "OZCAT" "CPOZP" "PPFFG" "CWR"
+}
+
+const fn index_of(abbrev: &str) -> usize {
+    let mut i = 0;
+    while i < ABBREVS.len() {
+        if const_str_eq(ABBREVS[i], abbrev) {
+            return i;
+        }
+        i += 1;
+    }
+    panic!("abbreviation isn't in ABBREVS");
+}
+
+const fn const_str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// A `.idx` file like one from GFS: every product at many levels and steps. Most records are
+/// common WMO products, and about one in ten is one of NCEP's local products.
+fn gfs_like_idx(num_records: usize) -> String {
+    const COMMON: &[&str] = &[
+        "TMP", "UGRD", "VGRD", "HGT", "RH", "SPFH", "VVEL", "ABSV", "O3MR", "DZDT", "PRES",
+        "PRMSL", "APCP", "PRATE", "TMAX", "TMIN", "DPT", "PWAT", "WEASD", "SNOD",
+    ];
+    const LOCAL: &[&str] = &["CRAIN", "CSNOW", "HPBL", "MSLET", "U-GWD", "CPRAT"];
+    let mut idx = String::new();
+    let mut offset = 0u64;
+    for i in 0..num_records {
+        let abbrev = if i % 10 == 9 {
+            LOCAL[i % LOCAL.len()]
+        } else {
+            COMMON[i % COMMON.len()]
+        };
+        let level = [1000, 925, 850, 700, 500, 250][i % 6];
+        idx.push_str(&format!(
+            "{}:{offset}:d=2024010100:{abbrev}:{level} mb:{} hour fcst:\n",
+            i + 1,
+            i / 200
+        ));
+        offset += 987_654;
+    }
+    idx
+}
+
+fn abbrevs(idx: &str) -> Vec<&str> {
+    idx.lines()
+        .map(|line| line.split(':').nth(3).unwrap())
+        .collect()
+}
+
+fn bench_abbrev_lookup(c: &mut Criterion) {
+    let tables = CenterAndTableVersions::from_numbers(7, 0, 1, 33);
+
+    // The same products, in the other data structures.
+    let products: Vec<_> = tables.products().collect();
+    assert_eq!(products.len(), ABBREVS.len(), "ABBREVS is out of date");
+    let hash_map: HashMap<_, _> = products
+        .iter()
+        .map(|product| (product.abbrev(), *product))
+        .collect();
+    let mut sorted: Vec<_> = hash_map.iter().map(|(k, v)| (*k, *v)).collect();
+    sorted.sort_unstable_by_key(|(abbrev, _)| *abbrev);
+    let by_index: Vec<_> = ABBREVS
+        .iter()
+        .map(|abbrev| tables.abbrev_to_product(abbrev).unwrap())
+        .collect();
+    for abbrev in ABBREVS {
+        assert_eq!(by_index[match_index(abbrev).unwrap()].abbrev(), *abbrev);
+        assert_eq!(by_index[PHF_INDEX[abbrev]].abbrev(), *abbrev);
+    }
+
+    let mut group = c.benchmark_group("abbrev_lookup");
+    for num_records in [1_000, 10_000] {
+        let idx = gfs_like_idx(num_records);
+        let abbrevs = abbrevs(&idx);
+        group.throughput(Throughput::Elements(num_records as u64));
+        // What the crate actually does: look in the master tables, then in NCEP's local tables.
+        group.bench_with_input(
+            BenchmarkId::new("abbrev_to_product", num_records),
+            &abbrevs,
+            |b, abbrevs| {
+                b.iter(|| {
+                    for abbrev in abbrevs {
+                        black_box(tables.abbrev_to_product(black_box(abbrev)));
+                    }
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("phf", num_records),
+            &abbrevs,
+            |b, abbrevs| {
+                b.iter(|| {
+                    for abbrev in abbrevs {
+                        black_box(PHF_INDEX.get(black_box(abbrev)).map(|i| by_index[*i]));
+                    }
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("match", num_records),
+            &abbrevs,
+            |b, abbrevs| {
+                b.iter(|| {
+                    for abbrev in abbrevs {
+                        black_box(match_index(black_box(abbrev)).map(|i| by_index[i]));
+                    }
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("hash_map", num_records),
+            &abbrevs,
+            |b, abbrevs| {
+                b.iter(|| {
+                    for abbrev in abbrevs {
+                        black_box(hash_map.get(black_box(abbrev)));
+                    }
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("sorted_array", num_records),
+            &abbrevs,
+            |b, abbrevs| {
+                b.iter(|| {
+                    for abbrev in abbrevs {
+                        let i =
+                            sorted.binary_search_by_key(black_box(abbrev), |(abbrev, _)| abbrev);
+                        black_box(i.ok().map(|i| sorted[i].1));
+                    }
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_abbrev_lookup);
+criterion_main!(benches);
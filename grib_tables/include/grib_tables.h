@@ -0,0 +1,118 @@
+#ifndef GRIB_TABLES_H
+#define GRIB_TABLES_H
+
+/* Generated by cbindgen from src/ffi.rs. Don't edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// The numbers which identify a parameter in code table 4.2.
+typedef struct GribTablesProductNumbers {
+  uint8_t discipline;
+  uint8_t category;
+  uint8_t parameter;
+} GribTablesProductNumbers;
+
+// A parameter's abbreviation (e.g. "TMP"), name and unit. Free with
+// `grib_tables_product_free`.
+typedef struct GribTablesProduct {
+  char *abbrev;
+  char *name;
+  char *unit;
+} GribTablesProduct;
+
+// One line of a `.idx` file.
+typedef struct GribTablesIdxRecord {
+  uint32_t message_num;
+  // 0 unless the message holds more than one field, e.g. the `2` in `4.2`.
+  uint32_t field_num;
+  // The position of the start of the message, in bytes from the start of the GRIB2 file.
+  uint64_t offset;
+  uint16_t year;
+  uint8_t month;
+  uint8_t day;
+  uint8_t hour;
+  char *abbrev;
+  char *level;
+  char *step;
+  // Any fields after the step (e.g. `ENS=+1`), separated by colons. Empty if there are none.
+  char *extra;
+} GribTablesIdxRecord;
+
+// The records of a `.idx` file, in order. Free with `grib_tables_idx_records_free`.
+typedef struct GribTablesIdxRecords {
+  struct GribTablesIdxRecord *records;
+  size_t len;
+} GribTablesIdxRecords;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Look up the numbers of the parameter abbreviated `abbrev` (e.g. "TMP") in the tables of the
+// given center, sub-centre and table versions (as found in section 1). Returns false, and leaves
+// `numbers` untouched, if the abbreviation isn't known.
+//
+// # Safety
+//
+// `abbrev` must be a NUL-terminated string, and `numbers` must be valid for writes.
+bool grib_tables_abbrev_to_numbers(const char *abbrev,
+                                   uint16_t center_num,
+                                   uint16_t subcenter,
+                                   uint8_t local_table_version,
+                                   uint8_t master_table_version,
+                                   struct GribTablesProductNumbers *numbers);
+
+// Look up the parameter with the given numbers, in the tables of the given center, sub-centre and
+// table versions (as found in section 1). Returns NULL if the parameter isn't known.
+struct GribTablesProduct *grib_tables_product(uint8_t discipline,
+                                              uint8_t category,
+                                              uint8_t parameter,
+                                              uint16_t center_num,
+                                              uint16_t subcenter,
+                                              uint8_t local_table_version,
+                                              uint8_t master_table_version);
+
+// Free a product returned by `grib_tables_product`.
+//
+// # Safety
+//
+// `product` must be NULL or have been returned by `grib_tables_product`, and mustn't be used
+// afterwards.
+void grib_tables_product_free(struct GribTablesProduct *product);
+
+// Parse the contents of a `.idx` file (`len` bytes of UTF-8, which needn't be NUL-terminated).
+// Blank lines are skipped.
+//
+// Returns NULL if any line isn't a valid `.idx` record. If so, and `error` isn't NULL, `*error`
+// is set to a description of the problem, which must be freed with `grib_tables_string_free`.
+//
+// # Safety
+//
+// `idx` must be valid for reads of `len` bytes, and `error` must be NULL or valid for writes.
+struct GribTablesIdxRecords *grib_tables_idx_parse(const char *idx, size_t len, char **error);
+
+// Free the records returned by `grib_tables_idx_parse`, including their strings.
+//
+// # Safety
+//
+// `records` must be NULL or have been returned by `grib_tables_idx_parse`, and mustn't be used
+// afterwards.
+void grib_tables_idx_records_free(struct GribTablesIdxRecords *records);
+
+// Free a string returned by this API, e.g. an error from `grib_tables_idx_parse`.
+//
+// # Safety
+//
+// `string` must be NULL or have been returned by this API (and not already freed as part of a
+// struct), and mustn't be used afterwards.
+void grib_tables_string_free(char *string);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* GRIB_TABLES_H */
@@ -0,0 +1,41 @@
+use crate::product::Product;
+
+/// A table from abbreviation (e.g. "TMP") to product, built with [`abbrev_table!`].
+///
+/// Lookups use a `match` on the abbreviation, which `benches/abbrev_lookup.rs` shows is about
+/// three times faster than a `phf::Map`, a `HashMap` or a binary search: `rustc` compiles a string
+/// `match` to a branch on the string's length followed by a few comparisons, rather than hashing
+/// the whole string.
+pub(crate) struct AbbrevTable {
+    pub(crate) lookup: fn(&str) -> Option<&'static Product>,
+    pub(crate) products: &'static [Product],
+}
+
+impl AbbrevTable {
+    pub(crate) fn get(&self, abbrev: &str) -> Option<&'static Product> {
+        (self.lookup)(abbrev)
+    }
+
+    /// Every product in this table, in the order they're listed.
+    pub(crate) fn values(&self) -> impl Iterator<Item = &'static Product> {
+        self.products.iter()
+    }
+}
+
+/// Build an [`AbbrevTable`], with the same syntax as `phf::phf_map!`.
+macro_rules! abbrev_table {
+    ($($abbrev:literal => $product:expr),* $(,)?) => {{
+        fn lookup(abbrev: &str) -> Option<&'static Product> {
+            match abbrev {
+                $($abbrev => Some(&$product),)*
+                _ => None,
+            }
+        }
+        AbbrevTable {
+            lookup,
+            products: &[$($product),*],
+        }
+    }};
+}
+
+pub(crate) use abbrev_table;
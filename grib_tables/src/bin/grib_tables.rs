@@ -0,0 +1,121 @@
+//! Command line tools for maintaining the GRIB tables.
+//!
+//! ```text
+//! grib_tables diff-tables <older master table version> <newer master table version | WMO CSV>
+//! grib_tables validate <GRIB2 file> [<.idx file>]
+//! grib_tables validate-idx <.idx file> <center> <sub-centre> <local table version> <master table version>
+//! ```
+//!
+//! `validate-idx` checks a `.idx` file without its GRIB2 file, decoding it with the tables of the
+//! given center (common code table C-11), sub-centre (0 for the center itself) and table versions
+//! (code tables 1.1 and 1.0).
+//! `validate` and `validate-idx` exit with status 1 if they find any issues.
+
+use std::{fs::File, process::ExitCode};
+
+use hypergrib_grib_tables::{error::Error, table_diff::ParameterTable, validate};
+
+const USAGE: &str = "usage:
+    grib_tables diff-tables <older master table version> <newer master table version | path to WMO's GRIB2_CodeFlag_4_2 CSV>
+    grib_tables validate <GRIB2 file> [<.idx file>]
+    grib_tables validate-idx <.idx file> <center> <sub-centre> <local table version> <master table version>";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let result = match args.as_slice() {
+        ["diff-tables", older, newer] if older.parse::<u8>().is_ok() => {
+            diff_tables(older.parse().unwrap(), newer)
+        }
+        ["validate", grib] => validate(grib, None),
+        ["validate", grib, idx] => validate(grib, Some(idx)),
+        ["validate-idx", idx, center, subcenter, local_table_version, master_table_version] => {
+            match (
+                center.parse(),
+                subcenter.parse(),
+                local_table_version.parse(),
+                master_table_version.parse(),
+            ) {
+                (Ok(center), Ok(subcenter), Ok(local_table_version), Ok(master_table_version)) => {
+                    validate_idx(
+                        idx,
+                        center,
+                        subcenter,
+                        local_table_version,
+                        master_table_version,
+                    )
+                }
+                _ => {
+                    eprintln!("{USAGE}");
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+    match result {
+        Ok(exit_code) => exit_code,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn diff_tables(older_version: u8, newer: &str) -> Result<ExitCode, Error> {
+    let older = ParameterTable::from_master_table_version(older_version);
+    let newer = match newer.parse() {
+        Ok(newer_version) => ParameterTable::from_master_table_version(newer_version),
+        Err(_) => ParameterTable::from_wmo_csv(File::open(newer)?)?,
+    };
+    let diff = older.diff(&newer);
+    if diff.is_empty() {
+        println!("no differences");
+    } else {
+        print!("{diff}");
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn validate(grib: &str, idx: Option<&str>) -> Result<ExitCode, Error> {
+    let grib = std::fs::read(grib)?;
+    let report = match idx {
+        Some(idx) => validate::validate_grib_and_idx(&grib, &std::fs::read_to_string(idx)?)?,
+        None => validate::validate_grib(&grib),
+    };
+    Ok(print_report(&report))
+}
+
+fn validate_idx(
+    idx: &str,
+    center: u16,
+    subcenter: u16,
+    local_table_version: u8,
+    master_table_version: u8,
+) -> Result<ExitCode, Error> {
+    let report = validate::validate_idx(
+        &std::fs::read_to_string(idx)?,
+        center,
+        subcenter,
+        local_table_version,
+        master_table_version,
+    )?;
+    Ok(print_report(&report))
+}
+
+fn print_report(report: &validate::Report) -> ExitCode {
+    print!("{report}");
+    println!(
+        "checked {} fields: {} issues",
+        report.num_fields,
+        report.issues.len()
+    );
+    if report.is_valid() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
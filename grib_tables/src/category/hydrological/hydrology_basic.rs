@@ -0,0 +1,103 @@
+use core::ops::RangeInclusive;
+
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+
+use crate::{
+    center_and_table_versions::CenterAndTableVersions, code_table::CodeValue, semantics::Semantics,
+    Parameter,
+};
+
+#[derive(FromPrimitive, Clone, Copy)]
+pub enum HydrologyBasicParameter {
+    FlashFloodGuidance = 0,
+    FlashFloodRunoff,
+    RemotelySensedSnowCover,
+    ElevationOfSnowCoveredTerrain,
+    SnowWaterEquivalentPercentOfNormal,
+    BaseflowGroundwaterRunoff,
+    StormSurfaceRunoff,
+    // etc.
+
+    // NCEP's local 192 and 193 are "BGRUN" and "SSRUN" again, so an abbreviation can't tell them
+    // apart from 5 and 6. We only decode the WMO numbers.
+}
+
+impl Parameter for HydrologyBasicParameter {
+    fn from_parameter_num(
+        parameter_num: u8,
+        _center_and_table_versions: &CenterAndTableVersions,
+    ) -> CodeValue<Self>
+    where
+        Self: Sized,
+    {
+        CodeValue::new(parameter_num, HydrologyBasicParameter::from_u8, |_| None)
+    }
+
+    fn parameter_num(&self) -> u8 {
+        *self as u8
+    }
+
+    fn abbrev(&self) -> &'static str {
+        match *self {
+            HydrologyBasicParameter::FlashFloodGuidance => "FFLDG",
+            HydrologyBasicParameter::FlashFloodRunoff => "FFLDRO",
+            HydrologyBasicParameter::RemotelySensedSnowCover => "RSSC",
+            HydrologyBasicParameter::ElevationOfSnowCoveredTerrain => "ESCT",
+            HydrologyBasicParameter::SnowWaterEquivalentPercentOfNormal => "SWEPON",
+            HydrologyBasicParameter::BaseflowGroundwaterRunoff => "BGRUN",
+            HydrologyBasicParameter::StormSurfaceRunoff => "SSRUN",
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match *self {
+            HydrologyBasicParameter::FlashFloodGuidance => "Flash flood guidance",
+            HydrologyBasicParameter::FlashFloodRunoff => "Flash flood runoff",
+            HydrologyBasicParameter::RemotelySensedSnowCover => "Remotely sensed snow cover",
+            HydrologyBasicParameter::ElevationOfSnowCoveredTerrain => {
+                "Elevation of snow covered terrain"
+            }
+            HydrologyBasicParameter::SnowWaterEquivalentPercentOfNormal => {
+                "Snow water equivalent percent of normal"
+            }
+            HydrologyBasicParameter::BaseflowGroundwaterRunoff => "Baseflow-groundwater runoff",
+            HydrologyBasicParameter::StormSurfaceRunoff => "Storm surface runoff",
+        }
+    }
+
+    fn unit(&self) -> &'static str {
+        match *self {
+            HydrologyBasicParameter::FlashFloodGuidance
+            | HydrologyBasicParameter::FlashFloodRunoff
+            | HydrologyBasicParameter::BaseflowGroundwaterRunoff
+            | HydrologyBasicParameter::StormSurfaceRunoff => "kg m-2",
+            HydrologyBasicParameter::RemotelySensedSnowCover => "Code table 4.215",
+            HydrologyBasicParameter::ElevationOfSnowCoveredTerrain => "Code table 4.216",
+            HydrologyBasicParameter::SnowWaterEquivalentPercentOfNormal => "%",
+        }
+    }
+
+    fn semantics(&self) -> Semantics {
+        match *self {
+            HydrologyBasicParameter::FlashFloodGuidance
+            | HydrologyBasicParameter::FlashFloodRunoff
+            | HydrologyBasicParameter::BaseflowGroundwaterRunoff
+            | HydrologyBasicParameter::StormSurfaceRunoff => Semantics::AccumulatedOverWindow,
+            _ => Semantics::Instantaneous,
+        }
+    }
+
+    fn plausible_range(&self) -> Option<RangeInclusive<f64>> {
+        match *self {
+            HydrologyBasicParameter::FlashFloodGuidance
+            | HydrologyBasicParameter::FlashFloodRunoff
+            | HydrologyBasicParameter::BaseflowGroundwaterRunoff
+            | HydrologyBasicParameter::StormSurfaceRunoff => Some(0.0..=2000.0),
+            // Snow can be far above normal where the normal is small.
+            HydrologyBasicParameter::SnowWaterEquivalentPercentOfNormal => None,
+            HydrologyBasicParameter::RemotelySensedSnowCover
+            | HydrologyBasicParameter::ElevationOfSnowCoveredTerrain => Some(0.0..=255.0),
+        }
+    }
+}
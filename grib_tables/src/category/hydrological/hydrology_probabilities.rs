@@ -0,0 +1,115 @@
+use core::ops::RangeInclusive;
+
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+
+use crate::{
+    center_and_table_versions::CenterAndTableVersions, code_table::CodeValue,
+    originating_center::OriginatingCenter, semantics::Semantics, Parameter,
+};
+
+#[derive(FromPrimitive, Clone, Copy)]
+pub enum HydrologyProbabilitiesParameter {
+    ConditionalPercentPrecipitationAmountFractile = 0,
+    PercentPrecipitationInSubPeriod,
+    ProbabilityOfPrecipitation,
+    // etc.
+
+    // NCEP local:
+    NcepProbabilityOfFreezingPrecipitation = 192,
+    // 193 is "CPOFP", which is 0.1.39 in the WMO tables.
+    NcepProbabilityOfPrecipitationExceedingFlashFloodGuidance = 194,
+    NcepProbabilityOfWettingRain,
+    // etc.
+}
+
+impl Parameter for HydrologyProbabilitiesParameter {
+    fn from_parameter_num(
+        parameter_num: u8,
+        center_and_table_versions: &CenterAndTableVersions,
+    ) -> CodeValue<Self>
+    where
+        Self: Sized,
+    {
+        CodeValue::new(
+            parameter_num,
+            HydrologyProbabilitiesParameter::from_u8,
+            |parameter_num| match center_and_table_versions.originating_center() {
+                center @ OriginatingCenter::Ncep { .. } => {
+                    HydrologyProbabilitiesParameter::from_u8(parameter_num)
+                        .filter(|parameter| center.has_local_parameter(parameter.abbrev()))
+                }
+                OriginatingCenter::Other { .. } => None,
+            },
+        )
+    }
+
+    fn parameter_num(&self) -> u8 {
+        *self as u8
+    }
+
+    fn abbrev(&self) -> &'static str {
+        match *self {
+            HydrologyProbabilitiesParameter::ConditionalPercentPrecipitationAmountFractile => {
+                "CPPOP"
+            }
+            HydrologyProbabilitiesParameter::PercentPrecipitationInSubPeriod => "PPOSP",
+            HydrologyProbabilitiesParameter::ProbabilityOfPrecipitation => "POP",
+
+            // Local to NCEP:
+            HydrologyProbabilitiesParameter::NcepProbabilityOfFreezingPrecipitation => "CPOZP",
+            HydrologyProbabilitiesParameter::NcepProbabilityOfPrecipitationExceedingFlashFloodGuidance => "PPFFG",
+            HydrologyProbabilitiesParameter::NcepProbabilityOfWettingRain => "CWR",
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match *self {
+            HydrologyProbabilitiesParameter::ConditionalPercentPrecipitationAmountFractile => {
+                "Conditional percent precipitation amount fractile for an overall period"
+            }
+            HydrologyProbabilitiesParameter::PercentPrecipitationInSubPeriod => {
+                "Percent precipitation in a sub-period of an overall period"
+            }
+            HydrologyProbabilitiesParameter::ProbabilityOfPrecipitation => {
+                "Probability of 0.01 inch of precipitation"
+            }
+            HydrologyProbabilitiesParameter::NcepProbabilityOfFreezingPrecipitation => {
+                "Probability of freezing precipitation"
+            }
+            HydrologyProbabilitiesParameter::NcepProbabilityOfPrecipitationExceedingFlashFloodGuidance => {
+                "Probability of precipitation exceeding flash flood guidance values"
+            }
+            HydrologyProbabilitiesParameter::NcepProbabilityOfWettingRain => {
+                "Probability of wetting rain, exceeding 0.1 inch in a given time period"
+            }
+        }
+    }
+
+    fn unit(&self) -> &'static str {
+        match *self {
+            HydrologyProbabilitiesParameter::ConditionalPercentPrecipitationAmountFractile => {
+                "kg m-2"
+            }
+            _ => "%",
+        }
+    }
+
+    fn semantics(&self) -> Semantics {
+        match *self {
+            HydrologyProbabilitiesParameter::ConditionalPercentPrecipitationAmountFractile => {
+                Semantics::AccumulatedOverWindow
+            }
+            _ => Semantics::Instantaneous,
+        }
+    }
+
+    fn plausible_range(&self) -> Option<RangeInclusive<f64>> {
+        match *self {
+            HydrologyProbabilitiesParameter::ConditionalPercentPrecipitationAmountFractile => {
+                Some(0.0..=2000.0)
+            }
+            _ => Some(0.0..=100.0),
+        }
+    }
+}
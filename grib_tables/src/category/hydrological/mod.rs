@@ -1,9 +1,17 @@
-use crate::center_and_table_versions::CenterAndTableVersions;
+use hydrology_basic::HydrologyBasicParameter;
+use hydrology_probabilities::HydrologyProbabilitiesParameter;
+
+use crate::{center_and_table_versions::CenterAndTableVersions, code_table::CodeValue, Parameter};
 
 use super::Category;
 
-pub(crate) enum HydrologicalCategory {
-    HydrologyBasicProduct, // TODO: Add embedded enum
+pub(crate) mod hydrology_basic;
+pub(crate) mod hydrology_probabilities;
+
+pub enum HydrologicalCategory {
+    HydrologyBasic(HydrologyBasicParameter),
+    HydrologyProbabilities(HydrologyProbabilitiesParameter),
+    // etc.
 }
 
 impl Category for HydrologicalCategory {
@@ -11,10 +19,31 @@ impl Category for HydrologicalCategory {
         category_num: u8,
         parameter_num: u8,
         center_and_table_versions: &CenterAndTableVersions,
-    ) -> Option<Self>
+    ) -> CodeValue<Self>
     where
         Self: Sized,
     {
-        todo!();
+        let ctv = center_and_table_versions;
+        match category_num {
+            0 => HydrologyBasicParameter::from_parameter_num(parameter_num, ctv)
+                .map(HydrologicalCategory::HydrologyBasic),
+            1 => HydrologyProbabilitiesParameter::from_parameter_num(parameter_num, ctv)
+                .map(HydrologicalCategory::HydrologyProbabilities),
+            _ => CodeValue::unknown(category_num),
+        }
+    }
+
+    fn category_num(&self) -> u8 {
+        match self {
+            HydrologicalCategory::HydrologyBasic(_) => 0,
+            HydrologicalCategory::HydrologyProbabilities(_) => 1,
+        }
+    }
+
+    fn parameter(&self) -> &dyn Parameter {
+        match self {
+            HydrologicalCategory::HydrologyBasic(parameter) => parameter,
+            HydrologicalCategory::HydrologyProbabilities(parameter) => parameter,
+        }
     }
 }
@@ -0,0 +1,154 @@
+use core::ops::RangeInclusive;
+
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+
+use crate::{
+    center_and_table_versions::CenterAndTableVersions, code_table::CodeValue, semantics::Semantics,
+    Parameter,
+};
+
+/// Category 20, "Atmospheric chemical constituents". These parameters are only meaningful
+/// together with the constituent type (code tables 4.230 and 4.233) from product definition
+/// templates 4.40 to 4.48, e.g. "mass density" of "ozone".
+#[derive(FromPrimitive, Clone, Copy)]
+pub enum AtmosphericChemistryParameter {
+    MassDensity = 0,
+    ColumnIntegratedMassDensity,
+    MassMixingRatio,
+    AtmosphereEmissionMassFlux,
+    AtmosphereNetProductionMassFlux,
+    AtmosphereNetProductionAndEmissionMassFlux,
+    SurfaceDryDepositionMassFlux,
+    SurfaceWetDepositionMassFlux,
+    AtmosphereReEmissionMassFlux,
+    // etc.
+    SurfaceAreaDensity = 100,
+    VerticalVisualRange,
+    AtmosphereOpticalThickness,
+    SingleScatteringAlbedo,
+    AsymmetryFactor,
+    // etc.
+}
+
+impl Parameter for AtmosphericChemistryParameter {
+    fn from_parameter_num(
+        parameter_num: u8,
+        _center_and_table_versions: &CenterAndTableVersions,
+    ) -> CodeValue<Self>
+    where
+        Self: Sized,
+    {
+        // No center that we support defines local parameters in this category.
+        CodeValue::new(
+            parameter_num,
+            AtmosphericChemistryParameter::from_u8,
+            |_| None,
+        )
+    }
+
+    fn parameter_num(&self) -> u8 {
+        *self as u8
+    }
+
+    fn abbrev(&self) -> &'static str {
+        match *self {
+            AtmosphericChemistryParameter::MassDensity => "MASSDEN",
+            AtmosphericChemistryParameter::ColumnIntegratedMassDensity => "COLMD",
+            AtmosphericChemistryParameter::MassMixingRatio => "MASSMR",
+            AtmosphericChemistryParameter::AtmosphereEmissionMassFlux => "AEMFLX",
+            AtmosphericChemistryParameter::AtmosphereNetProductionMassFlux => "ANPMFLX",
+            AtmosphericChemistryParameter::AtmosphereNetProductionAndEmissionMassFlux => "ANPEMFLX",
+            AtmosphericChemistryParameter::SurfaceDryDepositionMassFlux => "SDDMFLX",
+            AtmosphericChemistryParameter::SurfaceWetDepositionMassFlux => "SWDMFLX",
+            AtmosphericChemistryParameter::AtmosphereReEmissionMassFlux => "AREMFLX",
+            AtmosphericChemistryParameter::SurfaceAreaDensity => "SADEN",
+            AtmosphericChemistryParameter::VerticalVisualRange => "ATMTK",
+            AtmosphericChemistryParameter::AtmosphereOpticalThickness => "AOTK",
+            AtmosphericChemistryParameter::SingleScatteringAlbedo => "SSALBK",
+            AtmosphericChemistryParameter::AsymmetryFactor => "ASYSFK",
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match *self {
+            AtmosphericChemistryParameter::MassDensity => "Mass density (concentration)",
+            AtmosphericChemistryParameter::ColumnIntegratedMassDensity => {
+                "Column-integrated mass density"
+            }
+            AtmosphericChemistryParameter::MassMixingRatio => {
+                "Mass mixing ratio (mass fraction in air)"
+            }
+            AtmosphericChemistryParameter::AtmosphereEmissionMassFlux => {
+                "Atmosphere emission mass flux"
+            }
+            AtmosphericChemistryParameter::AtmosphereNetProductionMassFlux => {
+                "Atmosphere net production mass flux"
+            }
+            AtmosphericChemistryParameter::AtmosphereNetProductionAndEmissionMassFlux => {
+                "Atmosphere net production and emission mass flux"
+            }
+            AtmosphericChemistryParameter::SurfaceDryDepositionMassFlux => {
+                "Surface dry deposition mass flux"
+            }
+            AtmosphericChemistryParameter::SurfaceWetDepositionMassFlux => {
+                "Surface wet deposition mass flux"
+            }
+            AtmosphericChemistryParameter::AtmosphereReEmissionMassFlux => {
+                "Atmosphere re-emission mass flux"
+            }
+            AtmosphericChemistryParameter::SurfaceAreaDensity => "Surface area density (aerosol)",
+            AtmosphericChemistryParameter::VerticalVisualRange => "Vertical visual range",
+            AtmosphericChemistryParameter::AtmosphereOpticalThickness => {
+                "Atmosphere optical thickness"
+            }
+            AtmosphericChemistryParameter::SingleScatteringAlbedo => "Single scattering albedo",
+            AtmosphericChemistryParameter::AsymmetryFactor => "Asymmetry factor",
+        }
+    }
+
+    fn unit(&self) -> &'static str {
+        match *self {
+            AtmosphericChemistryParameter::MassDensity => "kg m-3",
+            AtmosphericChemistryParameter::ColumnIntegratedMassDensity => "kg m-2",
+            AtmosphericChemistryParameter::MassMixingRatio => "kg kg-1",
+            AtmosphericChemistryParameter::AtmosphereEmissionMassFlux
+            | AtmosphericChemistryParameter::AtmosphereNetProductionMassFlux
+            | AtmosphericChemistryParameter::AtmosphereNetProductionAndEmissionMassFlux
+            | AtmosphericChemistryParameter::SurfaceDryDepositionMassFlux
+            | AtmosphericChemistryParameter::SurfaceWetDepositionMassFlux
+            | AtmosphericChemistryParameter::AtmosphereReEmissionMassFlux => "kg m-2 s-1",
+            AtmosphericChemistryParameter::SurfaceAreaDensity => "m-1",
+            AtmosphericChemistryParameter::VerticalVisualRange => "m",
+            AtmosphericChemistryParameter::AtmosphereOpticalThickness
+            | AtmosphericChemistryParameter::SingleScatteringAlbedo
+            | AtmosphericChemistryParameter::AsymmetryFactor => "Numeric",
+        }
+    }
+
+    fn semantics(&self) -> Semantics {
+        Semantics::Instantaneous
+    }
+
+    fn plausible_range(&self) -> Option<RangeInclusive<f64>> {
+        match *self {
+            // Fluxes may be negative, e.g. net production.
+            AtmosphericChemistryParameter::AtmosphereEmissionMassFlux
+            | AtmosphericChemistryParameter::AtmosphereNetProductionMassFlux
+            | AtmosphericChemistryParameter::AtmosphereNetProductionAndEmissionMassFlux
+            | AtmosphericChemistryParameter::SurfaceDryDepositionMassFlux
+            | AtmosphericChemistryParameter::SurfaceWetDepositionMassFlux
+            | AtmosphericChemistryParameter::AtmosphereReEmissionMassFlux => None,
+            AtmosphericChemistryParameter::MassMixingRatio
+            | AtmosphericChemistryParameter::SingleScatteringAlbedo => Some(0.0..=1.0),
+            AtmosphericChemistryParameter::AsymmetryFactor => Some(-1.0..=1.0),
+            AtmosphericChemistryParameter::MassDensity
+            | AtmosphericChemistryParameter::ColumnIntegratedMassDensity
+            | AtmosphericChemistryParameter::SurfaceAreaDensity
+            | AtmosphericChemistryParameter::VerticalVisualRange
+            | AtmosphericChemistryParameter::AtmosphereOpticalThickness => {
+                Some(0.0..=f64::INFINITY)
+            }
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use core::ops::RangeInclusive;
+
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+
+use crate::{
+    center_and_table_versions::CenterAndTableVersions, code_table::CodeValue,
+    originating_center::OriginatingCenter, semantics::Semantics, Parameter,
+};
+
+#[derive(FromPrimitive, Clone, Copy)]
+pub enum MassParameter {
+    Pressure = 0,
+    PressureReducedToMsl,
+    PressureTendency,
+    IcaoStandardAtmosphereReferenceHeight,
+    Geopotential,
+    GeopotentialHeight,
+    GeometricHeight,
+    StandardDeviationOfHeight,
+    PressureAnomaly,
+    GeopotentialHeightAnomaly,
+    // etc.
+
+    // NCEP local:
+    NcepMslpEtaModelReduction = 192,
+    NcepFiveWaveGeopotentialHeight,
+    NcepZonalFluxOfGravityWaveStress,
+    NcepMeridionalFluxOfGravityWaveStress,
+    NcepPlanetaryBoundaryLayerHeight,
+    NcepFiveWaveGeopotentialHeightAnomaly,
+    NcepMslpMapsSystemReduction,
+    NcepPressureOfLevelFromWhichParcelWasLifted = 200,
+    // etc.
+}
+
+impl Parameter for MassParameter {
+    fn from_parameter_num(
+        parameter_num: u8,
+        center_and_table_versions: &CenterAndTableVersions,
+    ) -> CodeValue<Self>
+    where
+        Self: Sized,
+    {
+        CodeValue::new(parameter_num, MassParameter::from_u8, |parameter_num| {
+            match center_and_table_versions.originating_center() {
+                center @ OriginatingCenter::Ncep { .. } => MassParameter::from_u8(parameter_num)
+                    .filter(|parameter| center.has_local_parameter(parameter.abbrev())),
+                OriginatingCenter::Other { .. } => None,
+            }
+        })
+    }
+
+    fn parameter_num(&self) -> u8 {
+        *self as u8
+    }
+
+    fn abbrev(&self) -> &'static str {
+        match *self {
+            MassParameter::Pressure => "PRES",
+            MassParameter::PressureReducedToMsl => "PRMSL",
+            MassParameter::PressureTendency => "PTEND",
+            MassParameter::IcaoStandardAtmosphereReferenceHeight => "ICAHT",
+            MassParameter::Geopotential => "GP",
+            MassParameter::GeopotentialHeight => "HGT",
+            MassParameter::GeometricHeight => "DIST",
+            MassParameter::StandardDeviationOfHeight => "HSTDV",
+            MassParameter::PressureAnomaly => "PRESA",
+            MassParameter::GeopotentialHeightAnomaly => "GPA",
+
+            // Local to NCEP:
+            MassParameter::NcepMslpEtaModelReduction => "MSLET",
+            MassParameter::NcepFiveWaveGeopotentialHeight => "5WAVH",
+            MassParameter::NcepZonalFluxOfGravityWaveStress => "U-GWD",
+            MassParameter::NcepMeridionalFluxOfGravityWaveStress => "V-GWD",
+            MassParameter::NcepPlanetaryBoundaryLayerHeight => "HPBL",
+            MassParameter::NcepFiveWaveGeopotentialHeightAnomaly => "5WAVA",
+            MassParameter::NcepMslpMapsSystemReduction => "MSLMA",
+            MassParameter::NcepPressureOfLevelFromWhichParcelWasLifted => "PLPL",
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match *self {
+            MassParameter::Pressure => "Pressure",
+            MassParameter::PressureReducedToMsl => "Pressure reduced to MSL",
+            MassParameter::PressureTendency => "Pressure tendency",
+            MassParameter::IcaoStandardAtmosphereReferenceHeight => {
+                "ICAO Standard Atmosphere reference height"
+            }
+            MassParameter::Geopotential => "Geopotential",
+            MassParameter::GeopotentialHeight => "Geopotential height",
+            MassParameter::GeometricHeight => "Geometric height",
+            MassParameter::StandardDeviationOfHeight => "Standard deviation of height",
+            MassParameter::PressureAnomaly => "Pressure anomaly",
+            MassParameter::GeopotentialHeightAnomaly => "Geopotential height anomaly",
+            MassParameter::NcepMslpEtaModelReduction => "MSLP (Eta model reduction)",
+            MassParameter::NcepFiveWaveGeopotentialHeight => "5-wave geopotential height",
+            MassParameter::NcepZonalFluxOfGravityWaveStress => "Zonal flux of gravity wave stress",
+            MassParameter::NcepMeridionalFluxOfGravityWaveStress => {
+                "Meridional flux of gravity wave stress"
+            }
+            MassParameter::NcepPlanetaryBoundaryLayerHeight => "Planetary boundary layer height",
+            MassParameter::NcepFiveWaveGeopotentialHeightAnomaly => {
+                "5-wave geopotential height anomaly"
+            }
+            MassParameter::NcepMslpMapsSystemReduction => "MSLP (MAPS system reduction)",
+            MassParameter::NcepPressureOfLevelFromWhichParcelWasLifted => {
+                "Pressure of level from which parcel was lifted"
+            }
+        }
+    }
+
+    fn unit(&self) -> &'static str {
+        match *self {
+            MassParameter::Pressure
+            | MassParameter::PressureReducedToMsl
+            | MassParameter::PressureAnomaly
+            | MassParameter::NcepMslpEtaModelReduction
+            | MassParameter::NcepMslpMapsSystemReduction
+            | MassParameter::NcepPressureOfLevelFromWhichParcelWasLifted => "Pa",
+            MassParameter::PressureTendency => "Pa s-1",
+            MassParameter::IcaoStandardAtmosphereReferenceHeight
+            | MassParameter::GeometricHeight
+            | MassParameter::StandardDeviationOfHeight
+            | MassParameter::NcepPlanetaryBoundaryLayerHeight => "m",
+            MassParameter::Geopotential => "m2 s-2",
+            MassParameter::GeopotentialHeight
+            | MassParameter::GeopotentialHeightAnomaly
+            | MassParameter::NcepFiveWaveGeopotentialHeight
+            | MassParameter::NcepFiveWaveGeopotentialHeightAnomaly => "gpm",
+            MassParameter::NcepZonalFluxOfGravityWaveStress
+            | MassParameter::NcepMeridionalFluxOfGravityWaveStress => "N m-2",
+        }
+    }
+
+    fn semantics(&self) -> Semantics {
+        Semantics::Instantaneous
+    }
+
+    fn plausible_range(&self) -> Option<RangeInclusive<f64>> {
+        match *self {
+            MassParameter::Pressure
+            | MassParameter::NcepPressureOfLevelFromWhichParcelWasLifted => Some(0.0..=110_000.0),
+            MassParameter::PressureReducedToMsl
+            | MassParameter::NcepMslpEtaModelReduction
+            | MassParameter::NcepMslpMapsSystemReduction => Some(85_000.0..=110_000.0),
+            MassParameter::GeopotentialHeight
+            | MassParameter::GeometricHeight
+            | MassParameter::IcaoStandardAtmosphereReferenceHeight
+            | MassParameter::NcepFiveWaveGeopotentialHeight => Some(-1000.0..=100_000.0),
+            MassParameter::Geopotential => Some(-10_000.0..=1_000_000.0),
+            MassParameter::NcepPlanetaryBoundaryLayerHeight => Some(0.0..=10_000.0),
+            _ => None,
+        }
+    }
+}
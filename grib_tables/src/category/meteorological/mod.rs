@@ -1,16 +1,30 @@
+use atmospheric_chemistry::AtmosphericChemistryParameter;
+use mass::MassParameter;
 use moisture::MoistureParameter;
+use momentum::MomentumParameter;
 use temperature::TemperatureParameter;
+use trace_gases::TraceGasesParameter;
 
-use crate::{center_and_table_versions::CenterAndTableVersions, Parameter};
+use crate::{center_and_table_versions::CenterAndTableVersions, code_table::CodeValue, Parameter};
 
 use super::Category;
 
+pub(crate) mod atmospheric_chemistry;
+pub(crate) mod mass;
 pub(crate) mod moisture;
+pub(crate) mod momentum;
 pub(crate) mod temperature;
+pub(crate) mod trace_gases;
 
-pub(crate) enum MeteorologicalCategory {
+pub enum MeteorologicalCategory {
     Temperature(TemperatureParameter),
     Moisture(MoistureParameter),
+    Momentum(MomentumParameter),
+    Mass(MassParameter),
+    // etc.
+    TraceGases(TraceGasesParameter),
+    // etc.
+    AtmosphericChemistry(AtmosphericChemistryParameter),
     // etc.
 }
 
@@ -19,18 +33,47 @@ impl Category for MeteorologicalCategory {
         category_num: u8,
         parameter_num: u8,
         center_and_table_versions: &CenterAndTableVersions,
-    ) -> Option<Self>
+    ) -> CodeValue<Self>
     where
         Self: Sized,
     {
+        let ctv = center_and_table_versions;
         match category_num {
-            0 => Some(MeteorologicalCategory::Temperature(
-                TemperatureParameter::from_parameter_num(parameter_num, center_and_table_versions)?,
-            )),
-            1 => Some(MeteorologicalCategory::Moisture(
-                MoistureParameter::from_parameter_num(parameter_num, center_and_table_versions)?,
-            )),
-            _ => None,
+            0 => TemperatureParameter::from_parameter_num(parameter_num, ctv)
+                .map(MeteorologicalCategory::Temperature),
+            1 => MoistureParameter::from_parameter_num(parameter_num, ctv)
+                .map(MeteorologicalCategory::Moisture),
+            2 => MomentumParameter::from_parameter_num(parameter_num, ctv)
+                .map(MeteorologicalCategory::Momentum),
+            3 => MassParameter::from_parameter_num(parameter_num, ctv)
+                .map(MeteorologicalCategory::Mass),
+            14 => TraceGasesParameter::from_parameter_num(parameter_num, ctv)
+                .map(MeteorologicalCategory::TraceGases),
+            20 => AtmosphericChemistryParameter::from_parameter_num(parameter_num, ctv)
+                .map(MeteorologicalCategory::AtmosphericChemistry),
+            _ => CodeValue::unknown(category_num),
+        }
+    }
+
+    fn category_num(&self) -> u8 {
+        match self {
+            MeteorologicalCategory::Temperature(_) => 0,
+            MeteorologicalCategory::Moisture(_) => 1,
+            MeteorologicalCategory::Momentum(_) => 2,
+            MeteorologicalCategory::Mass(_) => 3,
+            MeteorologicalCategory::TraceGases(_) => 14,
+            MeteorologicalCategory::AtmosphericChemistry(_) => 20,
+        }
+    }
+
+    fn parameter(&self) -> &dyn Parameter {
+        match self {
+            MeteorologicalCategory::Temperature(parameter) => parameter,
+            MeteorologicalCategory::Moisture(parameter) => parameter,
+            MeteorologicalCategory::Momentum(parameter) => parameter,
+            MeteorologicalCategory::Mass(parameter) => parameter,
+            MeteorologicalCategory::TraceGases(parameter) => parameter,
+            MeteorologicalCategory::AtmosphericChemistry(parameter) => parameter,
         }
     }
 }
@@ -1,4 +1,227 @@
+use core::ops::RangeInclusive;
+
 use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+
+use crate::{
+    center_and_table_versions::CenterAndTableVersions, code_table::CodeValue,
+    originating_center::OriginatingCenter, semantics::Semantics, Parameter,
+};
+
+#[derive(FromPrimitive, Clone, Copy)]
+pub enum MoistureParameter {
+    SpecificHumidity = 0,
+    RelativeHumidity,
+    HumidityMixingRatio,
+    PrecipitableWater,
+    VapourPressure,
+    SaturationDeficit,
+    Evaporation,
+    PrecipitationRate,
+    TotalPrecipitation,
+    LargeScalePrecipitation,
+    ConvectivePrecipitation,
+    SnowDepth,
+    SnowfallRateWaterEquivalent,
+    WaterEquivalentOfAccumulatedSnowDepth,
+    ConvectiveSnow,
+    LargeScaleSnow,
+    SnowMelt,
+    // etc.
+
+    // NCEP local:
+    NcepCategoricalRain = 192,
+    NcepCategoricalFreezingRain,
+    NcepCategoricalIcePellets,
+    NcepCategoricalSnow,
+    NcepConvectivePrecipitationRate,
+    NcepRainFraction = 202,
+    NcepRimeFactor,
+    NcepTotalColumnIntegratedRain,
+    NcepTotalColumnIntegratedSnow,
+    // etc.
+
+    // Local to NCEP's sub-centre 5 (HPC). It's 0.1.192, like CRAIN, so it can't have 192 as its
+    // discriminant: see `parameter_num`.
+    HpcWeatherCode = 0x100 | 192,
+}
+
+impl Parameter for MoistureParameter {
+    fn from_parameter_num(
+        parameter_num: u8,
+        center_and_table_versions: &CenterAndTableVersions,
+    ) -> CodeValue<Self>
+    where
+        Self: Sized,
+    {
+        CodeValue::new(parameter_num, MoistureParameter::from_u8, |parameter_num| {
+            match center_and_table_versions.originating_center() {
+                center @ OriginatingCenter::Ncep { .. } => {
+                    MoistureParameter::from_u8(parameter_num)
+                        .into_iter()
+                        .chain((parameter_num == 192).then_some(MoistureParameter::HpcWeatherCode))
+                        .find(|parameter| center.has_local_parameter(parameter.abbrev()))
+                }
+                OriginatingCenter::Other { .. } => None,
+            }
+        })
+    }
+
+    fn parameter_num(&self) -> u8 {
+        match *self {
+            MoistureParameter::HpcWeatherCode => 192,
+            parameter => parameter as u8,
+        }
+    }
+
+    fn abbrev(&self) -> &'static str {
+        match *self {
+            MoistureParameter::SpecificHumidity => "SPFH",
+            MoistureParameter::RelativeHumidity => "RH",
+            MoistureParameter::HumidityMixingRatio => "MIXR",
+            MoistureParameter::PrecipitableWater => "PWAT",
+            MoistureParameter::VapourPressure => "VAPP",
+            MoistureParameter::SaturationDeficit => "SATD",
+            MoistureParameter::Evaporation => "EVP",
+            MoistureParameter::PrecipitationRate => "PRATE",
+            MoistureParameter::TotalPrecipitation => "APCP",
+            MoistureParameter::LargeScalePrecipitation => "NCPCP",
+            MoistureParameter::ConvectivePrecipitation => "ACPCP",
+            MoistureParameter::SnowDepth => "SNOD",
+            MoistureParameter::SnowfallRateWaterEquivalent => "SRWEQ",
+            MoistureParameter::WaterEquivalentOfAccumulatedSnowDepth => "WEASD",
+            MoistureParameter::ConvectiveSnow => "SNOC",
+            MoistureParameter::LargeScaleSnow => "SNOL",
+            MoistureParameter::SnowMelt => "SNOM",
+
+            // Local to NCEP:
+            MoistureParameter::NcepCategoricalRain => "CRAIN",
+            MoistureParameter::NcepCategoricalFreezingRain => "CFRZR",
+            MoistureParameter::NcepCategoricalIcePellets => "CICEP",
+            MoistureParameter::NcepCategoricalSnow => "CSNOW",
+            MoistureParameter::NcepConvectivePrecipitationRate => "CPRAT",
+            MoistureParameter::NcepRainFraction => "FRAIN",
+            MoistureParameter::NcepRimeFactor => "RIME",
+            MoistureParameter::NcepTotalColumnIntegratedRain => "TCOLR",
+            MoistureParameter::NcepTotalColumnIntegratedSnow => "TCOLS",
+
+            // Local to HPC:
+            MoistureParameter::HpcWeatherCode => "HPC-Wx",
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match *self {
+            MoistureParameter::SpecificHumidity => "Specific humidity",
+            MoistureParameter::RelativeHumidity => "Relative humidity",
+            MoistureParameter::HumidityMixingRatio => "Humidity mixing ratio",
+            MoistureParameter::PrecipitableWater => "Precipitable water",
+            MoistureParameter::VapourPressure => "Vapour pressure",
+            MoistureParameter::SaturationDeficit => "Saturation deficit",
+            MoistureParameter::Evaporation => "Evaporation",
+            MoistureParameter::PrecipitationRate => "Precipitation rate",
+            MoistureParameter::TotalPrecipitation => "Total precipitation",
+            MoistureParameter::LargeScalePrecipitation => {
+                "Large-scale precipitation (non-convective)"
+            }
+            MoistureParameter::ConvectivePrecipitation => "Convective precipitation",
+            MoistureParameter::SnowDepth => "Snow depth",
+            MoistureParameter::SnowfallRateWaterEquivalent => "Snowfall rate water equivalent",
+            MoistureParameter::WaterEquivalentOfAccumulatedSnowDepth => {
+                "Water equivalent of accumulated snow depth"
+            }
+            MoistureParameter::ConvectiveSnow => "Convective snow",
+            MoistureParameter::LargeScaleSnow => "Large-scale snow",
+            MoistureParameter::SnowMelt => "Snow melt",
+            MoistureParameter::NcepCategoricalRain => "Categorical rain",
+            MoistureParameter::NcepCategoricalFreezingRain => "Categorical freezing rain",
+            MoistureParameter::NcepCategoricalIcePellets => "Categorical ice pellets",
+            MoistureParameter::NcepCategoricalSnow => "Categorical snow",
+            MoistureParameter::NcepConvectivePrecipitationRate => "Convective precipitation rate",
+            MoistureParameter::NcepRainFraction => "Rain fraction of total cloud water",
+            MoistureParameter::NcepRimeFactor => "Rime factor",
+            MoistureParameter::NcepTotalColumnIntegratedRain => "Total column integrated rain",
+            MoistureParameter::NcepTotalColumnIntegratedSnow => "Total column integrated snow",
+            MoistureParameter::HpcWeatherCode => "HPC Code",
+        }
+    }
+
+    fn unit(&self) -> &'static str {
+        match *self {
+            MoistureParameter::SpecificHumidity | MoistureParameter::HumidityMixingRatio => {
+                "kg kg-1"
+            }
+            MoistureParameter::RelativeHumidity => "%",
+            MoistureParameter::VapourPressure | MoistureParameter::SaturationDeficit => "Pa",
+            MoistureParameter::PrecipitationRate
+            | MoistureParameter::SnowfallRateWaterEquivalent
+            | MoistureParameter::NcepConvectivePrecipitationRate => "kg m-2 s-1",
+            MoistureParameter::SnowDepth => "m",
+            MoistureParameter::NcepCategoricalRain
+            | MoistureParameter::NcepCategoricalFreezingRain
+            | MoistureParameter::NcepCategoricalIcePellets
+            | MoistureParameter::NcepCategoricalSnow => "Code table 4.222",
+            MoistureParameter::NcepRainFraction => "Proportion",
+            MoistureParameter::NcepRimeFactor => "Numeric",
+            MoistureParameter::HpcWeatherCode => "-",
+            MoistureParameter::PrecipitableWater
+            | MoistureParameter::Evaporation
+            | MoistureParameter::TotalPrecipitation
+            | MoistureParameter::LargeScalePrecipitation
+            | MoistureParameter::ConvectivePrecipitation
+            | MoistureParameter::WaterEquivalentOfAccumulatedSnowDepth
+            | MoistureParameter::ConvectiveSnow
+            | MoistureParameter::LargeScaleSnow
+            | MoistureParameter::SnowMelt
+            | MoistureParameter::NcepTotalColumnIntegratedRain
+            | MoistureParameter::NcepTotalColumnIntegratedSnow => "kg m-2",
+        }
+    }
+
+    fn semantics(&self) -> Semantics {
+        match *self {
+            MoistureParameter::Evaporation
+            | MoistureParameter::TotalPrecipitation
+            | MoistureParameter::LargeScalePrecipitation
+            | MoistureParameter::ConvectivePrecipitation
+            | MoistureParameter::ConvectiveSnow
+            | MoistureParameter::LargeScaleSnow
+            | MoistureParameter::SnowMelt => Semantics::AccumulatedOverWindow,
+            _ => Semantics::Instantaneous,
+        }
+    }
 
-#[derive(FromPrimitive)]
-pub(crate) enum MoistureParameter {}
+    fn plausible_range(&self) -> Option<RangeInclusive<f64>> {
+        match *self {
+            MoistureParameter::SpecificHumidity | MoistureParameter::HumidityMixingRatio => {
+                Some(0.0..=0.05)
+            }
+            // Models allow a little supersaturation.
+            MoistureParameter::RelativeHumidity => Some(0.0..=105.0),
+            MoistureParameter::PrecipitableWater
+            | MoistureParameter::NcepTotalColumnIntegratedRain
+            | MoistureParameter::NcepTotalColumnIntegratedSnow => Some(0.0..=150.0),
+            MoistureParameter::VapourPressure | MoistureParameter::SaturationDeficit => {
+                Some(0.0..=10_000.0)
+            }
+            // 0.1 kg m-2 s-1 is 360 mm per hour.
+            MoistureParameter::PrecipitationRate
+            | MoistureParameter::SnowfallRateWaterEquivalent
+            | MoistureParameter::NcepConvectivePrecipitationRate => Some(0.0..=0.1),
+            MoistureParameter::TotalPrecipitation
+            | MoistureParameter::LargeScalePrecipitation
+            | MoistureParameter::ConvectivePrecipitation
+            | MoistureParameter::ConvectiveSnow
+            | MoistureParameter::LargeScaleSnow
+            | MoistureParameter::SnowMelt => Some(0.0..=2000.0),
+            MoistureParameter::SnowDepth => Some(0.0..=50.0),
+            MoistureParameter::WaterEquivalentOfAccumulatedSnowDepth => Some(0.0..=50_000.0),
+            MoistureParameter::NcepCategoricalRain
+            | MoistureParameter::NcepCategoricalFreezingRain
+            | MoistureParameter::NcepCategoricalIcePellets
+            | MoistureParameter::NcepCategoricalSnow
+            | MoistureParameter::NcepRainFraction => Some(0.0..=1.0),
+            _ => None,
+        }
+    }
+}
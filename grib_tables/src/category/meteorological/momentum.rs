@@ -0,0 +1,153 @@
+use core::ops::RangeInclusive;
+
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+
+use crate::{
+    center_and_table_versions::CenterAndTableVersions, code_table::CodeValue,
+    originating_center::OriginatingCenter, semantics::Semantics, Parameter,
+};
+
+#[derive(FromPrimitive, Clone, Copy)]
+pub enum MomentumParameter {
+    WindDirection = 0,
+    WindSpeed,
+    UComponentOfWind,
+    VComponentOfWind,
+    StreamFunction,
+    VelocityPotential,
+    MontgomeryStreamFunction,
+    SigmaCoordinateVerticalVelocity,
+    VerticalVelocityPressure,
+    VerticalVelocityGeometric,
+    AbsoluteVorticity,
+    AbsoluteDivergence,
+    RelativeVorticity,
+    RelativeDivergence,
+    PotentialVorticity,
+    // etc.
+
+    // NCEP local:
+    NcepVerticalSpeedShear = 192,
+    NcepHorizontalMomentumFlux,
+    NcepUComponentStormMotion,
+    NcepVComponentStormMotion,
+    // etc.
+}
+
+impl Parameter for MomentumParameter {
+    fn from_parameter_num(
+        parameter_num: u8,
+        center_and_table_versions: &CenterAndTableVersions,
+    ) -> CodeValue<Self>
+    where
+        Self: Sized,
+    {
+        CodeValue::new(parameter_num, MomentumParameter::from_u8, |parameter_num| {
+            match center_and_table_versions.originating_center() {
+                center @ OriginatingCenter::Ncep { .. } => {
+                    MomentumParameter::from_u8(parameter_num)
+                        .filter(|parameter| center.has_local_parameter(parameter.abbrev()))
+                }
+                OriginatingCenter::Other { .. } => None,
+            }
+        })
+    }
+
+    fn parameter_num(&self) -> u8 {
+        *self as u8
+    }
+
+    fn abbrev(&self) -> &'static str {
+        match *self {
+            MomentumParameter::WindDirection => "WDIR",
+            MomentumParameter::WindSpeed => "WIND",
+            MomentumParameter::UComponentOfWind => "UGRD",
+            MomentumParameter::VComponentOfWind => "VGRD",
+            MomentumParameter::StreamFunction => "STRM",
+            MomentumParameter::VelocityPotential => "VPOT",
+            MomentumParameter::MontgomeryStreamFunction => "MNTSF",
+            MomentumParameter::SigmaCoordinateVerticalVelocity => "SGCVV",
+            MomentumParameter::VerticalVelocityPressure => "VVEL",
+            MomentumParameter::VerticalVelocityGeometric => "DZDT",
+            MomentumParameter::AbsoluteVorticity => "ABSV",
+            MomentumParameter::AbsoluteDivergence => "ABSD",
+            MomentumParameter::RelativeVorticity => "RELV",
+            MomentumParameter::RelativeDivergence => "RELD",
+            MomentumParameter::PotentialVorticity => "PVORT",
+
+            // Local to NCEP:
+            MomentumParameter::NcepVerticalSpeedShear => "VWSH",
+            MomentumParameter::NcepHorizontalMomentumFlux => "MFLX",
+            MomentumParameter::NcepUComponentStormMotion => "USTM",
+            MomentumParameter::NcepVComponentStormMotion => "VSTM",
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match *self {
+            MomentumParameter::WindDirection => "Wind direction (from which blowing)",
+            MomentumParameter::WindSpeed => "Wind speed",
+            MomentumParameter::UComponentOfWind => "U-component of wind",
+            MomentumParameter::VComponentOfWind => "V-component of wind",
+            MomentumParameter::StreamFunction => "Stream function",
+            MomentumParameter::VelocityPotential => "Velocity potential",
+            MomentumParameter::MontgomeryStreamFunction => "Montgomery stream function",
+            MomentumParameter::SigmaCoordinateVerticalVelocity => {
+                "Sigma coordinate vertical velocity"
+            }
+            MomentumParameter::VerticalVelocityPressure => "Vertical velocity (pressure)",
+            MomentumParameter::VerticalVelocityGeometric => "Vertical velocity (geometric)",
+            MomentumParameter::AbsoluteVorticity => "Absolute vorticity",
+            MomentumParameter::AbsoluteDivergence => "Absolute divergence",
+            MomentumParameter::RelativeVorticity => "Relative vorticity",
+            MomentumParameter::RelativeDivergence => "Relative divergence",
+            MomentumParameter::PotentialVorticity => "Potential vorticity",
+            MomentumParameter::NcepVerticalSpeedShear => "Vertical speed shear",
+            MomentumParameter::NcepHorizontalMomentumFlux => "Horizontal momentum flux",
+            MomentumParameter::NcepUComponentStormMotion => "U-component storm motion",
+            MomentumParameter::NcepVComponentStormMotion => "V-component storm motion",
+        }
+    }
+
+    fn unit(&self) -> &'static str {
+        match *self {
+            MomentumParameter::WindDirection => "degree true",
+            MomentumParameter::WindSpeed
+            | MomentumParameter::UComponentOfWind
+            | MomentumParameter::VComponentOfWind
+            | MomentumParameter::VerticalVelocityGeometric
+            | MomentumParameter::NcepUComponentStormMotion
+            | MomentumParameter::NcepVComponentStormMotion => "m s-1",
+            MomentumParameter::StreamFunction | MomentumParameter::VelocityPotential => "m2 s-1",
+            MomentumParameter::MontgomeryStreamFunction => "m2 s-2",
+            MomentumParameter::VerticalVelocityPressure => "Pa s-1",
+            MomentumParameter::SigmaCoordinateVerticalVelocity
+            | MomentumParameter::AbsoluteVorticity
+            | MomentumParameter::AbsoluteDivergence
+            | MomentumParameter::RelativeVorticity
+            | MomentumParameter::RelativeDivergence
+            | MomentumParameter::NcepVerticalSpeedShear => "s-1",
+            MomentumParameter::PotentialVorticity => "K m2 kg-1 s-1",
+            MomentumParameter::NcepHorizontalMomentumFlux => "N m-2",
+        }
+    }
+
+    fn semantics(&self) -> Semantics {
+        Semantics::Instantaneous
+    }
+
+    fn plausible_range(&self) -> Option<RangeInclusive<f64>> {
+        match *self {
+            MomentumParameter::WindDirection => Some(0.0..=360.0),
+            MomentumParameter::WindSpeed => Some(0.0..=150.0),
+            MomentumParameter::UComponentOfWind
+            | MomentumParameter::VComponentOfWind
+            | MomentumParameter::NcepUComponentStormMotion
+            | MomentumParameter::NcepVComponentStormMotion => Some(-150.0..=150.0),
+            MomentumParameter::VerticalVelocityPressure
+            | MomentumParameter::VerticalVelocityGeometric => Some(-100.0..=100.0),
+            _ => None,
+        }
+    }
+}
@@ -1,13 +1,15 @@
+use core::ops::RangeInclusive;
+
 use num_derive::FromPrimitive;
 use num_traits::FromPrimitive;
 
 use crate::{
-    center_and_table_versions::CenterAndTableVersions, originating_center::OriginatingCenter,
-    Parameter,
+    center_and_table_versions::CenterAndTableVersions, code_table::CodeValue,
+    originating_center::OriginatingCenter, semantics::Semantics, Parameter,
 };
 
-#[derive(FromPrimitive)]
-pub(crate) enum TemperatureParameter {
+#[derive(FromPrimitive, Clone, Copy)]
+pub enum TemperatureParameter {
     Temperature = 0,
     VirtualTemperature,
     PotentialTemperature,
@@ -17,10 +19,13 @@ pub(crate) enum TemperatureParameter {
     DewPointTemperature,
     DewPointDepression,
     LapseRate,
+    TemperatureAnomaly,
+    LatentHeatNetFlux,
+    SensibleHeatNetFlux,
     // etc.
 
     // NCEP local:
-    NcepSnowPhaseChangeHeatFlux,
+    NcepSnowPhaseChangeHeatFlux = 192,
     NcepTemperatureTendencyByAllRadiation,
     // etc.
 }
@@ -28,25 +33,28 @@ pub(crate) enum TemperatureParameter {
 impl Parameter for TemperatureParameter {
     fn from_parameter_num(
         parameter_num: u8,
-        center_and_table_versions: CenterAndTableVersions,
-    ) -> Option<Self>
+        center_and_table_versions: &CenterAndTableVersions,
+    ) -> CodeValue<Self>
     where
         Self: Sized,
     {
-        if parameter_num < 192 {
-            TemperatureParameter::from_u8(parameter_num)
-        } else {
-            // Parameter numbers >= 194 are reserved for local use:
-            match center_and_table_versions.originating_center() {
-                OriginatingCenter::NCEP {
-                    local_table_version: _,
-                } => match parameter_num {
+        CodeValue::new(
+            parameter_num,
+            TemperatureParameter::from_u8,
+            |parameter_num| match center_and_table_versions.originating_center() {
+                center @ OriginatingCenter::Ncep { .. } => match parameter_num {
                     192 => Some(TemperatureParameter::NcepSnowPhaseChangeHeatFlux),
                     193 => Some(TemperatureParameter::NcepTemperatureTendencyByAllRadiation),
-                    _ => todo!(),
-                },
-            }
-        }
+                    _ => None,
+                }
+                .filter(|parameter| center.has_local_parameter(parameter.abbrev())),
+                OriginatingCenter::Other { .. } => None,
+            },
+        )
+    }
+
+    fn parameter_num(&self) -> u8 {
+        *self as u8
     }
 
     fn abbrev(&self) -> &'static str {
@@ -62,6 +70,9 @@ impl Parameter for TemperatureParameter {
             TemperatureParameter::DewPointTemperature => "DPT",
             TemperatureParameter::DewPointDepression => "DEPR",
             TemperatureParameter::LapseRate => "LAPR",
+            TemperatureParameter::TemperatureAnomaly => "TMPA",
+            TemperatureParameter::LatentHeatNetFlux => "LHTFL",
+            TemperatureParameter::SensibleHeatNetFlux => "SHTFL",
             // etc.
 
             // Local to NCEP:
@@ -74,11 +85,61 @@ impl Parameter for TemperatureParameter {
         match *self {
             TemperatureParameter::Temperature => "Temperature",
             TemperatureParameter::VirtualTemperature => "Virtual temperature",
-            _ => todo!(), // etc...
+            TemperatureParameter::PotentialTemperature => "Potential temperature",
+            TemperatureParameter::PseudoAdiabaticPotentialTemperature => {
+                "Pseudo-adiabatic potential temperature"
+            }
+            TemperatureParameter::MaximumTemperature => "Maximum temperature",
+            TemperatureParameter::MinimumTemperature => "Minimum temperature",
+            TemperatureParameter::DewPointTemperature => "Dew point temperature",
+            TemperatureParameter::DewPointDepression => "Dew point depression",
+            TemperatureParameter::LapseRate => "Lapse rate",
+            TemperatureParameter::TemperatureAnomaly => "Temperature anomaly",
+            TemperatureParameter::LatentHeatNetFlux => "Latent heat net flux",
+            TemperatureParameter::SensibleHeatNetFlux => "Sensible heat net flux",
+            TemperatureParameter::NcepSnowPhaseChangeHeatFlux => "Snow phase change heat flux",
+            TemperatureParameter::NcepTemperatureTendencyByAllRadiation => {
+                "Temperature tendency by all radiation"
+            }
         }
     }
 
     fn unit(&self) -> &'static str {
-        todo!();
+        match *self {
+            TemperatureParameter::LapseRate => "K m-1",
+            TemperatureParameter::LatentHeatNetFlux
+            | TemperatureParameter::SensibleHeatNetFlux
+            | TemperatureParameter::NcepSnowPhaseChangeHeatFlux => "W m-2",
+            TemperatureParameter::NcepTemperatureTendencyByAllRadiation => "K s-1",
+            _ => "K",
+        }
+    }
+
+    fn semantics(&self) -> Semantics {
+        match *self {
+            TemperatureParameter::MaximumTemperature | TemperatureParameter::MinimumTemperature => {
+                Semantics::Extremum
+            }
+            _ => Semantics::Instantaneous,
+        }
+    }
+
+    fn plausible_range(&self) -> Option<RangeInclusive<f64>> {
+        match *self {
+            TemperatureParameter::Temperature
+            | TemperatureParameter::VirtualTemperature
+            | TemperatureParameter::MaximumTemperature
+            | TemperatureParameter::MinimumTemperature
+            | TemperatureParameter::DewPointTemperature => Some(150.0..=350.0),
+            // Potential temperatures get very large in the stratosphere.
+            TemperatureParameter::PotentialTemperature
+            | TemperatureParameter::PseudoAdiabaticPotentialTemperature => Some(150.0..=2000.0),
+            TemperatureParameter::DewPointDepression => Some(0.0..=100.0),
+            TemperatureParameter::TemperatureAnomaly => Some(-100.0..=100.0),
+            TemperatureParameter::LatentHeatNetFlux | TemperatureParameter::SensibleHeatNetFlux => {
+                Some(-2000.0..=2000.0)
+            }
+            _ => None,
+        }
     }
 }
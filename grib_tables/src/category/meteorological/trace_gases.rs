@@ -0,0 +1,98 @@
+use core::ops::RangeInclusive;
+
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+
+use crate::{
+    center_and_table_versions::CenterAndTableVersions, code_table::CodeValue,
+    originating_center::OriginatingCenter, semantics::Semantics, Parameter,
+};
+
+#[derive(FromPrimitive, Clone, Copy)]
+pub enum TraceGasesParameter {
+    TotalOzone = 0,
+    OzoneMixingRatio,
+    TotalColumnIntegratedOzone,
+    // etc.
+
+    // NCEP local:
+    NcepOzoneConcentration = 193,
+    NcepCategoricalOzoneConcentration,
+    // etc.
+}
+
+impl Parameter for TraceGasesParameter {
+    fn from_parameter_num(
+        parameter_num: u8,
+        center_and_table_versions: &CenterAndTableVersions,
+    ) -> CodeValue<Self>
+    where
+        Self: Sized,
+    {
+        CodeValue::new(
+            parameter_num,
+            TraceGasesParameter::from_u8,
+            |parameter_num| match center_and_table_versions.originating_center() {
+                center @ OriginatingCenter::Ncep { .. } => {
+                    TraceGasesParameter::from_u8(parameter_num)
+                        .filter(|parameter| center.has_local_parameter(parameter.abbrev()))
+                }
+                OriginatingCenter::Other { .. } => None,
+            },
+        )
+    }
+
+    fn parameter_num(&self) -> u8 {
+        *self as u8
+    }
+
+    fn abbrev(&self) -> &'static str {
+        match *self {
+            TraceGasesParameter::TotalOzone => "TOZNE",
+            TraceGasesParameter::OzoneMixingRatio => "O3MR",
+            TraceGasesParameter::TotalColumnIntegratedOzone => "TCIOZ",
+
+            // Local to NCEP:
+            TraceGasesParameter::NcepOzoneConcentration => "OZCON",
+            TraceGasesParameter::NcepCategoricalOzoneConcentration => "OZCAT",
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match *self {
+            TraceGasesParameter::TotalOzone => "Total ozone",
+            TraceGasesParameter::OzoneMixingRatio => "Ozone mixing ratio",
+            TraceGasesParameter::TotalColumnIntegratedOzone => "Total column integrated ozone",
+            TraceGasesParameter::NcepOzoneConcentration => "Ozone concentration",
+            TraceGasesParameter::NcepCategoricalOzoneConcentration => {
+                "Categorical ozone concentration"
+            }
+        }
+    }
+
+    fn unit(&self) -> &'static str {
+        match *self {
+            TraceGasesParameter::TotalOzone | TraceGasesParameter::TotalColumnIntegratedOzone => {
+                "DU"
+            }
+            TraceGasesParameter::OzoneMixingRatio => "kg kg-1",
+            TraceGasesParameter::NcepOzoneConcentration => "ppb",
+            TraceGasesParameter::NcepCategoricalOzoneConcentration => "Non-Dim",
+        }
+    }
+
+    fn semantics(&self) -> Semantics {
+        Semantics::Instantaneous
+    }
+
+    fn plausible_range(&self) -> Option<RangeInclusive<f64>> {
+        match *self {
+            TraceGasesParameter::TotalOzone | TraceGasesParameter::TotalColumnIntegratedOzone => {
+                Some(0.0..=1000.0)
+            }
+            TraceGasesParameter::OzoneMixingRatio => Some(0.0..=0.0001),
+            TraceGasesParameter::NcepOzoneConcentration => Some(0.0..=1000.0),
+            TraceGasesParameter::NcepCategoricalOzoneConcentration => None,
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use crate::center_and_table_versions::CenterAndTableVersions;
+use crate::{center_and_table_versions::CenterAndTableVersions, code_table::CodeValue, Parameter};
 
 pub(crate) mod hydrological;
 pub(crate) mod meteorological;
@@ -8,7 +8,11 @@ pub(crate) trait Category {
         category_num: u8,
         parameter_num: u8,
         center_and_table_versions: &CenterAndTableVersions,
-    ) -> Option<Self>
+    ) -> CodeValue<Self>
     where
         Self: Sized;
+
+    /// The inverse of `from_category_and_parameter_numbers`.
+    fn category_num(&self) -> u8;
+    fn parameter(&self) -> &dyn Parameter;
 }
@@ -1,9 +1,13 @@
+use core::borrow::Borrow;
+
 use crate::{
     master_table::MasterTable, originating_center::OriginatingCenter, product::Product,
     AbbrevToProduct,
 };
 
-pub(crate) struct CenterAndTableVersions {
+/// Which center produced a message, and which versions of its local tables and of the WMO master
+/// tables it uses. Together, these say how to decode the message's code table values.
+pub struct CenterAndTableVersions {
     originating_center: OriginatingCenter,
     local_table_version: u8,
     master_table: MasterTable,
@@ -11,14 +15,137 @@ pub(crate) struct CenterAndTableVersions {
 
 impl AbbrevToProduct for CenterAndTableVersions {
     fn abbrev_to_product(&self, abbrev: &str) -> Option<&'static Product> {
-        self.master_table
+        let product = self
+            .master_table
             .abbrev_to_product(abbrev)
-            .or_else(|| self.originating_center.abbrev_to_product(abbrev))
+            .or_else(|| self.originating_center.abbrev_to_product(abbrev));
+
+        #[cfg(feature = "runtime-tables")]
+        let product = product.or_else(|| {
+            crate::local_tables::product_from_abbrev(
+                self.originating_center.center_num(),
+                self.local_table_version,
+                abbrev,
+            )
+        });
+
+        product
     }
 }
 
 impl CenterAndTableVersions {
+    /// The numbers are as found in section 1: `center_num` from common code table C-11 (e.g. 7
+    /// for NCEP), `subcenter` from the center's own table of sub-centres (0 for the center
+    /// itself), and `local_table_version` and `master_table_version` from code tables 1.1 and
+    /// 1.0. Some sub-centres have local tables of their own, e.g. 5 (HPC) at NCEP.
+    pub fn from_numbers(
+        center_num: u16,
+        subcenter: u16,
+        local_table_version: u8,
+        master_table_version: u8,
+    ) -> Self {
+        Self::new(
+            OriginatingCenter::from_center_num(center_num, subcenter, local_table_version),
+            local_table_version,
+            MasterTable::from_version_num(master_table_version),
+        )
+    }
+
+    /// Which versions and sub-centres the center's compiled-in local tables cover, oldest first
+    /// for each group of sub-centres. Empty for centers whose local tables we don't have.
+    pub fn local_table_versions(&self) -> impl Iterator<Item = LocalTableVersion> {
+        self.originating_center
+            .local_tables()
+            .iter()
+            .map(|table| table.version)
+    }
+
+    /// Every product in the compiled-in master and local tables, in no particular order. Products
+    /// from runtime tables aren't included.
+    pub fn products(&self) -> impl Iterator<Item = &'static Product> + '_ {
+        self.master_table
+            .products()
+            .chain(self.originating_center.products())
+    }
+
+    pub(crate) fn new(
+        originating_center: OriginatingCenter,
+        local_table_version: u8,
+        master_table: MasterTable,
+    ) -> Self {
+        Self {
+            originating_center,
+            local_table_version,
+            master_table,
+        }
+    }
+
     pub(crate) fn originating_center(&self) -> &OriginatingCenter {
         &self.originating_center
     }
+
+    /// The center's number in common code table C-11, e.g. 7 for NCEP.
+    pub fn center_num(&self) -> u16 {
+        self.originating_center.center_num()
+    }
+
+    pub fn local_table_version(&self) -> u8 {
+        self.local_table_version
+    }
+
+    /// The sub-centre whose own local tables are used, or 0 if the center's main local tables
+    /// are (including for sub-centres without tables of their own).
+    pub fn local_table_subcenter(&self) -> u16 {
+        self.originating_center.local_table_subcenter()
+    }
+
+    pub(crate) fn master_table(&self) -> &MasterTable {
+        &self.master_table
+    }
+}
+
+/// Which local table versions (code table 1.1) and sub-centres one of a center's local tables
+/// describes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LocalTableVersion {
+    /// The first version which the table describes. It also describes later versions, up to the
+    /// next table for the same sub-centres.
+    pub first_version: u8,
+
+    /// The sub-centres which use the table. Empty for the table used by every sub-centre which
+    /// doesn't have tables of its own.
+    pub subcenters: &'static [u16],
+}
+
+impl LocalTableVersion {
+    /// Choose the table for `subcenter` in `local_table_version` from one center's `tables`,
+    /// which must be oldest first for each group of sub-centres. If we don't have that version,
+    /// we use the nearest earlier version: centers add entries to their local tables far more
+    /// often than they change or remove them.
+    pub fn select<T: Borrow<LocalTableVersion>>(
+        tables: &[T],
+        subcenter: u16,
+        local_table_version: u8,
+    ) -> Option<&T> {
+        let version = |table: &T| -> LocalTableVersion { *table.borrow() };
+        let has_own_tables = tables
+            .iter()
+            .any(|table| version(table).subcenters.contains(&subcenter));
+        let mut tables = tables.iter().filter(|table| {
+            let subcenters = version(table).subcenters;
+            if has_own_tables {
+                subcenters.contains(&subcenter)
+            } else {
+                subcenters.is_empty()
+            }
+        });
+        match local_table_version {
+            // Strictly, 0 means "local tables not used". But NCEP's files and `ProductId` use it
+            // to mean "any version", so use the latest. 255 means the version is missing.
+            0 | u8::MAX => tables.next_back(),
+            local_table_version => tables
+                .take_while(|table| version(table).first_version <= local_table_version)
+                .last(),
+        }
+    }
 }
@@ -0,0 +1,106 @@
+use alloc::{borrow::Cow, format};
+
+/// A value read from a GRIB2 code table. `N` is the type of the octets which hold it: `u8` for
+/// most tables, or `u16` for two-octet tables like 4.230.
+///
+/// Most one-octet code tables reserve 192 to 254 for local use, and use 255 to mean "missing".
+/// Values below 192 which we don't recognise are reserved by WMO, which usually means the message
+/// uses a newer version of the table than this crate knows about.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CodeValue<T, N = u8> {
+    Known(T),
+
+    /// A value in WMO's part of the table which we don't recognise.
+    Reserved(N),
+
+    /// A value reserved for local use which isn't in any local table we know.
+    LocalUse(N),
+    Missing,
+}
+
+impl<T> CodeValue<T> {
+    /// Decode `num` with `wmo` if it's in WMO's part of the table, or with `local` if it's
+    /// reserved for local use.
+    pub(crate) fn new(
+        num: u8,
+        wmo: impl FnOnce(u8) -> Option<T>,
+        local: impl FnOnce(u8) -> Option<T>,
+    ) -> Self {
+        let (decoded, unknown) = match num {
+            ..192 => (wmo(num), CodeValue::Reserved(num)),
+            192..=254 => (local(num), CodeValue::LocalUse(num)),
+            255 => return CodeValue::Missing,
+        };
+        decoded.map_or(unknown, CodeValue::Known)
+    }
+
+    /// A value which isn't in any table we know.
+    pub(crate) fn unknown(num: u8) -> Self {
+        CodeValue::new(num, |_| None, |_| None)
+    }
+
+    /// `Ok` with the value if we know it, or `Err` with its number if we don't (255 for
+    /// `Missing`).
+    pub fn known_or_num(self) -> Result<T, u8> {
+        match self {
+            CodeValue::Known(value) => Ok(value),
+            CodeValue::Reserved(num) | CodeValue::LocalUse(num) => Err(num),
+            CodeValue::Missing => Err(u8::MAX),
+        }
+    }
+
+    /// The text which we write in `.idx` files: `known`'s text for values we know, or `key=N` for
+    /// values we don't, so that they can be read back.
+    pub(crate) fn idx_str(
+        self,
+        key: &str,
+        known: impl FnOnce(T) -> &'static str,
+    ) -> Cow<'static, str> {
+        match self.known_or_num() {
+            Ok(value) => Cow::Borrowed(known(value)),
+            Err(num) => Cow::Owned(format!("{key}={num}")),
+        }
+    }
+
+    /// The inverse of `idx_str`. `wmo` decodes the numbers of values in WMO's part of the table.
+    pub(crate) fn from_idx_str(
+        s: &str,
+        key: &str,
+        known: impl FnOnce(&str) -> Option<T>,
+        wmo: impl FnOnce(u8) -> Option<T>,
+    ) -> Option<Self> {
+        match s.strip_prefix(key).and_then(|num| num.strip_prefix('=')) {
+            Some(num) => Some(CodeValue::new(num.parse().ok()?, wmo, |_| None)),
+            None => known(s).map(CodeValue::Known),
+        }
+    }
+}
+
+impl<T> CodeValue<T, u16> {
+    /// Decode `num` from a two-octet table. These use 65535 to mean "missing", but each table sets
+    /// aside its own range for local use, so every other value we don't recognise is `Reserved`.
+    pub(crate) fn from_u16(num: u16, decode: impl FnOnce(u16) -> Option<T>) -> Self {
+        match num {
+            u16::MAX => CodeValue::Missing,
+            _ => decode(num).map_or(CodeValue::Reserved(num), CodeValue::Known),
+        }
+    }
+}
+
+impl<T, N> CodeValue<T, N> {
+    pub fn known(self) -> Option<T> {
+        match self {
+            CodeValue::Known(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> CodeValue<U, N> {
+        match self {
+            CodeValue::Known(value) => CodeValue::Known(f(value)),
+            CodeValue::Reserved(num) => CodeValue::Reserved(num),
+            CodeValue::LocalUse(num) => CodeValue::LocalUse(num),
+            CodeValue::Missing => CodeValue::Missing,
+        }
+    }
+}
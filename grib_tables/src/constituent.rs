@@ -0,0 +1,417 @@
+use std::fmt;
+
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+
+use crate::{
+    code_table::CodeValue, error::Error, idx::format_g, level::ScaledValue, message::Section,
+};
+
+/// The chemical or aerosol which a product from templates 4.40 to 4.48 describes. For example,
+/// "mass density" is meaningless without knowing whether it's the mass density of ozone or dust.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Constituent {
+    /// From product definition templates 4.40 to 4.43.
+    Chemical(CodeValue<ChemicalConstituent, u16>),
+
+    /// From product definition templates 4.44 to 4.48.
+    Aerosol(Aerosol),
+}
+
+/// Atmospheric chemical constituent type (code table 4.230).
+#[derive(FromPrimitive, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ChemicalConstituent {
+    Ozone = 0,
+    WaterVapour,
+    Methane,
+    CarbonDioxide,
+    CarbonMonoxide,
+    NitrogenDioxide,
+    NitrousOxide,
+    Formaldehyde,
+    SulphurDioxide,
+    Ammonia,
+    AmmoniumIon,
+    NitrogenMonoxide,
+    AtomicOxygen,
+    NitrateRadical,
+    HydroperoxylRadical,
+    DinitrogenPentoxide,
+    NitrousAcid,
+    NitricAcid,
+    PeroxynitricAcid,
+    HydrogenPeroxide,
+    MolecularHydrogen,
+    AtomicNitrogen,
+    SulphateIon,
+    Radon,
+    ElementalMercury,
+    DivalentMercury,
+    // etc.
+}
+
+/// Aerosol type (code table 4.233).
+#[derive(FromPrimitive, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AerosolType {
+    TotalAerosol = 62000,
+    DustDry,
+    WaterInAmbient,
+    AmmoniumDry,
+    NitrateDry,
+    NitricAcidTrihydrate,
+    SulphateDry,
+    MercuryDry,
+    SeaSaltDry,
+    BlackCarbonDry,
+    ParticulateOrganicMatterDry,
+    PrimaryParticulateOrganicMatterDry,
+    SecondaryParticulateOrganicMatterDry,
+    // etc.
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Aerosol {
+    pub aerosol_type: CodeValue<AerosolType, u16>,
+
+    /// The range of particle diameters, in m. e.g. "< 2.5e-6" for PM2.5.
+    pub size: Option<Interval>,
+
+    /// The range of wavelengths, in m, for optical properties (template 4.48).
+    pub wavelength: Option<Interval>,
+}
+
+/// A range of sizes or wavelengths (code table 4.91).
+///
+/// Code table 4.91 has several ways to say the same thing (e.g. "smaller than first limit" and
+/// "smaller than second limit"), so we normalise on construction to always use the first limit
+/// where possible. Types which aren't in the table keep both limits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Interval {
+    pub interval_type: CodeValue<IntervalType>,
+    pub first_limit: Option<ScaledValue>,
+    pub second_limit: Option<ScaledValue>,
+}
+
+/// Type of interval (code table 4.91).
+#[derive(FromPrimitive, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum IntervalType {
+    SmallerThanFirstLimit = 0,
+    GreaterThanSecondLimit,
+    BetweenLimitsExcludingSecond,
+    GreaterThanFirstLimit,
+    SmallerThanSecondLimit,
+    SmallerThanOrEqualToFirstLimit,
+    GreaterThanOrEqualToSecondLimit,
+    BetweenLimitsIncludingBoth,
+    GreaterThanOrEqualToFirstLimit,
+    SmallerThanOrEqualToSecondLimit,
+    BetweenLimitsExcludingFirst,
+    EqualToFirstLimit,
+}
+
+impl Constituent {
+    /// Read the constituent type from templates 4.40 to 4.48. Returns `None` for other templates.
+    pub(crate) fn parse(section: &Section, template_num: u16) -> Result<Option<Self>, Error> {
+        let constituent = match template_num {
+            40..=43 => Constituent::Chemical(CodeValue::from_u16(
+                section.u16(12)?,
+                ChemicalConstituent::from_u16,
+            )),
+            44..=48 => Constituent::Aerosol(Aerosol {
+                aerosol_type: CodeValue::from_u16(section.u16(12)?, AerosolType::from_u16),
+                size: Interval::parse(section, 14)?,
+                wavelength: match template_num {
+                    48 => Interval::parse(section, 25)?,
+                    _ => None,
+                },
+            }),
+            _ => return Ok(None),
+        };
+        Ok(Some(constituent))
+    }
+
+    /// The name of the chemical or aerosol type, e.g. "Ozone". Types which we don't know are
+    /// named by their number in code table 4.230 or 4.233.
+    pub(crate) fn name(&self) -> String {
+        fn name<T>(value: CodeValue<T, u16>, name: impl FnOnce(T) -> &'static str) -> String {
+            match value {
+                CodeValue::Known(value) => name(value).to_string(),
+                CodeValue::Reserved(num) | CodeValue::LocalUse(num) => num.to_string(),
+                CodeValue::Missing => u16::MAX.to_string(),
+            }
+        }
+        match self {
+            Constituent::Chemical(chemical) => name(*chemical, |chemical| chemical.name()),
+            Constituent::Aerosol(aerosol) => {
+                name(aerosol.aerosol_type, |aerosol_type| aerosol_type.name())
+            }
+        }
+    }
+
+    /// The fields which we print after the step in `.idx` files, e.g.
+    /// `["aerosol=Total Aerosol", "aerosol_size <2.5e-06"]`.
+    pub(crate) fn to_idx_fields(self) -> Vec<String> {
+        match self {
+            Constituent::Chemical(_) => vec![format!("chemical={}", self.name())],
+            Constituent::Aerosol(aerosol) => {
+                let mut fields = vec![format!("aerosol={}", self.name())];
+                if let Some(size) = aerosol.size {
+                    fields.push(format!("aerosol_size {size}"));
+                }
+                if let Some(wavelength) = aerosol.wavelength {
+                    fields.push(format!("aerosol_wavelength {wavelength}"));
+                }
+                fields
+            }
+        }
+    }
+
+    /// The inverse of `to_idx_fields`. `fields` are all the fields after the step. Returns
+    /// `Ok(None)` if there's no constituent, and an error if we don't recognise the constituent.
+    pub(crate) fn from_idx_fields(fields: &[String]) -> Result<Option<Self>, &'static str> {
+        // The inverse of `name`.
+        fn from_name<T>(
+            name: &str,
+            from_name: impl FnOnce(&str) -> Option<T>,
+            from_u16: impl FnOnce(u16) -> Option<T>,
+        ) -> Option<CodeValue<T, u16>> {
+            match from_name(name) {
+                Some(value) => Some(CodeValue::Known(value)),
+                None => Some(CodeValue::from_u16(name.parse().ok()?, from_u16)),
+            }
+        }
+        let field_value = |prefix: &str| fields.iter().find_map(|field| field.strip_prefix(prefix));
+        if let Some(name) = field_value("chemical=") {
+            return from_name(
+                name,
+                ChemicalConstituent::from_name,
+                ChemicalConstituent::from_u16,
+            )
+            .map(|chemical| Some(Constituent::Chemical(chemical)))
+            .ok_or("unknown atmospheric chemical constituent type");
+        }
+        let Some(name) = field_value("aerosol=") else {
+            return Ok(None);
+        };
+        let interval = |prefix| {
+            field_value(prefix)
+                .map(|interval| Interval::from_idx_str(interval).ok_or("unknown interval"))
+                .transpose()
+        };
+        Ok(Some(Constituent::Aerosol(Aerosol {
+            aerosol_type: from_name(name, AerosolType::from_name, AerosolType::from_u16)
+                .ok_or("unknown aerosol type")?,
+            size: interval("aerosol_size ")?,
+            wavelength: interval("aerosol_wavelength ")?,
+        })))
+    }
+}
+
+impl ChemicalConstituent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ChemicalConstituent::Ozone => "Ozone",
+            ChemicalConstituent::WaterVapour => "Water Vapour",
+            ChemicalConstituent::Methane => "Methane",
+            ChemicalConstituent::CarbonDioxide => "Carbon Dioxide",
+            ChemicalConstituent::CarbonMonoxide => "Carbon Monoxide",
+            ChemicalConstituent::NitrogenDioxide => "Nitrogen Dioxide",
+            ChemicalConstituent::NitrousOxide => "Nitrous Oxide",
+            ChemicalConstituent::Formaldehyde => "Formaldehyde",
+            ChemicalConstituent::SulphurDioxide => "Sulphur Dioxide",
+            ChemicalConstituent::Ammonia => "Ammonia",
+            ChemicalConstituent::AmmoniumIon => "Ammonium Ion",
+            ChemicalConstituent::NitrogenMonoxide => "Nitrogen Monoxide",
+            ChemicalConstituent::AtomicOxygen => "Atomic Oxygen",
+            ChemicalConstituent::NitrateRadical => "Nitrate Radical",
+            ChemicalConstituent::HydroperoxylRadical => "Hydroperoxyl Radical",
+            ChemicalConstituent::DinitrogenPentoxide => "Dinitrogen Pentoxide",
+            ChemicalConstituent::NitrousAcid => "Nitrous Acid",
+            ChemicalConstituent::NitricAcid => "Nitric Acid",
+            ChemicalConstituent::PeroxynitricAcid => "Peroxynitric Acid",
+            ChemicalConstituent::HydrogenPeroxide => "Hydrogen Peroxide",
+            ChemicalConstituent::MolecularHydrogen => "Molecular Hydrogen",
+            ChemicalConstituent::AtomicNitrogen => "Atomic Nitrogen",
+            ChemicalConstituent::SulphateIon => "Sulphate Ion",
+            ChemicalConstituent::Radon => "Radon",
+            ChemicalConstituent::ElementalMercury => "Elemental Mercury",
+            ChemicalConstituent::DivalentMercury => "Divalent Mercury",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        (0..=u16::from(u8::MAX))
+            .filter_map(ChemicalConstituent::from_u16)
+            .find(|chemical| chemical.name() == name)
+    }
+}
+
+impl AerosolType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AerosolType::TotalAerosol => "Total Aerosol",
+            AerosolType::DustDry => "Dust Dry",
+            AerosolType::WaterInAmbient => "Water In Ambient",
+            AerosolType::AmmoniumDry => "Ammonium Dry",
+            AerosolType::NitrateDry => "Nitrate Dry",
+            AerosolType::NitricAcidTrihydrate => "Nitric Acid Trihydrate",
+            AerosolType::SulphateDry => "Sulphate Dry",
+            AerosolType::MercuryDry => "Mercury Dry",
+            AerosolType::SeaSaltDry => "Sea Salt Dry",
+            AerosolType::BlackCarbonDry => "Black Carbon Dry",
+            AerosolType::ParticulateOrganicMatterDry => "Particulate Organic Matter Dry",
+            AerosolType::PrimaryParticulateOrganicMatterDry => {
+                "Primary Particulate Organic Matter Dry"
+            }
+            AerosolType::SecondaryParticulateOrganicMatterDry => {
+                "Secondary Particulate Organic Matter Dry"
+            }
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        (62000..=62255)
+            .filter_map(AerosolType::from_u16)
+            .find(|aerosol_type| aerosol_type.name() == name)
+    }
+}
+
+impl Interval {
+    pub fn new(
+        interval_type: IntervalType,
+        first_limit: Option<ScaledValue>,
+        second_limit: Option<ScaledValue>,
+    ) -> Self {
+        use IntervalType::*;
+        let (interval_type, first_limit, second_limit) = match interval_type {
+            GreaterThanSecondLimit => (GreaterThanFirstLimit, second_limit, None),
+            SmallerThanSecondLimit => (SmallerThanFirstLimit, second_limit, None),
+            GreaterThanOrEqualToSecondLimit => (GreaterThanOrEqualToFirstLimit, second_limit, None),
+            SmallerThanOrEqualToSecondLimit => (SmallerThanOrEqualToFirstLimit, second_limit, None),
+            SmallerThanFirstLimit
+            | GreaterThanFirstLimit
+            | SmallerThanOrEqualToFirstLimit
+            | GreaterThanOrEqualToFirstLimit
+            | EqualToFirstLimit => (interval_type, first_limit, None),
+            BetweenLimitsExcludingSecond
+            | BetweenLimitsIncludingBoth
+            | BetweenLimitsExcludingFirst => (interval_type, first_limit, second_limit),
+        };
+        Self {
+            interval_type: CodeValue::Known(interval_type),
+            first_limit,
+            second_limit,
+        }
+    }
+
+    /// Read the type of interval (at `type_octet`) and the two limits which follow it.
+    /// Returns `None` if the type of interval is missing.
+    fn parse(section: &Section, type_octet: usize) -> Result<Option<Self>, Error> {
+        let type_num = section.u8(type_octet)?;
+        if type_num == u8::MAX {
+            return Ok(None);
+        }
+        let first_limit = ScaledValue::parse(section, type_octet + 1)?;
+        let second_limit = ScaledValue::parse(section, type_octet + 6)?;
+        Ok(Some(Self::from_type_num(
+            type_num,
+            first_limit,
+            second_limit,
+        )))
+    }
+
+    /// Like `new`, for types which may not be in code table 4.91.
+    fn from_type_num(
+        type_num: u8,
+        first_limit: Option<ScaledValue>,
+        second_limit: Option<ScaledValue>,
+    ) -> Self {
+        match IntervalType::from_u8(type_num) {
+            Some(interval_type) => Self::new(interval_type, first_limit, second_limit),
+            None => Self {
+                interval_type: CodeValue::unknown(type_num),
+                first_limit,
+                second_limit,
+            },
+        }
+    }
+
+    fn from_idx_str(s: &str) -> Option<Self> {
+        fn split_operator(s: &str) -> Option<(&str, &str)> {
+            // Check the two-character operators first.
+            ["<=", ">=", "<", ">", "="]
+                .into_iter()
+                .find_map(|operator| Some((operator, s.strip_prefix(operator)?)))
+        }
+        let limit = |value: &str| ScaledValue::from_f64(value.parse().ok()?);
+        if let Some(type_and_limits) = s.strip_prefix("type=") {
+            let optional_limit = |value| match value {
+                "missing" => Some(None),
+                value => limit(value).map(Some),
+            };
+            let (type_num, limits) = type_and_limits.split_once(" first=")?;
+            let (first, second) = limits.split_once(" second=")?;
+            return Some(Self::from_type_num(
+                type_num.parse().ok()?,
+                optional_limit(first)?,
+                optional_limit(second)?,
+            ));
+        }
+        let interval = match s.split_once(' ') {
+            None => {
+                let (operator, value) = split_operator(s)?;
+                let interval_type = match operator {
+                    "<" => IntervalType::SmallerThanFirstLimit,
+                    ">" => IntervalType::GreaterThanFirstLimit,
+                    "<=" => IntervalType::SmallerThanOrEqualToFirstLimit,
+                    ">=" => IntervalType::GreaterThanOrEqualToFirstLimit,
+                    _ => IntervalType::EqualToFirstLimit,
+                };
+                Self::new(interval_type, Some(limit(value)?), None)
+            }
+            Some((first, second)) => {
+                let (first_operator, first) = split_operator(first)?;
+                let (second_operator, second) = split_operator(second)?;
+                let interval_type = match (first_operator, second_operator) {
+                    (">=", "<") => IntervalType::BetweenLimitsExcludingSecond,
+                    (">=", "<=") => IntervalType::BetweenLimitsIncludingBoth,
+                    (">", "<=") => IntervalType::BetweenLimitsExcludingFirst,
+                    _ => return None,
+                };
+                Self::new(interval_type, Some(limit(first)?), Some(limit(second)?))
+            }
+        };
+        Some(interval)
+    }
+}
+
+/// Formats intervals the same way as `wgrib2`, e.g. "<2.5e-06" or ">=5.45e-07 <5.65e-07". Types
+/// which aren't in code table 4.91 are written with both limits, e.g.
+/// "type=20 first=1e-06 second=missing".
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let limit = |value: Option<ScaledValue>| match value {
+            Some(value) => format_g(value.value()),
+            None => "missing".to_string(),
+        };
+        let (first, second) = (limit(self.first_limit), limit(self.second_limit));
+        let interval_type = match self.interval_type.known_or_num() {
+            Ok(interval_type) => interval_type,
+            Err(type_num) => return write!(f, "type={type_num} first={first} second={second}"),
+        };
+        match interval_type {
+            IntervalType::SmallerThanFirstLimit => write!(f, "<{first}"),
+            IntervalType::GreaterThanSecondLimit => write!(f, ">{second}"),
+            IntervalType::BetweenLimitsExcludingSecond => write!(f, ">={first} <{second}"),
+            IntervalType::GreaterThanFirstLimit => write!(f, ">{first}"),
+            IntervalType::SmallerThanSecondLimit => write!(f, "<{second}"),
+            IntervalType::SmallerThanOrEqualToFirstLimit => write!(f, "<={first}"),
+            IntervalType::GreaterThanOrEqualToSecondLimit => write!(f, ">={second}"),
+            IntervalType::BetweenLimitsIncludingBoth => write!(f, ">={first} <={second}"),
+            IntervalType::GreaterThanOrEqualToFirstLimit => write!(f, ">={first}"),
+            IntervalType::SmallerThanOrEqualToSecondLimit => write!(f, "<={second}"),
+            IntervalType::BetweenLimitsExcludingFirst => write!(f, ">{first} <={second}"),
+            IntervalType::EqualToFirstLimit => write!(f, "={first}"),
+        }
+    }
+}
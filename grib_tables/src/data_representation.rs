@@ -0,0 +1,360 @@
+use crate::{
+    error::Error,
+    jpeg2000,
+    message::{Section, SectionWriter},
+};
+
+/// Section 5 (the data representation section): how the values in section 7 are packed.
+pub(crate) enum DataRepresentation {
+    /// Data representation template 5.0.
+    Simple(SimplePacking),
+
+    /// Data representation template 5.40: the integers `X` are compressed as a JPEG 2000 image.
+    Jpeg2000(SimplePacking),
+
+    /// Data representation template 5.200, which NCEP uses for radar products.
+    RunLength(RunLengthPacking),
+    // etc.
+}
+
+/// Simple packing: each value `Y` is stored as an unsigned integer `X` of `bits_per_value` bits,
+/// where `Y * 10^D = R + X * 2^E`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct SimplePacking {
+    /// The number of values in section 7. Less than the number of grid points if there's a
+    /// bitmap.
+    pub(crate) num_values: u32,
+
+    /// R.
+    pub(crate) reference_value: f32,
+
+    /// E.
+    pub(crate) binary_scale_factor: i16,
+
+    /// D.
+    pub(crate) decimal_scale_factor: i16,
+    pub(crate) bits_per_value: u8,
+}
+
+/// Run-length packing with level values: each value is one of a few levels, and section 7 holds
+/// runs of level numbers.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct RunLengthPacking {
+    /// The number of values in section 7.
+    pub(crate) num_values: u32,
+    pub(crate) bits_per_value: u8,
+
+    /// MV, the largest level number. Packed values above this encode run lengths.
+    pub(crate) max_level: u16,
+
+    /// The value of each level, from level 1, scaled by `10^decimal_scale_factor`. Level 0 is
+    /// missing.
+    pub(crate) level_values: Vec<u16>,
+    pub(crate) decimal_scale_factor: u8,
+}
+
+impl DataRepresentation {
+    pub(crate) fn parse(section: &Section) -> Result<Self, Error> {
+        let template_num = section.u16(10)?;
+        match template_num {
+            0 => Ok(DataRepresentation::Simple(SimplePacking::parse(section)?)),
+            40 => Ok(DataRepresentation::Jpeg2000(SimplePacking::parse(section)?)),
+            200 => Ok(DataRepresentation::RunLength(RunLengthPacking::parse(
+                section,
+            )?)),
+            // etc.
+            _ => Err(Error::UnsupportedTemplate {
+                section_num: 5,
+                template_num,
+            }),
+        }
+    }
+
+    /// Unpack the values in `data` (section 7). Doesn't apply the bitmap.
+    ///
+    /// `num_points` is the number of points in the grid (section 3). Fields can't have more
+    /// values than that, and checking first means that a corrupt number of values in section 5
+    /// can't make us allocate more memory than the grid needs.
+    pub(crate) fn unpack(&self, data: &Section, num_points: usize) -> Result<Vec<f32>, Error> {
+        let num_values = match self {
+            DataRepresentation::Simple(packing) | DataRepresentation::Jpeg2000(packing) => {
+                packing.num_values
+            }
+            DataRepresentation::RunLength(packing) => packing.num_values,
+        };
+        if num_values as usize > num_points {
+            return Err(Error::InvalidSection {
+                offset: data.offset(),
+                section_num: 7,
+                reason: "section 5 has more values than the grid has points",
+            });
+        }
+        match self {
+            DataRepresentation::Simple(packing) => packing.unpack(data),
+            DataRepresentation::Jpeg2000(packing) => packing.unpack_jpeg2000(data),
+            DataRepresentation::RunLength(packing) => packing.unpack(data),
+        }
+    }
+}
+
+impl SimplePacking {
+    fn parse(section: &Section) -> Result<Self, Error> {
+        Ok(Self {
+            num_values: section.u32(6)?,
+            reference_value: section.f32(12)?,
+            binary_scale_factor: section.i16(16)?,
+            decimal_scale_factor: section.i16(18)?,
+            bits_per_value: section.u8(20)?,
+        })
+    }
+
+    fn unpack(&self, data: &Section) -> Result<Vec<f32>, Error> {
+        let num_values = self.num_values as usize;
+        let bits_per_value = usize::from(self.bits_per_value);
+        let packed = data.bytes_from(6)?;
+        if bits_per_value > 32 {
+            return Err(Error::InvalidSection {
+                offset: data.offset(),
+                section_num: 7,
+                reason: "simple packing uses more than 32 bits per value",
+            });
+        }
+        if packed.len() * 8 < num_values * bits_per_value {
+            return Err(Error::InvalidSection {
+                offset: data.offset(),
+                section_num: 7,
+                reason: "section 7 is too short for the number of values in section 5",
+            });
+        }
+        let scale = self.scale();
+        let values = (0..num_values)
+            .map(|i| scale(read_bits(packed, i * bits_per_value, bits_per_value) as f64))
+            .collect();
+        Ok(values)
+    }
+
+    /// Like [`Self::unpack`], but `X` is a JPEG 2000 code stream.
+    fn unpack_jpeg2000(&self, data: &Section) -> Result<Vec<f32>, Error> {
+        let num_values = self.num_values as usize;
+        let scale = self.scale();
+        // With no bits per value, the field is constant and the code stream may be empty.
+        if self.bits_per_value == 0 {
+            return Ok(vec![scale(0.0); num_values]);
+        }
+        let invalid = |reason| Error::InvalidSection {
+            offset: data.offset(),
+            section_num: 7,
+            reason,
+        };
+        let packed = jpeg2000::decode(data.bytes_from(6)?, num_values).map_err(invalid)?;
+        Ok(packed.into_iter().map(|x| scale(f64::from(x))).collect())
+    }
+
+    /// Convert a packed integer `X` to its value `Y`.
+    fn scale(&self) -> impl Fn(f64) -> f32 {
+        let reference_value = f64::from(self.reference_value);
+        let binary_scale = 2f64.powi(self.binary_scale_factor.into());
+        let decimal_scale = 10f64.powi(self.decimal_scale_factor.into());
+        move |packed| ((reference_value + packed * binary_scale) / decimal_scale) as f32
+    }
+
+    /// Pack `values` (which must not include any missing values) into `bits_per_value` bits each,
+    /// after rounding them to `decimal_scale_factor` decimal places. Returns the whole of
+    /// section 5 and the packed values for section 7.
+    pub(crate) fn pack(
+        values: &[f32],
+        bits_per_value: u8,
+        decimal_scale_factor: i16,
+    ) -> Result<(Vec<u8>, Vec<u8>), Error> {
+        if !(1..=32).contains(&bits_per_value) {
+            return Err(Error::CannotEncode {
+                reason: "simple packing needs between 1 and 32 bits per value",
+            });
+        }
+        let decimal_scale = 10f64.powi(decimal_scale_factor.into());
+        let scaled: Vec<f64> = values
+            .iter()
+            .map(|&value| f64::from(value) * decimal_scale)
+            .collect();
+        if scaled.iter().any(|value| !value.is_finite()) {
+            return Err(Error::CannotEncode {
+                reason: "values must be finite (NaN means missing)",
+            });
+        }
+        let min = scaled.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = scaled.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+        // A constant field (or one with no values at all) needs no bits: every value is R.
+        let (reference_value, binary_scale_factor, bits_per_value) = if max > min {
+            let max_packed = ((1u64 << bits_per_value) - 1) as f64;
+            let mut binary_scale_factor = ((max - min) / max_packed).log2().ceil() as i16;
+            if (max - min) / 2f64.powi(binary_scale_factor.into()) > max_packed {
+                binary_scale_factor += 1;
+            }
+            (min as f32, binary_scale_factor, bits_per_value)
+        } else if max == min {
+            (min as f32, 0, 0)
+        } else {
+            (0.0, 0, 0)
+        };
+        let packing = Self {
+            num_values: values.len() as u32,
+            reference_value,
+            binary_scale_factor,
+            decimal_scale_factor,
+            bits_per_value,
+        };
+
+        let mut packed = Vec::new();
+        if bits_per_value > 0 {
+            let binary_scale = 2f64.powi(binary_scale_factor.into());
+            let max_packed = ((1u64 << bits_per_value) - 1) as f64;
+            let mut writer = BitWriter::default();
+            for value in scaled {
+                // `reference_value` may have been rounded up when it was converted to f32.
+                let x = ((value - f64::from(reference_value)) / binary_scale)
+                    .round()
+                    .clamp(0.0, max_packed);
+                writer.write(x as u64, bits_per_value.into());
+            }
+            packed = writer.finish();
+        }
+        Ok((packing.write(), packed))
+    }
+
+    fn write(&self) -> Vec<u8> {
+        let mut section = SectionWriter::new(5);
+        section
+            .u32(self.num_values)
+            .u16(0) // Template number.
+            .f32(self.reference_value)
+            .i16(self.binary_scale_factor)
+            .i16(self.decimal_scale_factor)
+            .u8(self.bits_per_value)
+            .u8(0); // Type of original field values: floating point.
+        section.finish()
+    }
+}
+
+impl RunLengthPacking {
+    fn parse(section: &Section) -> Result<Self, Error> {
+        let num_levels = section.u16(15)?;
+        let level_values = (0..num_levels)
+            .map(|i| section.u16(18 + 2 * usize::from(i)))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            num_values: section.u32(6)?,
+            bits_per_value: section.u8(12)?,
+            max_level: section.u16(13)?,
+            level_values,
+            decimal_scale_factor: section.u8(17)?,
+        })
+    }
+
+    /// Each run starts with a level number, which is followed by the digits of its length minus
+    /// one, least significant first, in base `2^bits_per_value - 1 - max_level`. The digits are
+    /// stored plus `max_level + 1`, so they can't be mistaken for level numbers.
+    fn unpack(&self, data: &Section) -> Result<Vec<f32>, Error> {
+        let invalid = |reason| Error::InvalidSection {
+            offset: data.offset(),
+            section_num: 7,
+            reason,
+        };
+        let bits_per_value = usize::from(self.bits_per_value);
+        let max_level = u64::from(self.max_level);
+        let base = (1u64 << bits_per_value.min(32)) - 1;
+        if !(1..=32).contains(&bits_per_value)
+            || max_level >= base
+            || usize::from(self.max_level) > self.level_values.len()
+        {
+            return Err(invalid("run-length packing has invalid levels"));
+        }
+        let base = base - max_level;
+        let decimal_scale = 10f64.powi(self.decimal_scale_factor.into());
+        let levels: Vec<f32> = std::iter::once(f32::NAN)
+            .chain(
+                self.level_values
+                    .iter()
+                    .map(|&value| (f64::from(value) / decimal_scale) as f32),
+            )
+            .collect();
+
+        let num_values = self.num_values as usize;
+        let packed = data.bytes_from(6)?;
+        let num_packed = packed.len() * 8 / bits_per_value;
+        let mut packed = (0..num_packed)
+            .map(|i| read_bits(packed, i * bits_per_value, bits_per_value))
+            .peekable();
+        let mut values = Vec::with_capacity(num_values);
+        while values.len() < num_values {
+            let level = packed.next().ok_or_else(|| {
+                invalid("section 7 is too short for the number of values in section 5")
+            })?;
+            if level > max_level {
+                return Err(invalid("run-length packed data doesn't start with a level"));
+            }
+            let too_long = || invalid("run-length packed data has more values than section 5");
+            let mut run_length = 1u64;
+            // `None` once the scale of the next digit doesn't fit in a `u64`.
+            let mut digit_scale = Some(1u64);
+            while let Some(digit) = packed.next_if(|&digit| digit > max_level) {
+                run_length = digit_scale
+                    .and_then(|scale| scale.checked_mul(digit - max_level - 1))
+                    .and_then(|run| run_length.checked_add(run))
+                    .ok_or_else(too_long)?;
+                digit_scale = digit_scale.and_then(|scale| scale.checked_mul(base));
+            }
+            if values.len() as u64 + run_length > num_values as u64 {
+                return Err(too_long());
+            }
+            let value = levels[level as usize];
+            values.extend(std::iter::repeat_n(value, run_length as usize));
+        }
+        Ok(values)
+    }
+}
+
+/// Read `num_bits` bits (at most 32), starting `bit_offset` bits from the start of `bytes`.
+fn read_bits(bytes: &[u8], bit_offset: usize, num_bits: usize) -> u64 {
+    let mut value = 0u64;
+    let mut bit = bit_offset;
+    while bit < bit_offset + num_bits {
+        // Read as many bits as possible from the current byte.
+        let available = 8 - bit % 8;
+        let wanted = (bit_offset + num_bits - bit).min(available);
+        let byte = u64::from(bytes[bit / 8]);
+        let bits = (byte >> (available - wanted)) & ((1 << wanted) - 1);
+        value = (value << wanted) | bits;
+        bit += wanted;
+    }
+    value
+}
+
+/// Packs unsigned integers into a big-endian bit stream.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u64,
+    buffered_bits: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u64, num_bits: u32) {
+        self.buffer = (self.buffer << num_bits) | value;
+        self.buffered_bits += num_bits;
+        while self.buffered_bits >= 8 {
+            self.buffered_bits -= 8;
+            self.bytes.push((self.buffer >> self.buffered_bits) as u8);
+        }
+        self.buffer &= (1 << self.buffered_bits) - 1;
+    }
+
+    /// Pad the last byte with zeros.
+    fn finish(mut self) -> Vec<u8> {
+        if self.buffered_bits > 0 {
+            self.bytes
+                .push((self.buffer << (8 - self.buffered_bits)) as u8);
+        }
+        self.bytes
+    }
+}
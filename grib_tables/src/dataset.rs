@@ -0,0 +1,402 @@
+use std::{collections::HashMap, hash::Hash};
+
+use crate::{
+    center_and_table_versions::CenterAndTableVersions, constituent::Constituent,
+    ensemble::Ensemble, error::Error, idx::IdxRecord, level::Level, message::ReferenceTime,
+    step::Step, AbbrevToProduct,
+};
+
+/// Builds a [`DatasetIndex`] from the `.idx` files of many GRIB2 files, e.g. one GRIB2 file per
+/// init time and ensemble member of an NWP model.
+pub struct DatasetIndexer {
+    center_and_table_versions: CenterAndTableVersions,
+    files: Vec<String>,
+    init_times: Dimension<ReferenceTime>,
+    steps: Dimension<Step>,
+    ensembles: Dimension<Option<Ensemble>>,
+    levels: Dimension<Level>,
+    products: Dimension<(&'static str, Option<Constituent>)>,
+    cells: HashMap<CellIndex, Location>,
+    duplicates: Vec<Duplicate>,
+    undecoded: Vec<UndecodedRecord>,
+}
+
+/// The position of a cell along each dimension: init time, step, ensemble, level, product.
+pub(crate) type CellIndex = [usize; 5];
+
+/// An N-dimensional index of a dataset: init time × step × ensemble × level × product.
+/// Each cell maps to the byte range of one GRIB2 message in one file.
+///
+/// The coordinates along each dimension are in the order in which they were first seen.
+pub struct DatasetIndex {
+    center_and_table_versions: CenterAndTableVersions,
+    files: Vec<String>,
+    init_times: Vec<ReferenceTime>,
+    steps: Vec<Step>,
+    ensembles: Vec<Option<Ensemble>>,
+    levels: Vec<Level>,
+    products: Vec<(&'static str, Option<Constituent>)>,
+    cells: HashMap<CellIndex, Location>,
+    duplicates: Vec<Duplicate>,
+    undecoded: Vec<UndecodedRecord>,
+}
+
+/// The coordinates of one cell of a [`DatasetIndex`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Coordinates {
+    pub init_time: ReferenceTime,
+    pub step: Step,
+
+    /// The ensemble member, derived forecast or probability. `None` for deterministic forecasts.
+    pub ensemble: Option<Ensemble>,
+    pub level: Level,
+
+    /// The abbreviation of the product, e.g. "TMP".
+    pub product: &'static str,
+
+    /// The chemical or aerosol, for atmospheric chemistry products such as "MASSDEN".
+    pub constituent: Option<Constituent>,
+}
+
+/// Where to find the GRIB2 message for one cell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Location {
+    /// Index into [`DatasetIndex::files`].
+    pub file_index: usize,
+
+    /// The position of the start of the GRIB2 message, in bytes from the start of the file.
+    pub start: u64,
+
+    /// The position one past the end of the message. `None` if this is the last message in the
+    /// file and the length of the file isn't known.
+    pub end: Option<u64>,
+}
+
+/// Two `.idx` records which decode to the same coordinates. Only the first is kept in the index.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Duplicate {
+    pub coordinates: Coordinates,
+    pub kept: Location,
+    pub ignored: Location,
+}
+
+/// A `.idx` record which couldn't be placed in the index, e.g. because its product is unknown.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UndecodedRecord {
+    pub file_index: usize,
+    pub line: String,
+    pub reason: &'static str,
+}
+
+impl DatasetIndexer {
+    /// `center_num`, `subcenter`, `local_table_version` and `master_table_version` say which
+    /// tables to use to decode the product abbreviations in the `.idx` files, e.g. 7, 0, 1 and 33
+    /// for NCEP. See [`CenterAndTableVersions::from_numbers`].
+    pub fn new(
+        center_num: u16,
+        subcenter: u16,
+        local_table_version: u8,
+        master_table_version: u8,
+    ) -> Self {
+        Self {
+            center_and_table_versions: CenterAndTableVersions::from_numbers(
+                center_num,
+                subcenter,
+                local_table_version,
+                master_table_version,
+            ),
+            files: Vec::new(),
+            init_times: Dimension::default(),
+            steps: Dimension::default(),
+            ensembles: Dimension::default(),
+            levels: Dimension::default(),
+            products: Dimension::default(),
+            cells: HashMap::new(),
+            duplicates: Vec::new(),
+            undecoded: Vec::new(),
+        }
+    }
+
+    /// Add every record of `idx` (the contents of a `.idx` file) to the index.
+    ///
+    /// `grib_location` is the path or URL of the GRIB2 file which `idx` describes, and
+    /// `grib_len` is its length in bytes, if known. The length is only used to find the end of
+    /// the last message.
+    ///
+    /// Returns an error (without changing the index) if any line isn't a valid `.idx` record.
+    pub fn add_idx(
+        &mut self,
+        grib_location: impl Into<String>,
+        idx: &str,
+        grib_len: Option<u64>,
+    ) -> Result<(), Error> {
+        let records = idx
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| Ok((line, IdxRecord::parse(line)?)))
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        // Fields in the same message share an offset, so each message ends where the next
+        // distinct offset begins.
+        let mut offsets: Vec<u64> = records.iter().map(|(_, record)| record.offset).collect();
+        offsets.sort_unstable();
+        offsets.dedup();
+        let end_of = |offset: u64| {
+            let i = offsets.partition_point(|&o| o <= offset);
+            offsets.get(i).copied().or(grib_len)
+        };
+
+        let file_index = self.files.len();
+        self.files.push(grib_location.into());
+        for (line, record) in records {
+            let location = Location {
+                file_index,
+                start: record.offset,
+                end: end_of(record.offset),
+            };
+            match self.decode(&record) {
+                Ok(coordinates) => self.insert(coordinates, location),
+                Err(reason) => self.undecoded.push(UndecodedRecord {
+                    file_index,
+                    line: line.to_string(),
+                    reason,
+                }),
+            }
+        }
+        Ok(())
+    }
+
+    pub fn finish(self) -> DatasetIndex {
+        DatasetIndex {
+            center_and_table_versions: self.center_and_table_versions,
+            files: self.files,
+            init_times: self.init_times.values,
+            steps: self.steps.values,
+            ensembles: self.ensembles.values,
+            levels: self.levels.values,
+            products: self.products.values,
+            cells: self.cells,
+            duplicates: self.duplicates,
+            undecoded: self.undecoded,
+        }
+    }
+
+    fn decode(&self, record: &IdxRecord) -> Result<Coordinates, &'static str> {
+        let product = self
+            .center_and_table_versions
+            .abbrev_to_product(&record.abbrev)
+            .ok_or("unknown product")?;
+        Ok(Coordinates {
+            init_time: record.reference_time,
+            step: Step::from_idx_str(&record.step).ok_or("unknown step")?,
+            ensemble: Ensemble::from_idx_fields(&record.extra)?,
+            level: Level::from_idx_str(&record.level).ok_or("unknown level")?,
+            product: product.abbrev(),
+            constituent: Constituent::from_idx_fields(&record.extra)?,
+        })
+    }
+
+    fn insert(&mut self, coordinates: Coordinates, location: Location) {
+        let cell_index = [
+            self.init_times.index_of(coordinates.init_time),
+            self.steps.index_of(coordinates.step),
+            self.ensembles.index_of(coordinates.ensemble),
+            self.levels.index_of(coordinates.level),
+            self.products
+                .index_of((coordinates.product, coordinates.constituent)),
+        ];
+        match self.cells.get(&cell_index) {
+            Some(&kept) => self.duplicates.push(Duplicate {
+                coordinates,
+                kept,
+                ignored: location,
+            }),
+            None => {
+                self.cells.insert(cell_index, location);
+            }
+        }
+    }
+}
+
+impl DatasetIndex {
+    /// The paths or URLs of the GRIB2 files, in the order they were added.
+    pub fn files(&self) -> &[String] {
+        &self.files
+    }
+
+    pub fn init_times(&self) -> &[ReferenceTime] {
+        &self.init_times
+    }
+
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    pub fn ensembles(&self) -> &[Option<Ensemble>] {
+        &self.ensembles
+    }
+
+    pub fn levels(&self) -> &[Level] {
+        &self.levels
+    }
+
+    /// Each product's abbreviation, and its constituent for atmospheric chemistry products.
+    pub fn products(&self) -> &[(&'static str, Option<Constituent>)] {
+        &self.products
+    }
+
+    /// The number of cells which have a location.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn get(&self, coordinates: &Coordinates) -> Option<&Location> {
+        let cell_index = [
+            position(&self.init_times, &coordinates.init_time)?,
+            position(&self.steps, &coordinates.step)?,
+            position(&self.ensembles, &coordinates.ensemble)?,
+            position(&self.levels, &coordinates.level)?,
+            position(
+                &self.products,
+                &(coordinates.product, coordinates.constituent),
+            )?,
+        ];
+        self.cells.get(&cell_index)
+    }
+
+    /// Every cell which has a location, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (Coordinates, &Location)> + '_ {
+        self.cells
+            .iter()
+            .map(|(cell_index, location)| (self.coordinates(cell_index), location))
+    }
+
+    /// Records which decoded to the same coordinates as an earlier record.
+    pub fn duplicates(&self) -> &[Duplicate] {
+        &self.duplicates
+    }
+
+    /// Records whose product, level or step we couldn't decode.
+    pub fn undecoded(&self) -> &[UndecodedRecord] {
+        &self.undecoded
+    }
+
+    /// Cells which we'd expect to exist but which no `.idx` file mentions.
+    ///
+    /// Not every product exists at every level and step (e.g. accumulations don't exist in the
+    /// analysis), so we only expect each (product, level) pair to exist at the steps where it
+    /// appears at least once, for every init time. Likewise, we only expect it at the ensemble
+    /// entries which [`expected_ensembles`](Self::expected_ensembles) gives for the entries where
+    /// it appears.
+    pub fn missing(&self) -> Vec<Coordinates> {
+        let mut seen: HashMap<[usize; 2], (Vec<usize>, Vec<usize>)> = HashMap::new();
+        for &[_, step, ensemble, level, product] in self.cells.keys() {
+            let (steps, ensembles) = seen.entry([level, product]).or_default();
+            if !steps.contains(&step) {
+                steps.push(step);
+            }
+            if !ensembles.contains(&ensemble) {
+                ensembles.push(ensemble);
+            }
+        }
+        let mut variables: Vec<_> = seen.into_iter().collect();
+        variables.sort_unstable();
+
+        let mut missing = Vec::new();
+        for ([level, product], (mut steps, ensembles)) in variables {
+            steps.sort_unstable();
+            let ensembles = self.expected_ensembles(&ensembles);
+            for init_time in 0..self.init_times.len() {
+                for &step in &steps {
+                    for &ensemble in &ensembles {
+                        let cell_index = [init_time, step, ensemble, level, product];
+                        if !self.cells.contains_key(&cell_index) {
+                            missing.push(self.coordinates(&cell_index));
+                        }
+                    }
+                }
+            }
+        }
+        missing
+    }
+
+    /// The ensemble entries (indices into [`ensembles`](Self::ensembles), in order) at which we'd
+    /// expect a variable which appears at the entries `seen`.
+    ///
+    /// A variable which appears in one ensemble member is expected in every member, and likewise
+    /// for deterministic and derived forecasts. But each product has its own probability
+    /// thresholds, so a probability is only expected at the thresholds where it appears.
+    pub fn expected_ensembles(&self, seen: &[usize]) -> Vec<usize> {
+        let same_kind = |a: &Option<Ensemble>, b: &Option<Ensemble>| match (a, b) {
+            (None, None)
+            | (Some(Ensemble::Member(_)), Some(Ensemble::Member(_)))
+            | (Some(Ensemble::Derived(_)), Some(Ensemble::Derived(_))) => true,
+            (Some(Ensemble::Probability(a)), Some(Ensemble::Probability(b))) => a == b,
+            _ => false,
+        };
+        (0..self.ensembles.len())
+            .filter(|&i| {
+                seen.iter()
+                    .any(|&j| same_kind(&self.ensembles[i], &self.ensembles[j]))
+            })
+            .collect()
+    }
+
+    /// The tables which decoded the product abbreviations.
+    pub fn center_and_table_versions(&self) -> &CenterAndTableVersions {
+        &self.center_and_table_versions
+    }
+
+    /// Every cell which has a location, by its position along each dimension.
+    #[cfg(feature = "kerchunk")]
+    pub(crate) fn cells(&self) -> impl Iterator<Item = (&CellIndex, &Location)> {
+        self.cells.iter()
+    }
+
+    fn coordinates(&self, cell_index: &CellIndex) -> Coordinates {
+        let [init_time, step, ensemble, level, product] = *cell_index;
+        Coordinates {
+            init_time: self.init_times[init_time],
+            step: self.steps[step],
+            ensemble: self.ensembles[ensemble],
+            level: self.levels[level],
+            product: self.products[product].0,
+            constituent: self.products[product].1,
+        }
+    }
+}
+
+/// The distinct coordinates along one dimension, in the order they were first seen.
+struct Dimension<T> {
+    values: Vec<T>,
+    indices: HashMap<T, usize>,
+}
+
+impl<T> Default for Dimension<T> {
+    fn default() -> Self {
+        Self {
+            values: Vec::new(),
+            indices: HashMap::new(),
+        }
+    }
+}
+
+impl<T: Clone + Eq + Hash> Dimension<T> {
+    fn index_of(&mut self, value: T) -> usize {
+        if let Some(&i) = self.indices.get(&value) {
+            return i;
+        }
+        let i = self.values.len();
+        self.values.push(value.clone());
+        self.indices.insert(value, i);
+        i
+    }
+}
+
+fn position<T: PartialEq>(values: &[T], value: &T) -> Option<usize> {
+    values.iter().position(|v| v == value)
+}
@@ -0,0 +1,370 @@
+//! A verbose, human-readable description of every section of a GRIB2 message, like
+//! `wgrib2 -V`, for debugging.
+
+use std::fmt;
+
+use crate::{
+    code_table::CodeValue,
+    constituent::Constituent,
+    data_representation::DataRepresentation,
+    ensemble::Ensemble,
+    error::Error,
+    generating_process::Model,
+    grid::Grid,
+    message::{Field, Message},
+    product::Product,
+    product_definition::ProductDefinition,
+};
+
+/// Returned by [`Message::describe`]. Fields which can't be decoded are described as far as
+/// possible, followed by the error.
+pub struct Description<'a> {
+    message: &'a Message<'a>,
+}
+
+impl<'a> Message<'a> {
+    /// A multi-line description of this message, for printing with `{}`. e.g.
+    ///
+    /// ```text
+    /// GRIB2 message at byte 0, 188 bytes, edition 2
+    ///   discipline 0 (meteorological)
+    ///   center 7 (NCEP), subcenter 0, master table version 33, local table version 1
+    ///   reference time 2024-01-02 06:00:00 (start of forecast), operational products, ...
+    ///   field 1 of 1
+    ///     grid: lat/lon (template 3.0), 3 x 2 points, lat 50 to 49.5 by 0.5, lon 0 to 1 by 0.5, ...
+    ///     product: TMP, Temperature [K] (template 4.0, parameter 0.0.0)
+    ///     level: 2 m above ground, step: 6 hour fcst
+    ///     generating process: Forecast, model missing
+    ///     data representation: template 5.0, simple packing, 5 values, 12 bits per value, ...
+    ///     bitmap: indicator 0, 1 of 6 points missing
+    ///     values: min 270, max 275.5, mean 272.7
+    /// ```
+    pub fn describe(&'a self) -> Description<'a> {
+        Description { message: self }
+    }
+}
+
+impl fmt::Display for Description<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = self.message;
+        let indicator = message.indicator();
+        let identification = message.identification();
+        writeln!(
+            f,
+            "GRIB2 message at byte {}, {} bytes, edition {}",
+            message.offset(),
+            message.total_length(),
+            indicator.edition
+        )?;
+        writeln!(
+            f,
+            "  discipline {} ({})",
+            indicator.discipline,
+            discipline_name(indicator.discipline)
+        )?;
+        writeln!(
+            f,
+            "  center {} ({}), subcenter {}, master table version {}, local table version {}",
+            identification.center,
+            center_name(identification.center),
+            identification.subcenter,
+            identification.master_table_version,
+            identification.local_table_version
+        )?;
+        let time = identification.reference_time;
+        writeln!(
+            f,
+            "  reference time {:04}-{:02}-{:02} {:02}:{:02}:{:02} ({}), {}, {}",
+            time.year,
+            time.month,
+            time.day,
+            time.hour,
+            time.minute,
+            time.second,
+            significance_of_reference_time(identification.significance_of_reference_time),
+            production_status(identification.production_status),
+            type_of_data(identification.type_of_data),
+        )?;
+        let fields = message.fields();
+        for (i, field) in fields.iter().enumerate() {
+            writeln!(f, "  field {} of {}", i + 1, fields.len())?;
+            if let Err(err) = describe_field(f, message, field)? {
+                writeln!(f, "    error: {err}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Write one line per section of `field`. Returns `Ok(Err(_))` if part of the field can't be
+/// decoded, so the caller can say why.
+fn describe_field(
+    f: &mut fmt::Formatter<'_>,
+    message: &Message,
+    field: &Field,
+) -> Result<Result<(), Error>, fmt::Error> {
+    macro_rules! try_decode {
+        ($result:expr) => {
+            match $result {
+                Ok(value) => value,
+                Err(err) => return Ok(Err(err)),
+            }
+        };
+    }
+
+    let grid = try_decode!(field.grid());
+    writeln!(f, "    grid: {}", GridDescription(&grid))?;
+
+    let center_and_table_versions = message.center_and_table_versions();
+    let product_definition = try_decode!(ProductDefinition::parse(
+        &field.product_definition,
+        field.reference_time
+    ));
+    let discipline = message.indicator().discipline;
+    let (category, parameter) = (product_definition.category, product_definition.parameter);
+    let template = format!(
+        "template 4.{}, parameter {discipline}.{category}.{parameter}",
+        product_definition.template_num
+    );
+    match Product::from_discipline_and_category_and_parameter_numbers(
+        discipline,
+        category,
+        parameter,
+        message.center_and_table_versions(),
+    ) {
+        CodeValue::Known(product) => writeln!(
+            f,
+            "    product: {}, {} [{}] ({template})",
+            product.abbrev(),
+            product.name(),
+            product.unit()
+        )?,
+        _ => writeln!(f, "    product: unknown ({template})")?,
+    }
+    writeln!(
+        f,
+        "    level: {}, step: {}",
+        product_definition.level, product_definition.step
+    )?;
+    let generating_process = try_decode!(field.generating_process(&center_and_table_versions));
+    let model = match generating_process.model {
+        Model::Ncep(model) => model.name().to_string(),
+        Model::Unknown { process_num, .. } => format!("{process_num} (unknown)"),
+        Model::Missing => "missing".to_string(),
+    };
+    writeln!(
+        f,
+        "    generating process: {}, model {model}",
+        code_value(generating_process.type_of_generating_process)
+    )?;
+    let extra: Vec<String> = product_definition
+        .constituent
+        .map_or_else(Vec::new, Constituent::to_idx_fields)
+        .into_iter()
+        .chain(
+            product_definition
+                .ensemble
+                .map_or_else(Vec::new, Ensemble::to_idx_fields),
+        )
+        .collect();
+    if !extra.is_empty() {
+        writeln!(f, "    {}", extra.join(", "))?;
+    }
+
+    let data_representation = try_decode!(DataRepresentation::parse(&field.data_representation));
+    let template_num = try_decode!(field.data_representation.u16(10));
+    write!(f, "    data representation: template 5.{template_num}, ")?;
+    match data_representation {
+        DataRepresentation::Simple(packing) | DataRepresentation::Jpeg2000(packing) => {
+            let name = match data_representation {
+                DataRepresentation::Simple(_) => "simple packing",
+                _ => "JPEG 2000",
+            };
+            writeln!(
+                f,
+                "{name}, {} values, {} bits per value, R = {}, E = {}, D = {}",
+                packing.num_values,
+                packing.bits_per_value,
+                packing.reference_value,
+                packing.binary_scale_factor,
+                packing.decimal_scale_factor
+            )?;
+        }
+        DataRepresentation::RunLength(packing) => writeln!(
+            f,
+            "run-length packing, {} values, {} bits per value, {} levels, D = {}",
+            packing.num_values,
+            packing.bits_per_value,
+            packing.level_values.len(),
+            packing.decimal_scale_factor
+        )?,
+    }
+
+    let values = try_decode!(field.values());
+    let num_missing = values.iter().filter(|value| value.is_nan()).count();
+    match try_decode!(field.bitmap.u8(6)) {
+        255 => writeln!(f, "    bitmap: none")?,
+        indicator => writeln!(
+            f,
+            "    bitmap: indicator {indicator}, {num_missing} of {} points missing",
+            values.len()
+        )?,
+    }
+    let present = values.iter().filter(|value| !value.is_nan());
+    let (min, max, sum) = present.fold(
+        (f64::INFINITY, f64::NEG_INFINITY, 0.0),
+        |(min, max, sum), &value| {
+            let value = f64::from(value);
+            (min.min(value), max.max(value), sum + value)
+        },
+    );
+    let num_present = values.len() - num_missing;
+    if num_present == 0 {
+        writeln!(f, "    values: all {} missing", values.len())?;
+    } else {
+        writeln!(
+            f,
+            "    values: min {min}, max {max}, mean {}",
+            sum / num_present as f64
+        )?;
+    }
+    Ok(Ok(()))
+}
+
+struct GridDescription<'a>(&'a Grid);
+
+impl fmt::Display for GridDescription<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (ni, nj) = self.0.dimensions();
+        match self.0 {
+            Grid::LatLon(grid) => write!(
+                f,
+                "lat/lon (template 3.0), {ni} x {nj} points, lat {} to {} by {}, lon {} to {} by {}, \
+                 shape of earth {}, scanning mode {:#04x}",
+                grid.first_lat,
+                grid.last_lat,
+                grid.dj,
+                grid.first_lon,
+                grid.last_lon,
+                grid.di,
+                grid.shape_of_earth,
+                grid.scanning_mode
+            ),
+            Grid::PolarStereographic(grid) => write!(
+                f,
+                "polar stereographic (template 3.20), {ni} x {nj} points, first point ({}, {}), \
+                 LaD {}, LoV {}, {} x {} m, {} pole, shape of earth {}, scanning mode {:#04x}",
+                grid.first_lat,
+                grid.first_lon,
+                grid.lad,
+                grid.lov,
+                grid.dx,
+                grid.dy,
+                if grid.projection_centre & 0x80 == 0 {
+                    "north"
+                } else {
+                    "south"
+                },
+                grid.shape_of_earth,
+                grid.scanning_mode
+            ),
+            Grid::LambertConformal(grid) => write!(
+                f,
+                "Lambert conformal (template 3.30), {ni} x {nj} points, first point ({}, {}), \
+                 LaD {}, LoV {}, Latin1 {}, Latin2 {}, {} x {} m, shape of earth {}, \
+                 scanning mode {:#04x}",
+                grid.first_lat,
+                grid.first_lon,
+                grid.lad,
+                grid.lov,
+                grid.latin1,
+                grid.latin2,
+                grid.dx,
+                grid.dy,
+                grid.shape_of_earth,
+                grid.scanning_mode
+            ),
+        }
+    }
+}
+
+fn code_value<T: fmt::Debug>(value: CodeValue<T>) -> String {
+    match value {
+        CodeValue::Known(value) => format!("{value:?}"),
+        CodeValue::Reserved(num) => format!("reserved value {num}"),
+        CodeValue::LocalUse(num) => format!("local value {num}"),
+        CodeValue::Missing => "missing".to_string(),
+    }
+}
+
+/// Code table 0.0.
+fn discipline_name(discipline: u8) -> &'static str {
+    match discipline {
+        0 => "meteorological",
+        1 => "hydrological",
+        2 => "land surface",
+        3 => "satellite remote sensing",
+        4 => "space weather",
+        10 => "oceanographic",
+        20 => "health and socioeconomic impacts",
+        // etc.
+        _ => "unknown",
+    }
+}
+
+/// Common code table C-11.
+fn center_name(center: u16) -> &'static str {
+    match center {
+        7 => "NCEP",
+        8 => "NWS Telecommunications Gateway",
+        34 => "JMA",
+        54 => "CMC",
+        74 => "UK Met Office",
+        78 => "DWD",
+        85 => "Meteo-France",
+        98 => "ECMWF",
+        // etc.
+        _ => "unknown",
+    }
+}
+
+/// Code table 1.2.
+fn significance_of_reference_time(significance: u8) -> &'static str {
+    match significance {
+        0 => "analysis",
+        1 => "start of forecast",
+        2 => "verifying time of forecast",
+        3 => "observation time",
+        // etc.
+        _ => "unknown significance",
+    }
+}
+
+/// Code table 1.3.
+fn production_status(status: u8) -> &'static str {
+    match status {
+        0 => "operational products",
+        1 => "operational test products",
+        2 => "research products",
+        3 => "re-analysis products",
+        // etc.
+        _ => "unknown production status",
+    }
+}
+
+/// Code table 1.4.
+fn type_of_data(type_of_data: u8) -> &'static str {
+    match type_of_data {
+        0 => "analysis products",
+        1 => "forecast products",
+        2 => "analysis and forecast products",
+        3 => "control forecast products",
+        4 => "perturbed forecast products",
+        5 => "control and perturbed forecast products",
+        6 => "processed satellite observations",
+        7 => "processed radar observations",
+        8 => "event probability",
+        // etc.
+        _ => "unknown type of data",
+    }
+}
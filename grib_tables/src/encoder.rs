@@ -0,0 +1,157 @@
+use crate::{
+    center_and_table_versions::CenterAndTableVersions,
+    data_representation::SimplePacking,
+    error::Error,
+    grid::Grid,
+    level::{FixedSurface, Level},
+    message::{ReferenceTime, SectionWriter, END_SECTION, INDICATOR_LEN},
+    product::Product,
+    step::Step,
+};
+
+/// Everything needed to encode one field as a GRIB2 message, apart from its values.
+///
+/// The message uses product definition template 4.0 and simple packing (data representation
+/// template 5.0), which every GRIB2 decoder understands.
+pub struct Encoder<'a> {
+    pub product: &'a Product,
+    pub center_and_table_versions: &'a CenterAndTableVersions,
+    pub reference_time: ReferenceTime,
+
+    /// Code table 4.3, e.g. 2 for a forecast or 3 for a bias corrected forecast.
+    pub type_of_generating_process: u8,
+    pub grid: Grid,
+    pub level: Level,
+
+    /// Must be instantaneous: statistically processed steps need a different template.
+    pub step: Step,
+
+    /// From 1 to 32. More bits keep more precision, at the cost of a bigger message.
+    pub bits_per_value: u8,
+
+    /// Values are rounded to `10^-decimal_scale_factor` before packing, e.g. 2 keeps two decimal
+    /// places. May be negative.
+    pub decimal_scale_factor: i16,
+}
+
+impl Encoder<'_> {
+    /// Encode `values` as one complete GRIB2 message. `values` must have one value per grid
+    /// point, in the order given by the grid's scanning mode. NaNs are written as missing values,
+    /// using a bitmap.
+    pub fn encode(&self, values: &[f32]) -> Result<Vec<u8>, Error> {
+        if values.len() as u64 != self.grid.num_points() {
+            return Err(Error::CannotEncode {
+                reason: "the number of values doesn't match the number of grid points",
+            });
+        }
+        if self.step.statistics.is_some() {
+            return Err(Error::CannotEncode {
+                reason: "statistically processed steps aren't supported",
+            });
+        }
+
+        let present: Vec<f32> = values.iter().copied().filter(|v| !v.is_nan()).collect();
+        let (data_representation, packed) =
+            SimplePacking::pack(&present, self.bits_per_value, self.decimal_scale_factor)?;
+        let mut data = SectionWriter::new(7);
+        data.bytes(&packed);
+
+        let sections = [
+            self.identification(),
+            self.grid.write()?,
+            self.product_definition(),
+            data_representation,
+            bitmap(values),
+            data.finish(),
+        ];
+        let total_length =
+            INDICATOR_LEN + sections.iter().map(Vec::len).sum::<usize>() + END_SECTION.len();
+
+        let mut message = Vec::with_capacity(total_length);
+        message.extend_from_slice(b"GRIB");
+        message.extend_from_slice(&[0, 0]); // Reserved.
+        message.push(self.product.discipline_num());
+        message.push(2); // Edition.
+        message.extend_from_slice(&(total_length as u64).to_be_bytes());
+        for section in sections {
+            message.extend_from_slice(&section);
+        }
+        message.extend_from_slice(END_SECTION);
+        Ok(message)
+    }
+
+    /// Section 1.
+    fn identification(&self) -> Vec<u8> {
+        let ctv = self.center_and_table_versions;
+        let t = &self.reference_time;
+        let mut section = SectionWriter::new(1);
+        section
+            .u16(ctv.originating_center().center_num())
+            .u16(0) // Sub-center.
+            .u8(ctv.master_table().version_num())
+            .u8(ctv.local_table_version())
+            .u8(1) // Significance of reference time: start of forecast.
+            .u16(t.year)
+            .u8(t.month)
+            .u8(t.day)
+            .u8(t.hour)
+            .u8(t.minute)
+            .u8(t.second)
+            .u8(0) // Production status: operational products.
+            .u8(match self.step.forecast_time {
+                0 => 0, // Type of data: analysis products.
+                _ => 1, // Forecast products.
+            });
+        section.finish()
+    }
+
+    /// Section 4, using template 4.0.
+    fn product_definition(&self) -> Vec<u8> {
+        let mut section = SectionWriter::new(4);
+        section
+            .u16(0) // No coordinate values after the template.
+            .u16(0) // Template number.
+            .u8(self.product.category_num())
+            .u8(self.product.parameter_num())
+            .u8(self.type_of_generating_process)
+            .u8(u8::MAX) // Background generating process identifier: missing.
+            .u8(u8::MAX) // Forecast generating process identifier: missing.
+            .u16(0) // Hours of observational data cutoff.
+            .u8(0) // Minutes of observational data cutoff.
+            .u8(self.step.time_unit.known_or_num().map_or_else(|num| num, |unit| unit as u8))
+            .u32(self.step.forecast_time);
+        write_fixed_surface(&mut section, Some(self.level.first));
+        write_fixed_surface(&mut section, self.level.second);
+        section.finish()
+    }
+}
+
+/// Section 6. Only has a bitmap if some values are missing.
+fn bitmap(values: &[f32]) -> Vec<u8> {
+    let mut section = SectionWriter::new(6);
+    if values.iter().any(|value| value.is_nan()) {
+        section.u8(0); // A bitmap follows.
+        for chunk in values.chunks(8) {
+            let byte = chunk
+                .iter()
+                .enumerate()
+                .filter(|(_, value)| !value.is_nan())
+                .fold(0u8, |byte, (i, _)| byte | (0x80 >> i));
+            section.u8(byte);
+        }
+    } else {
+        section.u8(u8::MAX); // No bitmap.
+    }
+    section.finish()
+}
+
+/// The inverse of `Level::parse`, for one surface. Missing surfaces and values have all bits set
+/// to 1.
+fn write_fixed_surface(section: &mut SectionWriter, surface: Option<FixedSurface>) {
+    let type_num = surface.map_or(u8::MAX, |surface| surface.type_num);
+    section.u8(type_num);
+    match surface.and_then(|surface| surface.value) {
+        Some(value) => section.i8(value.scale_factor()).i32(value.scaled_value()),
+        None => section.u8(u8::MAX).u32(u32::MAX),
+    };
+}
@@ -0,0 +1,327 @@
+use std::fmt;
+
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+
+use crate::{
+    code_table::CodeValue, error::Error, idx::format_g, level::ScaledValue, message::Section,
+};
+
+/// What kind of ensemble forecast a field is: a single member, a product derived from all the
+/// members, or a probability.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Ensemble {
+    /// From product definition templates 4.1, 4.11, 4.41, 4.43, 4.45 and 4.47.
+    Member(EnsembleMember),
+
+    /// From product definition templates 4.2 and 4.12.
+    Derived(CodeValue<DerivedForecast>),
+
+    /// From product definition templates 4.5 and 4.9.
+    Probability(Probability),
+}
+
+/// One member of an ensemble, e.g. "ENS=+1" in a `.idx` file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct EnsembleMember {
+    pub forecast_type: CodeValue<EnsembleForecastType>,
+    pub perturbation_num: u8,
+}
+
+/// Type of ensemble forecast (code table 4.6).
+#[derive(FromPrimitive, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EnsembleForecastType {
+    HighResolutionControl = 0,
+    LowResolutionControl,
+    NegativelyPerturbed,
+    PositivelyPerturbed,
+    MultiModel,
+    // etc.
+}
+
+/// Derived forecast (code table 4.7).
+#[derive(FromPrimitive, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DerivedForecast {
+    UnweightedMean = 0,
+    WeightedMean,
+    StandardDeviation,
+    NormalisedStandardDeviation,
+    Spread,
+    LargeAnomalyIndex,
+    UnweightedMeanOfCluster,
+    InterquartileRange,
+    Minimum,
+    Maximum,
+    // etc.
+}
+
+/// A probability forecast, e.g. "prob >0.254:prob fcst 255/255" in a `.idx` file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Probability {
+    pub probability_type: CodeValue<ProbabilityType>,
+
+    /// `None` if missing. Only some probability types use each limit.
+    pub lower_limit: Option<ScaledValue>,
+    pub upper_limit: Option<ScaledValue>,
+    pub forecast_probability_num: u8,
+    pub total_forecast_probabilities: u8,
+}
+
+/// Probability type (code table 4.9).
+#[derive(FromPrimitive, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ProbabilityType {
+    BelowLowerLimit = 0,
+    AboveUpperLimit,
+    BetweenLimits,
+    AboveLowerLimit,
+    BelowUpperLimit,
+    // etc.
+}
+
+impl Ensemble {
+    /// Read the ensemble fields which follow the template 4.0 fields, if `template_num` has any.
+    pub(crate) fn parse(section: &Section, template_num: u16) -> Result<Option<Self>, Error> {
+        let ensemble = match template_num {
+            1 | 11 | 41 | 43 | 45 | 47 => {
+                // The chemistry and aerosol templates insert their own fields before these.
+                let octet = match template_num {
+                    1 | 11 => 35,
+                    41 | 43 => 37,
+                    _ => 48,
+                };
+                Ensemble::Member(EnsembleMember {
+                    forecast_type: CodeValue::new(
+                        section.u8(octet)?,
+                        EnsembleForecastType::from_u8,
+                        |_| None,
+                    ),
+                    perturbation_num: section.u8(octet + 1)?,
+                })
+            }
+            2 | 12 => Ensemble::Derived(CodeValue::new(
+                section.u8(35)?,
+                DerivedForecast::from_u8,
+                |_| None,
+            )),
+            5 | 9 => Ensemble::Probability(Probability {
+                probability_type: CodeValue::new(section.u8(37)?, ProbabilityType::from_u8, |_| {
+                    None
+                }),
+                lower_limit: ScaledValue::parse(section, 38)?,
+                upper_limit: ScaledValue::parse(section, 43)?,
+                forecast_probability_num: section.u8(35)?,
+                total_forecast_probabilities: section.u8(36)?,
+            }),
+            _ => return Ok(None),
+        };
+        Ok(Some(ensemble))
+    }
+
+    /// The fields which `wgrib2 -s` prints after the step, e.g. `["ENS=+1"]`. Derived forecasts
+    /// which aren't in code table 4.7 are written as e.g. "derived=12".
+    pub(crate) fn to_idx_fields(self) -> Vec<String> {
+        match self {
+            Ensemble::Member(member) => vec![member.to_string()],
+            Ensemble::Derived(derived) => {
+                vec![derived
+                    .idx_str("derived", |derived| derived.idx_str())
+                    .into_owned()]
+            }
+            Ensemble::Probability(probability) => vec![
+                probability.to_string(),
+                format!(
+                    "prob fcst {}/{}",
+                    probability.forecast_probability_num, probability.total_forecast_probabilities
+                ),
+            ],
+        }
+    }
+
+    /// The inverse of `to_idx_fields`. `fields` are all the fields after the step, some of which
+    /// may have nothing to do with ensembles. Returns `Ok(None)` for deterministic forecasts, and
+    /// an error if a field looks like an ensemble field but we can't decode it.
+    pub(crate) fn from_idx_fields(fields: &[String]) -> Result<Option<Self>, &'static str> {
+        for (i, field) in fields.iter().enumerate() {
+            if let Some(member) = field.strip_prefix("ENS=") {
+                return EnsembleMember::from_idx_str(member)
+                    .map(|member| Some(Ensemble::Member(member)))
+                    .ok_or("unknown ensemble member");
+            }
+            if let Some(derived) = CodeValue::from_idx_str(
+                field,
+                "derived",
+                DerivedForecast::from_idx_str,
+                DerivedForecast::from_u8,
+            ) {
+                return Ok(Some(Ensemble::Derived(derived)));
+            }
+            if let Some(limits) = field.strip_prefix("prob ") {
+                if limits.starts_with("fcst ") {
+                    continue;
+                }
+                let (forecast_probability_num, total_forecast_probabilities) = fields[i + 1..]
+                    .iter()
+                    .find_map(|field| field.strip_prefix("prob fcst "))
+                    .and_then(|nums| nums.split_once('/'))
+                    .and_then(|(num, total)| Some((num.parse().ok()?, total.parse().ok()?)))
+                    .unwrap_or((u8::MAX, u8::MAX));
+                return Probability::from_idx_str(
+                    limits,
+                    forecast_probability_num,
+                    total_forecast_probabilities,
+                )
+                .map(|probability| Some(Ensemble::Probability(probability)))
+                .ok_or("unknown probability");
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl EnsembleMember {
+    fn from_idx_str(s: &str) -> Option<Self> {
+        if let Some(type_and_num) = s.strip_prefix("type=") {
+            let (type_num, perturbation_num) = type_and_num.split_once(" pert=")?;
+            return Some(Self {
+                forecast_type: CodeValue::new(
+                    type_num.parse().ok()?,
+                    EnsembleForecastType::from_u8,
+                    |_| None,
+                ),
+                perturbation_num: perturbation_num.parse().ok()?,
+            });
+        }
+        let (forecast_type, perturbation_num) = match s {
+            "hi-res ctl" => (EnsembleForecastType::HighResolutionControl, 0),
+            "low-res ctl" => (EnsembleForecastType::LowResolutionControl, 0),
+            _ => {
+                if let Some(num) = s.strip_prefix('-') {
+                    (EnsembleForecastType::NegativelyPerturbed, num.parse().ok()?)
+                } else if let Some(num) = s.strip_prefix('+') {
+                    (EnsembleForecastType::PositivelyPerturbed, num.parse().ok()?)
+                } else {
+                    let num = s.strip_prefix("multi-model ")?;
+                    (EnsembleForecastType::MultiModel, num.parse().ok()?)
+                }
+            }
+        };
+        Some(Self {
+            forecast_type: CodeValue::Known(forecast_type),
+            perturbation_num,
+        })
+    }
+}
+
+/// Formats ensemble members the same way as `wgrib2`, e.g. "ENS=low-res ctl" or "ENS=+1". Types
+/// which aren't in code table 4.6 are written as e.g. "ENS=type=7 pert=1".
+impl fmt::Display for EnsembleMember {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let num = self.perturbation_num;
+        match self.forecast_type.known_or_num() {
+            Ok(EnsembleForecastType::HighResolutionControl) => f.write_str("ENS=hi-res ctl"),
+            Ok(EnsembleForecastType::LowResolutionControl) => f.write_str("ENS=low-res ctl"),
+            Ok(EnsembleForecastType::NegativelyPerturbed) => write!(f, "ENS=-{num}"),
+            Ok(EnsembleForecastType::PositivelyPerturbed) => write!(f, "ENS=+{num}"),
+            Ok(EnsembleForecastType::MultiModel) => write!(f, "ENS=multi-model {num}"),
+            Err(type_num) => write!(f, "ENS=type={type_num} pert={num}"),
+        }
+    }
+}
+
+impl DerivedForecast {
+    /// The text `wgrib2` prints, e.g. "ens mean".
+    pub fn idx_str(&self) -> &'static str {
+        match self {
+            DerivedForecast::UnweightedMean => "ens mean",
+            DerivedForecast::WeightedMean => "wt ens mean",
+            DerivedForecast::StandardDeviation => "ens std dev",
+            DerivedForecast::NormalisedStandardDeviation => "normalized ens std dev",
+            DerivedForecast::Spread => "ens spread",
+            DerivedForecast::LargeAnomalyIndex => "ens large anomaly index",
+            DerivedForecast::UnweightedMeanOfCluster => "cluster mean",
+            DerivedForecast::InterquartileRange => "ens interquartile range",
+            DerivedForecast::Minimum => "ens min",
+            DerivedForecast::Maximum => "ens max",
+        }
+    }
+
+    fn from_idx_str(s: &str) -> Option<Self> {
+        (0..=u8::MAX)
+            .filter_map(DerivedForecast::from_u8)
+            .find(|derived| derived.idx_str() == s)
+    }
+}
+
+impl Probability {
+    fn from_idx_str(
+        limits: &str,
+        forecast_probability_num: u8,
+        total_forecast_probabilities: u8,
+    ) -> Option<Self> {
+        let limit = |value: &str| ScaledValue::from_f64(value.parse().ok()?);
+        let (probability_type, lower_limit, upper_limit) =
+            if let Some(type_and_limits) = limits.strip_prefix("type=") {
+                let optional_limit = |value| match value {
+                    "missing" => Some(None),
+                    value => limit(value).map(Some),
+                };
+                let (type_num, limits) = type_and_limits.split_once(" lower=")?;
+                let (lower, upper) = limits.split_once(" upper=")?;
+                (
+                    CodeValue::new(type_num.parse().ok()?, ProbabilityType::from_u8, |_| None),
+                    optional_limit(lower)?,
+                    optional_limit(upper)?,
+                )
+            } else if let Some((lower, upper)) = limits.split_once(' ') {
+                let lower = lower.strip_prefix(">=")?;
+                let upper = upper.strip_prefix('<')?;
+                (
+                    CodeValue::Known(ProbabilityType::BetweenLimits),
+                    Some(limit(lower)?),
+                    Some(limit(upper)?),
+                )
+            } else if let Some(upper) = limits.strip_prefix('>') {
+                // wgrib2 prints types 1 and 3 the same way. Type 1 ("above upper limit") is the
+                // one NCEP uses.
+                (
+                    CodeValue::Known(ProbabilityType::AboveUpperLimit),
+                    None,
+                    Some(limit(upper)?),
+                )
+            } else {
+                let lower = limits.strip_prefix('<')?;
+                (
+                    CodeValue::Known(ProbabilityType::BelowLowerLimit),
+                    Some(limit(lower)?),
+                    None,
+                )
+            };
+        Some(Self {
+            probability_type,
+            lower_limit,
+            upper_limit,
+            forecast_probability_num,
+            total_forecast_probabilities,
+        })
+    }
+}
+
+/// Formats probabilities the same way as `wgrib2`, e.g. "prob >0.254". Types which aren't in code
+/// table 4.9 are written with both limits, e.g. "prob type=7 lower=0.254 upper=missing".
+impl fmt::Display for Probability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let limit = |value: Option<ScaledValue>| match value {
+            Some(value) => format_g(value.value()),
+            None => "missing".to_string(),
+        };
+        let (lower, upper) = (limit(self.lower_limit), limit(self.upper_limit));
+        match self.probability_type.known_or_num() {
+            Ok(ProbabilityType::BelowLowerLimit) => write!(f, "prob <{lower}"),
+            Ok(ProbabilityType::AboveUpperLimit) => write!(f, "prob >{upper}"),
+            Ok(ProbabilityType::BetweenLimits) => write!(f, "prob >={lower} <{upper}"),
+            Ok(ProbabilityType::AboveLowerLimit) => write!(f, "prob >{lower}"),
+            Ok(ProbabilityType::BelowUpperLimit) => write!(f, "prob <{upper}"),
+            Err(type_num) => write!(f, "prob type={type_num} lower={lower} upper={upper}"),
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use alloc::string::String;
+use core::fmt;
+
+#[derive(Debug)]
+pub enum Error {
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
+
+    /// The bytes at `offset` don't start with "GRIB".
+    NotGrib {
+        offset: u64,
+    },
+
+    /// This crate only decodes GRIB edition 2 messages.
+    UnsupportedEdition {
+        offset: u64,
+        edition: u8,
+    },
+
+    /// A section (or a whole message) claims to be longer than the bytes available.
+    Truncated {
+        offset: u64,
+        expected_len: u64,
+        available_len: u64,
+    },
+
+    /// A section is malformed, or isn't where the GRIB2 spec says it should be.
+    InvalidSection {
+        offset: u64,
+        section_num: u8,
+        reason: &'static str,
+    },
+
+    /// A line of a `.idx` file isn't in the format written by `wgrib2 -s`.
+    InvalidIdxRecord {
+        line: String,
+        reason: &'static str,
+    },
+
+    /// A local table file (CSV or JSON) is malformed. `line` starts at 1.
+    InvalidLocalTable {
+        line: usize,
+        reason: String,
+    },
+
+    /// A WMO CSV code table is malformed. `line` starts at 1.
+    InvalidWmoTable {
+        line: usize,
+        reason: String,
+    },
+
+    /// The encoder can't write this field, e.g. because the number of values doesn't match the
+    /// grid.
+    CannotEncode {
+        reason: &'static str,
+    },
+
+    /// A dataset can't be exported, e.g. because the end of a message isn't known.
+    CannotExport {
+        reason: &'static str,
+    },
+
+    /// We don't know how to decode this template yet.
+    UnsupportedTemplate {
+        section_num: u8,
+        template_num: u16,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(feature = "std")]
+            Error::Io(err) => write!(f, "I/O error: {err}"),
+            Error::NotGrib { offset } => write!(f, "no GRIB message at byte {offset}"),
+            Error::UnsupportedEdition { offset, edition } => write!(
+                f,
+                "GRIB message at byte {offset} is edition {edition}, but only edition 2 is supported"
+            ),
+            Error::Truncated {
+                offset,
+                expected_len,
+                available_len,
+            } => write!(
+                f,
+                "truncated at byte {offset}: expected {expected_len} bytes, but only {available_len} are available"
+            ),
+            Error::InvalidSection {
+                offset,
+                section_num,
+                reason,
+            } => write!(f, "invalid section {section_num} at byte {offset}: {reason}"),
+            Error::InvalidIdxRecord { line, reason } => {
+                write!(f, "invalid .idx record \"{line}\": {reason}")
+            }
+            Error::InvalidLocalTable { line, reason } => {
+                write!(f, "invalid local table at line {line}: {reason}")
+            }
+            Error::InvalidWmoTable { line, reason } => {
+                write!(f, "invalid WMO table at line {line}: {reason}")
+            }
+            Error::CannotEncode { reason } => write!(f, "cannot encode field: {reason}"),
+            Error::CannotExport { reason } => write!(f, "cannot export dataset: {reason}"),
+            Error::UnsupportedTemplate {
+                section_num,
+                template_num,
+            } => write!(f, "template {section_num}.{template_num} is not supported"),
+        }
+    }
+}
+
+impl core::error::Error for Error {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            #[cfg(feature = "std")]
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::Io(err)
+    }
+}
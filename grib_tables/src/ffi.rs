@@ -0,0 +1,257 @@
+//! A C API, for tools written in C or Fortran.
+//!
+//! Build the shared library with `cargo build --release --features ffi`. The header,
+//! `include/grib_tables.h`, is generated with `cbindgen` (see `cbindgen.toml`), and
+//! `tests/ffi_header.rs` fails if it's out of date.
+//!
+//! Every pointer returned by this API is owned by the caller, and must be freed with the matching
+//! `grib_tables_*_free` function (and only that function). Strings are NUL-terminated UTF-8.
+
+use std::{
+    ffi::{c_char, CStr, CString},
+    ptr, slice,
+};
+
+use crate::{
+    center_and_table_versions::CenterAndTableVersions, idx::IdxRecord, product::Product,
+    AbbrevToProduct,
+};
+
+/// The numbers which identify a parameter in code table 4.2.
+#[repr(C)]
+pub struct GribTablesProductNumbers {
+    pub discipline: u8,
+    pub category: u8,
+    pub parameter: u8,
+}
+
+/// A parameter's abbreviation (e.g. "TMP"), name and unit. Free with
+/// `grib_tables_product_free`.
+#[repr(C)]
+pub struct GribTablesProduct {
+    pub abbrev: *mut c_char,
+    pub name: *mut c_char,
+    pub unit: *mut c_char,
+}
+
+/// One line of a `.idx` file.
+#[repr(C)]
+pub struct GribTablesIdxRecord {
+    pub message_num: u32,
+
+    /// 0 unless the message holds more than one field, e.g. the `2` in `4.2`.
+    pub field_num: u32,
+
+    /// The position of the start of the message, in bytes from the start of the GRIB2 file.
+    pub offset: u64,
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub abbrev: *mut c_char,
+    pub level: *mut c_char,
+    pub step: *mut c_char,
+
+    /// Any fields after the step (e.g. `ENS=+1`), separated by colons. Empty if there are none.
+    pub extra: *mut c_char,
+}
+
+/// The records of a `.idx` file, in order. Free with `grib_tables_idx_records_free`.
+#[repr(C)]
+pub struct GribTablesIdxRecords {
+    pub records: *mut GribTablesIdxRecord,
+    pub len: usize,
+}
+
+/// Look up the numbers of the parameter abbreviated `abbrev` (e.g. "TMP") in the tables of the
+/// given center, sub-centre and table versions (as found in section 1). Returns false, and leaves
+/// `numbers` untouched, if the abbreviation isn't known.
+///
+/// # Safety
+///
+/// `abbrev` must be a NUL-terminated string, and `numbers` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn grib_tables_abbrev_to_numbers(
+    abbrev: *const c_char,
+    center_num: u16,
+    subcenter: u16,
+    local_table_version: u8,
+    master_table_version: u8,
+    numbers: *mut GribTablesProductNumbers,
+) -> bool {
+    if abbrev.is_null() || numbers.is_null() {
+        return false;
+    }
+    let Ok(abbrev) = CStr::from_ptr(abbrev).to_str() else {
+        return false;
+    };
+    let center_and_table_versions = CenterAndTableVersions::from_numbers(
+        center_num,
+        subcenter,
+        local_table_version,
+        master_table_version,
+    );
+    let Some(product) = center_and_table_versions.abbrev_to_product(abbrev) else {
+        return false;
+    };
+    numbers.write(GribTablesProductNumbers {
+        discipline: product.discipline_num(),
+        category: product.category_num(),
+        parameter: product.parameter_num(),
+    });
+    true
+}
+
+/// Look up the parameter with the given numbers, in the tables of the given center, sub-centre and
+/// table versions (as found in section 1). Returns NULL if the parameter isn't known.
+#[no_mangle]
+pub extern "C" fn grib_tables_product(
+    discipline: u8,
+    category: u8,
+    parameter: u8,
+    center_num: u16,
+    subcenter: u16,
+    local_table_version: u8,
+    master_table_version: u8,
+) -> *mut GribTablesProduct {
+    let center_and_table_versions = CenterAndTableVersions::from_numbers(
+        center_num,
+        subcenter,
+        local_table_version,
+        master_table_version,
+    );
+    Product::from_discipline_and_category_and_parameter_numbers(
+        discipline,
+        category,
+        parameter,
+        center_and_table_versions,
+    )
+    .known()
+    .map_or(ptr::null_mut(), |product| {
+        Box::into_raw(Box::new(GribTablesProduct {
+            abbrev: c_string(product.abbrev()),
+            name: c_string(product.name()),
+            unit: c_string(product.unit()),
+        }))
+    })
+}
+
+/// Free a product returned by `grib_tables_product`.
+///
+/// # Safety
+///
+/// `product` must be NULL or have been returned by `grib_tables_product`, and mustn't be used
+/// afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn grib_tables_product_free(product: *mut GribTablesProduct) {
+    if product.is_null() {
+        return;
+    }
+    let product = Box::from_raw(product);
+    grib_tables_string_free(product.abbrev);
+    grib_tables_string_free(product.name);
+    grib_tables_string_free(product.unit);
+}
+
+/// Parse the contents of a `.idx` file (`len` bytes of UTF-8, which needn't be NUL-terminated).
+/// Blank lines are skipped.
+///
+/// Returns NULL if any line isn't a valid `.idx` record. If so, and `error` isn't NULL, `*error`
+/// is set to a description of the problem, which must be freed with `grib_tables_string_free`.
+///
+/// # Safety
+///
+/// `idx` must be valid for reads of `len` bytes, and `error` must be NULL or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn grib_tables_idx_parse(
+    idx: *const c_char,
+    len: usize,
+    error: *mut *mut c_char,
+) -> *mut GribTablesIdxRecords {
+    let fail = |reason: &str| {
+        if !error.is_null() {
+            error.write(c_string(reason));
+        }
+        ptr::null_mut()
+    };
+    if idx.is_null() {
+        return fail("idx is NULL");
+    }
+    let Ok(idx) = std::str::from_utf8(slice::from_raw_parts(idx.cast(), len)) else {
+        return fail("idx isn't valid UTF-8");
+    };
+    let records = idx
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(IdxRecord::parse)
+        .collect::<Result<Vec<_>, _>>();
+    let records: Box<[GribTablesIdxRecord]> = match records {
+        Ok(records) => records.iter().map(GribTablesIdxRecord::new).collect(),
+        Err(err) => return fail(&err.to_string()),
+    };
+    let len = records.len();
+    Box::into_raw(Box::new(GribTablesIdxRecords {
+        records: Box::into_raw(records).cast(),
+        len,
+    }))
+}
+
+/// Free the records returned by `grib_tables_idx_parse`, including their strings.
+///
+/// # Safety
+///
+/// `records` must be NULL or have been returned by `grib_tables_idx_parse`, and mustn't be used
+/// afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn grib_tables_idx_records_free(records: *mut GribTablesIdxRecords) {
+    if records.is_null() {
+        return;
+    }
+    let records = Box::from_raw(records);
+    let records = Box::from_raw(ptr::slice_from_raw_parts_mut(records.records, records.len));
+    for record in records.iter() {
+        grib_tables_string_free(record.abbrev);
+        grib_tables_string_free(record.level);
+        grib_tables_string_free(record.step);
+        grib_tables_string_free(record.extra);
+    }
+}
+
+/// Free a string returned by this API, e.g. an error from `grib_tables_idx_parse`.
+///
+/// # Safety
+///
+/// `string` must be NULL or have been returned by this API (and not already freed as part of a
+/// struct), and mustn't be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn grib_tables_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+impl GribTablesIdxRecord {
+    fn new(record: &IdxRecord) -> Self {
+        let time = &record.reference_time;
+        Self {
+            message_num: record.message_num,
+            field_num: record.field_num.unwrap_or(0),
+            offset: record.offset,
+            year: time.year,
+            month: time.month,
+            day: time.day,
+            hour: time.hour,
+            abbrev: c_string(&record.abbrev),
+            level: c_string(&record.level),
+            step: c_string(&record.step),
+            extra: c_string(&record.extra.join(":")),
+        }
+    }
+}
+
+/// `s` can't contain a NUL: `.idx` lines are split from text and the tables are ours. Just in
+/// case, anything after a NUL is dropped.
+fn c_string(s: &str) -> *mut c_char {
+    let s = s.split('\0').next().unwrap_or_default();
+    CString::new(s).unwrap_or_default().into_raw()
+}
@@ -0,0 +1,157 @@
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+
+use crate::{
+    center_and_table_versions::CenterAndTableVersions, code_table::CodeValue,
+    originating_center::OriginatingCenter,
+};
+
+/// How a field was made, and by which model, from section 4.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct GeneratingProcess {
+    pub type_of_generating_process: CodeValue<TypeOfGeneratingProcess>,
+    pub model: Model,
+}
+
+/// Type of generating process (code table 4.3).
+#[derive(FromPrimitive, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TypeOfGeneratingProcess {
+    Analysis = 0,
+    Initialization,
+    Forecast,
+    BiasCorrectedForecast,
+    EnsembleForecast,
+    ProbabilityForecast,
+    ForecastError,
+    AnalysisError,
+    Observation,
+    Climatological,
+    ProbabilityWeightedForecast,
+    BiasCorrectedEnsembleForecast,
+    PostProcessedAnalysis,
+    PostProcessedForecast,
+    Nowcast,
+    Hindcast,
+    PhysicalRetrieval,
+    RegressionAnalysis,
+    DifferenceBetweenTwoForecasts,
+    FirstGuess,
+    AnalysisIncrement,
+    InitializationIncrementForAnalysis,
+    // etc.
+
+    // NCEP local:
+    NcepForecastConfidenceIndicator = 192,
+    NcepProbabilityMatchedMean,
+    NcepNeighborhoodProbability,
+    NcepBiasCorrectedAndDownscaledEnsembleForecast,
+    NcepPerturbedAnalysisForEnsembleInitialization,
+    NcepEnsembleAgreementScaleProbability,
+    NcepPostProcessedDeterministicExpertWeightedForecast,
+    NcepEnsembleForecastBasedOnCounting,
+    // etc.
+}
+
+impl TypeOfGeneratingProcess {
+    pub(crate) fn from_num(
+        num: u8,
+        center_and_table_versions: &CenterAndTableVersions,
+    ) -> CodeValue<Self> {
+        CodeValue::new(
+            num,
+            TypeOfGeneratingProcess::from_u8,
+            |num| match center_and_table_versions.originating_center() {
+                OriginatingCenter::Ncep { .. } => TypeOfGeneratingProcess::from_u8(num),
+                OriginatingCenter::Other { .. } => None,
+            },
+        )
+    }
+}
+
+/// The model which made a field. Each center numbers its own models, in section 4's "analysis or
+/// forecast generating process identifier".
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Model {
+    Ncep(NcepModel),
+
+    /// A model which isn't in any table we know.
+    Unknown {
+        center_num: u16,
+        process_num: u8,
+    },
+    Missing,
+}
+
+impl Model {
+    pub(crate) fn from_num(
+        process_num: u8,
+        center_and_table_versions: &CenterAndTableVersions,
+    ) -> Self {
+        if process_num == u8::MAX {
+            return Model::Missing;
+        }
+        let center = center_and_table_versions.originating_center();
+        let model = match center {
+            OriginatingCenter::Ncep { .. } => NcepModel::from_u8(process_num).map(Model::Ncep),
+            OriginatingCenter::Other { .. } => None,
+        };
+        model.unwrap_or(Model::Unknown {
+            center_num: center.center_num(),
+            process_num,
+        })
+    }
+}
+
+/// NCEP's generating processes and models (ON388 table A).
+#[derive(FromPrimitive, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NcepModel {
+    SeaSurfaceTemperatureAnalysis = 44,
+    GfsAnalysis = 81,
+    GdasAnalysis,
+    Hrrr,
+    Nam,
+    Rtofs,
+    WaveWatch3 = 88,
+    Nmm,
+    Gfs = 96,
+    Cfs = 98,
+    Rap = 105,
+    Gefs = 107,
+    Rtma = 109,
+    Sref = 113,
+    Naefs,
+    Urma = 118,
+    Href = 132,
+    Narr = 140,
+    Ldas,
+    Cfsr = 199,
+    // etc.
+}
+
+impl NcepModel {
+    /// A short name, e.g. to label a dataset.
+    pub fn name(&self) -> &'static str {
+        match self {
+            NcepModel::SeaSurfaceTemperatureAnalysis => "SST",
+            NcepModel::GfsAnalysis => "GFS analysis",
+            NcepModel::GdasAnalysis => "GDAS",
+            NcepModel::Hrrr => "HRRR",
+            NcepModel::Nam => "NAM",
+            NcepModel::Rtofs => "RTOFS",
+            NcepModel::WaveWatch3 => "WW3",
+            NcepModel::Nmm => "NMM",
+            NcepModel::Gfs => "GFS",
+            NcepModel::Cfs => "CFS",
+            NcepModel::Rap => "RAP",
+            NcepModel::Gefs => "GEFS",
+            NcepModel::Rtma => "RTMA",
+            NcepModel::Sref => "SREF",
+            NcepModel::Naefs => "NAEFS",
+            NcepModel::Urma => "URMA",
+            NcepModel::Href => "HREF",
+            NcepModel::Narr => "NARR",
+            NcepModel::Ldas => "LDAS",
+            NcepModel::Cfsr => "CFSR",
+        }
+    }
+}
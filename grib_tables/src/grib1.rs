@@ -0,0 +1,370 @@
+use crate::originating_center::OriginatingCenter;
+
+/// The GRIB edition 1 parameter tables ("table 2").
+///
+/// GRIB1 identifies a parameter by the originating center, the table 2 version number, and a
+/// parameter number. GRIB2 instead uses a discipline, a category and a parameter number. Each
+/// entry in these tables records the GRIB1 abbreviation alongside the GRIB2 numbers which describe
+/// the same quantity, so GRIB1 and GRIB2 parameters both decode into the same `Product`.
+///
+/// Where the GRIB1 and GRIB2 abbreviations differ (e.g. NCEP's GRIB1 `FRIME` became `RIME`),
+/// `Grib1Parameter::abbrev` keeps the GRIB1 spelling and `Product::abbrev` gives the GRIB2 one.
+///
+/// Some GRIB1 parameters map to GRIB2 disciplines or categories which this crate can't decode into
+/// a `Product` yet (e.g. land surface products). For those, the mapping is still recorded here.
+pub enum Grib1ParameterTable {
+    /// WMO's international table 2 (versions 1, 2 and 3). Parameters 128-254 are left for local
+    /// use, so centers we don't know about only get parameters 1-127.
+    Wmo,
+
+    /// NCEP's operational table 2 (versions 1, 2 and 3): WMO's table plus NCEP's parameters
+    /// 128-254.
+    Ncep2,
+
+    /// NCEP's table 129. Only holds NCEP-specific parameters.
+    Ncep129,
+
+    /// NCEP's table 130 (land surface). Parameters 1-127 are the same as the WMO table.
+    Ncep130,
+
+    /// NCEP's table 131 (used by NARR). Parameters 1-127 are the same as the WMO table, but many of
+    /// 128-254 differ from NCEP's operational table 2.
+    Ncep131,
+}
+
+pub struct Grib1Parameter {
+    /// The GRIB1 abbreviation, as printed by `wgrib`.
+    pub(crate) abbrev: &'static str,
+
+    /// The equivalent GRIB2 discipline number (code table 0.0).
+    pub(crate) discipline: u8,
+
+    /// The equivalent GRIB2 parameter category number (code table 4.1).
+    pub(crate) category: u8,
+
+    /// The equivalent GRIB2 parameter number (code table 4.2).
+    pub(crate) parameter: u8,
+}
+
+impl Grib1Parameter {
+    /// The GRIB1 abbreviation, as printed by `wgrib`. This can differ from the abbreviation of
+    /// the equivalent GRIB2 `Product`.
+    pub fn abbrev(&self) -> &'static str {
+        self.abbrev
+    }
+
+    /// The GRIB2 discipline, parameter category and parameter numbers of the same quantity.
+    pub fn grib2_numbers(&self) -> (u8, u8, u8) {
+        (self.discipline, self.category, self.parameter)
+    }
+
+    const fn new(abbrev: &'static str, discipline: u8, category: u8, parameter: u8) -> Self {
+        Self {
+            abbrev,
+            discipline,
+            category,
+            parameter,
+        }
+    }
+}
+
+impl Grib1ParameterTable {
+    /// `center_num` is from GRIB1 table 0 (octet 5 of the product definition section), and
+    /// `table_version` is the table 2 version number (octet 4).
+    pub fn from_center_and_table_version(center_num: u8, table_version: u8) -> Option<Self> {
+        match (
+            OriginatingCenter::from_center_num(center_num.into(), 0, 0),
+            table_version,
+        ) {
+            (OriginatingCenter::Ncep { .. }, 1..=3) => Some(Grib1ParameterTable::Ncep2),
+            (OriginatingCenter::Ncep { .. }, 129) => Some(Grib1ParameterTable::Ncep129),
+            (OriginatingCenter::Ncep { .. }, 130) => Some(Grib1ParameterTable::Ncep130),
+            (OriginatingCenter::Ncep { .. }, 131) => Some(Grib1ParameterTable::Ncep131),
+            (_, 1..=3) => Some(Grib1ParameterTable::Wmo),
+            _ => None,
+        }
+    }
+
+    /// The GRIB1 parameter numbered `parameter_num` (octet 9 of the product definition section).
+    pub fn parameter(&self, parameter_num: u8) -> Option<&'static Grib1Parameter> {
+        match self {
+            Grib1ParameterTable::Wmo => Self::wmo().get(&parameter_num),
+            Grib1ParameterTable::Ncep2 => Self::ncep_2()
+                .get(&parameter_num)
+                .or_else(|| Self::wmo().get(&parameter_num)),
+            Grib1ParameterTable::Ncep129 => Self::ncep_129().get(&parameter_num),
+            Grib1ParameterTable::Ncep130 => Self::ncep_130()
+                .get(&parameter_num)
+                .or_else(|| Self::wmo().get(&parameter_num)),
+            Grib1ParameterTable::Ncep131 => Self::ncep_131()
+                .get(&parameter_num)
+                .or_else(|| Self::wmo().get(&parameter_num)),
+        }
+    }
+
+    fn wmo() -> &'static phf::Map<u8, Grib1Parameter> {
+        static WMO: phf::Map<u8, Grib1Parameter> = phf::phf_map! {
+            1u8 => Grib1Parameter::new("PRES", 0, 3, 0),
+            2u8 => Grib1Parameter::new("PRMSL", 0, 3, 1),
+            3u8 => Grib1Parameter::new("PTEND", 0, 3, 2),
+            4u8 => Grib1Parameter::new("PVORT", 0, 2, 14),
+            5u8 => Grib1Parameter::new("ICAHT", 0, 3, 3),
+            6u8 => Grib1Parameter::new("GP", 0, 3, 4),
+            7u8 => Grib1Parameter::new("HGT", 0, 3, 5),
+            8u8 => Grib1Parameter::new("DIST", 0, 3, 6),
+            9u8 => Grib1Parameter::new("HSTDV", 0, 3, 7),
+            10u8 => Grib1Parameter::new("TOZNE", 0, 14, 0),
+            11u8 => Grib1Parameter::new("TMP", 0, 0, 0),
+            12u8 => Grib1Parameter::new("VTMP", 0, 0, 1),
+            13u8 => Grib1Parameter::new("POT", 0, 0, 2),
+            14u8 => Grib1Parameter::new("EPOT", 0, 0, 3),
+            15u8 => Grib1Parameter::new("TMAX", 0, 0, 4),
+            16u8 => Grib1Parameter::new("TMIN", 0, 0, 5),
+            17u8 => Grib1Parameter::new("DPT", 0, 0, 6),
+            18u8 => Grib1Parameter::new("DEPR", 0, 0, 7),
+            19u8 => Grib1Parameter::new("LAPR", 0, 0, 8),
+            20u8 => Grib1Parameter::new("VIS", 0, 19, 0),
+            25u8 => Grib1Parameter::new("TMPA", 0, 0, 9),
+            26u8 => Grib1Parameter::new("PRESA", 0, 3, 8),
+            27u8 => Grib1Parameter::new("GPA", 0, 3, 9),
+            31u8 => Grib1Parameter::new("WDIR", 0, 2, 0),
+            32u8 => Grib1Parameter::new("WIND", 0, 2, 1),
+            33u8 => Grib1Parameter::new("UGRD", 0, 2, 2),
+            34u8 => Grib1Parameter::new("VGRD", 0, 2, 3),
+            35u8 => Grib1Parameter::new("STRM", 0, 2, 4),
+            36u8 => Grib1Parameter::new("VPOT", 0, 2, 5),
+            37u8 => Grib1Parameter::new("MNTSF", 0, 2, 6),
+            38u8 => Grib1Parameter::new("SGCVV", 0, 2, 7),
+            39u8 => Grib1Parameter::new("VVEL", 0, 2, 8),
+            40u8 => Grib1Parameter::new("DZDT", 0, 2, 9),
+            41u8 => Grib1Parameter::new("ABSV", 0, 2, 10),
+            42u8 => Grib1Parameter::new("ABSD", 0, 2, 11),
+            43u8 => Grib1Parameter::new("RELV", 0, 2, 12),
+            44u8 => Grib1Parameter::new("RELD", 0, 2, 13),
+            51u8 => Grib1Parameter::new("SPFH", 0, 1, 0),
+            52u8 => Grib1Parameter::new("RH", 0, 1, 1),
+            53u8 => Grib1Parameter::new("MIXR", 0, 1, 2),
+            54u8 => Grib1Parameter::new("PWAT", 0, 1, 3),
+            55u8 => Grib1Parameter::new("VAPP", 0, 1, 4),
+            56u8 => Grib1Parameter::new("SATD", 0, 1, 5),
+            57u8 => Grib1Parameter::new("EVP", 0, 1, 6),
+            59u8 => Grib1Parameter::new("PRATE", 0, 1, 7),
+            61u8 => Grib1Parameter::new("APCP", 0, 1, 8),
+            62u8 => Grib1Parameter::new("NCPCP", 0, 1, 9),
+            63u8 => Grib1Parameter::new("ACPCP", 0, 1, 10),
+            64u8 => Grib1Parameter::new("SRWEQ", 0, 1, 12),
+            65u8 => Grib1Parameter::new("WEASD", 0, 1, 13),
+            66u8 => Grib1Parameter::new("SNOD", 0, 1, 11),
+            71u8 => Grib1Parameter::new("TCDC", 0, 6, 1),
+            78u8 => Grib1Parameter::new("SNOC", 0, 1, 14),
+            79u8 => Grib1Parameter::new("SNOL", 0, 1, 15),
+            81u8 => Grib1Parameter::new("LAND", 2, 0, 0),
+            83u8 => Grib1Parameter::new("SFCR", 2, 0, 1),
+            84u8 => Grib1Parameter::new("ALBDO", 0, 19, 1),
+            85u8 => Grib1Parameter::new("TSOIL", 2, 0, 2),
+            86u8 => Grib1Parameter::new("SOILM", 2, 0, 3),
+            87u8 => Grib1Parameter::new("VEG", 2, 0, 4),
+            91u8 => Grib1Parameter::new("ICEC", 10, 2, 0),
+            99u8 => Grib1Parameter::new("SNOM", 0, 1, 16),
+            121u8 => Grib1Parameter::new("LHTFL", 0, 0, 10),
+            122u8 => Grib1Parameter::new("SHTFL", 0, 0, 11),
+            // etc.
+        };
+        &WMO
+    }
+
+    fn ncep_2() -> &'static phf::Map<u8, Grib1Parameter> {
+        // Contains only NCEP's local parameters. Parameters 1-127 come from the WMO table.
+        static NCEP_2: phf::Map<u8, Grib1Parameter> = phf::phf_map! {
+            129u8 => Grib1Parameter::new("MSLMA", 0, 3, 198),
+            130u8 => Grib1Parameter::new("MSLET", 0, 3, 192),
+            131u8 => Grib1Parameter::new("LFTX", 0, 7, 192),
+            132u8 => Grib1Parameter::new("4LFTX", 0, 7, 193),
+            136u8 => Grib1Parameter::new("VWSH", 0, 2, 192),
+            140u8 => Grib1Parameter::new("CRAIN", 0, 1, 192),
+            141u8 => Grib1Parameter::new("CFRZR", 0, 1, 193),
+            142u8 => Grib1Parameter::new("CICEP", 0, 1, 194),
+            143u8 => Grib1Parameter::new("CSNOW", 0, 1, 195),
+            144u8 => Grib1Parameter::new("SOILW", 2, 0, 192),
+            145u8 => Grib1Parameter::new("PEVPR", 0, 1, 200),
+            147u8 => Grib1Parameter::new("U-GWD", 0, 3, 194),
+            148u8 => Grib1Parameter::new("V-GWD", 0, 3, 195),
+            153u8 => Grib1Parameter::new("CLWMR", 0, 1, 22),
+            // NCEP's local GRIB2 table also has O3MR (0.14.192), which we don't decode. WMO's
+            // ozone mixing ratio (0.14.1) is the same quantity.
+            154u8 => Grib1Parameter::new("O3MR", 0, 14, 1),
+            155u8 => Grib1Parameter::new("GFLUX", 2, 0, 193),
+            156u8 => Grib1Parameter::new("CIN", 0, 7, 7),
+            157u8 => Grib1Parameter::new("CAPE", 0, 7, 6),
+            158u8 => Grib1Parameter::new("TKE", 0, 19, 11),
+            172u8 => Grib1Parameter::new("MFLX", 0, 2, 193),
+            189u8 => Grib1Parameter::new("VPTMP", 0, 0, 15),
+            190u8 => Grib1Parameter::new("HLCY", 0, 7, 8),
+            196u8 => Grib1Parameter::new("USTM", 0, 2, 194),
+            197u8 => Grib1Parameter::new("VSTM", 0, 2, 195),
+            204u8 => Grib1Parameter::new("DSWRF", 0, 4, 192),
+            205u8 => Grib1Parameter::new("DLWRF", 0, 5, 192),
+            211u8 => Grib1Parameter::new("USWRF", 0, 4, 193),
+            212u8 => Grib1Parameter::new("ULWRF", 0, 5, 193),
+            214u8 => Grib1Parameter::new("CPRAT", 0, 1, 196),
+            221u8 => Grib1Parameter::new("HPBL", 0, 3, 196),
+            222u8 => Grib1Parameter::new("5WAVH", 0, 3, 193),
+            223u8 => Grib1Parameter::new("CNWAT", 2, 0, 196),
+            229u8 => Grib1Parameter::new("SNOHF", 0, 0, 192),
+            230u8 => Grib1Parameter::new("5WAVA", 0, 3, 197),
+            // etc.
+        };
+        &NCEP_2
+    }
+
+    fn ncep_129() -> &'static phf::Map<u8, Grib1Parameter> {
+        static NCEP_129: phf::Map<u8, Grib1Parameter> = phf::phf_map! {
+            131u8 => Grib1Parameter::new("FRAIN", 0, 1, 202),
+            132u8 => Grib1Parameter::new("FICE", 0, 6, 199),
+            133u8 => Grib1Parameter::new("FRIME", 0, 1, 203),
+            134u8 => Grib1Parameter::new("CUEFI", 0, 6, 194),
+            135u8 => Grib1Parameter::new("TCOND", 0, 6, 195),
+            136u8 => Grib1Parameter::new("TCOLW", 0, 6, 196),
+            137u8 => Grib1Parameter::new("TCOLI", 0, 6, 197),
+            138u8 => Grib1Parameter::new("TCOLR", 0, 1, 204),
+            139u8 => Grib1Parameter::new("TCOLS", 0, 1, 205),
+            140u8 => Grib1Parameter::new("TCOLC", 0, 6, 198),
+            141u8 => Grib1Parameter::new("PLPL", 0, 3, 200),
+            // etc.
+        };
+        &NCEP_129
+    }
+
+    fn ncep_130() -> &'static phf::Map<u8, Grib1Parameter> {
+        // Contains only the diff between table 130 and the WMO table.
+        static NCEP_130: phf::Map<u8, Grib1Parameter> = phf::phf_map! {
+            146u8 => Grib1Parameter::new("VEGT", 2, 0, 210),
+            150u8 => Grib1Parameter::new("SSTOR", 2, 0, 211),
+            151u8 => Grib1Parameter::new("LSOIL", 2, 0, 212),
+            152u8 => Grib1Parameter::new("EWATR", 2, 0, 213),
+            179u8 => Grib1Parameter::new("ACOND", 2, 0, 228),
+            200u8 => Grib1Parameter::new("EVCW", 2, 0, 229),
+            210u8 => Grib1Parameter::new("TRANS", 2, 0, 230),
+            // etc.
+        };
+        &NCEP_130
+    }
+
+    fn ncep_131() -> &'static phf::Map<u8, Grib1Parameter> {
+        // Contains only the diff between table 131 and the WMO table. Parameters with no GRIB2
+        // equivalent (MSLSA, PVMW and DRIP) are left out, as is ASNOW: its GRIB1 units (kg m-2)
+        // don't match those of GRIB2's total snowfall (m).
+        static NCEP_131: phf::Map<u8, Grib1Parameter> = phf::phf_map! {
+            130u8 => Grib1Parameter::new("MSLET", 0, 3, 192),
+            131u8 => Grib1Parameter::new("LFTX", 0, 7, 192),
+            132u8 => Grib1Parameter::new("4LFTX", 0, 7, 193),
+            134u8 => Grib1Parameter::new("PRESN", 0, 3, 212),
+            135u8 => Grib1Parameter::new("MCONV", 0, 1, 197),
+            136u8 => Grib1Parameter::new("VWSH", 0, 2, 192),
+            140u8 => Grib1Parameter::new("CRAIN", 0, 1, 192),
+            141u8 => Grib1Parameter::new("CFRZR", 0, 1, 193),
+            142u8 => Grib1Parameter::new("CICEP", 0, 1, 194),
+            143u8 => Grib1Parameter::new("CSNOW", 0, 1, 195),
+            144u8 => Grib1Parameter::new("SOILW", 2, 0, 192),
+            145u8 => Grib1Parameter::new("PEVPR", 0, 1, 200),
+            146u8 => Grib1Parameter::new("VEGT", 2, 0, 210),
+            147u8 => Grib1Parameter::new("BARET", 2, 3, 200),
+            148u8 => Grib1Parameter::new("AVSFT", 2, 3, 201),
+            149u8 => Grib1Parameter::new("RADT", 2, 3, 202),
+            150u8 => Grib1Parameter::new("SSTOR", 2, 0, 211),
+            151u8 => Grib1Parameter::new("LSOIL", 2, 0, 212),
+            152u8 => Grib1Parameter::new("EWATR", 2, 0, 213),
+            153u8 => Grib1Parameter::new("CLWMR", 0, 1, 22),
+            155u8 => Grib1Parameter::new("GFLUX", 2, 0, 193),
+            156u8 => Grib1Parameter::new("CIN", 0, 7, 7),
+            157u8 => Grib1Parameter::new("CAPE", 0, 7, 6),
+            158u8 => Grib1Parameter::new("TKE", 0, 19, 11),
+            159u8 => Grib1Parameter::new("MXSALB", 0, 19, 192),
+            160u8 => Grib1Parameter::new("SOILL", 2, 3, 192),
+            162u8 => Grib1Parameter::new("ARAIN", 0, 1, 221),
+            163u8 => Grib1Parameter::new("GWREC", 2, 0, 214),
+            164u8 => Grib1Parameter::new("QREC", 2, 0, 215),
+            165u8 => Grib1Parameter::new("SNOWT", 0, 1, 222),
+            166u8 => Grib1Parameter::new("VBDSF", 0, 4, 200),
+            167u8 => Grib1Parameter::new("VDDSF", 0, 4, 201),
+            168u8 => Grib1Parameter::new("NBDSF", 0, 4, 202),
+            169u8 => Grib1Parameter::new("NDDSF", 0, 4, 203),
+            170u8 => Grib1Parameter::new("SNFALB", 0, 19, 193),
+            171u8 => Grib1Parameter::new("RLYRS", 2, 3, 193),
+            172u8 => Grib1Parameter::new("MFLX", 0, 2, 193),
+            173u8 => Grib1Parameter::new("LMH", 0, 3, 210),
+            174u8 => Grib1Parameter::new("LMV", 0, 2, 218),
+            175u8 => Grib1Parameter::new("MLYNO", 0, 191, 195),
+            176u8 => Grib1Parameter::new("NLAT", 0, 191, 192),
+            177u8 => Grib1Parameter::new("ELON", 0, 191, 193),
+            178u8 => Grib1Parameter::new("ICMR", 0, 1, 23),
+            179u8 => Grib1Parameter::new("ACOND", 2, 0, 228),
+            180u8 => Grib1Parameter::new("SNOAG", 0, 1, 17),
+            181u8 => Grib1Parameter::new("CCOND", 2, 0, 199),
+            182u8 => Grib1Parameter::new("LAI", 0, 7, 198),
+            183u8 => Grib1Parameter::new("SFCRH", 2, 0, 216),
+            184u8 => Grib1Parameter::new("SALBD", 0, 19, 19),
+            187u8 => Grib1Parameter::new("NDVI", 2, 0, 217),
+            189u8 => Grib1Parameter::new("LANDN", 2, 0, 218),
+            190u8 => Grib1Parameter::new("HLCY", 0, 7, 8),
+            191u8 => Grib1Parameter::new("NLATN", 0, 191, 196),
+            192u8 => Grib1Parameter::new("ELONN", 0, 191, 197),
+            194u8 => Grib1Parameter::new("CPOFP", 0, 1, 39),
+            196u8 => Grib1Parameter::new("USTM", 0, 2, 194),
+            197u8 => Grib1Parameter::new("VSTM", 0, 2, 195),
+            198u8 => Grib1Parameter::new("SBSNO", 0, 1, 212),
+            199u8 => Grib1Parameter::new("EVBS", 2, 3, 198),
+            200u8 => Grib1Parameter::new("EVCW", 2, 0, 229),
+            202u8 => Grib1Parameter::new("APCPN", 0, 1, 223),
+            203u8 => Grib1Parameter::new("RSMIN", 2, 0, 200),
+            204u8 => Grib1Parameter::new("DSWRF", 0, 4, 192),
+            205u8 => Grib1Parameter::new("DLWRF", 0, 5, 192),
+            206u8 => Grib1Parameter::new("ACPCPN", 0, 1, 224),
+            207u8 => Grib1Parameter::new("MSTAV", 2, 0, 194),
+            208u8 => Grib1Parameter::new("SFEXC", 2, 0, 195),
+            210u8 => Grib1Parameter::new("TRANS", 2, 0, 230),
+            211u8 => Grib1Parameter::new("USWRF", 0, 4, 193),
+            212u8 => Grib1Parameter::new("ULWRF", 0, 5, 193),
+            213u8 => Grib1Parameter::new("CDLYR", 0, 6, 192),
+            214u8 => Grib1Parameter::new("CPRAT", 0, 1, 196),
+            216u8 => Grib1Parameter::new("TTRAD", 0, 0, 193),
+            218u8 => Grib1Parameter::new("HGTN", 0, 3, 211),
+            219u8 => Grib1Parameter::new("WILT", 2, 0, 201),
+            220u8 => Grib1Parameter::new("FLDCP", 2, 3, 203),
+            221u8 => Grib1Parameter::new("HPBL", 0, 3, 196),
+            222u8 => Grib1Parameter::new("SLTYP", 2, 3, 194),
+            223u8 => Grib1Parameter::new("CNWAT", 2, 0, 196),
+            224u8 => Grib1Parameter::new("SOTYP", 2, 3, 0),
+            225u8 => Grib1Parameter::new("VGTYP", 2, 0, 198),
+            226u8 => Grib1Parameter::new("BMIXL", 2, 0, 197),
+            227u8 => Grib1Parameter::new("AMIXL", 2, 0, 219),
+            228u8 => Grib1Parameter::new("PEVAP", 0, 1, 199),
+            229u8 => Grib1Parameter::new("SNOHF", 0, 0, 192),
+            230u8 => Grib1Parameter::new("SMREF", 2, 3, 195),
+            231u8 => Grib1Parameter::new("SMDRY", 2, 3, 196),
+            232u8 => Grib1Parameter::new("WVINC", 2, 0, 220),
+            233u8 => Grib1Parameter::new("WCINC", 2, 0, 221),
+            234u8 => Grib1Parameter::new("BGRUN", 1, 0, 192),
+            235u8 => Grib1Parameter::new("SSRUN", 1, 0, 193),
+            237u8 => Grib1Parameter::new("WVCONV", 2, 0, 222),
+            238u8 => Grib1Parameter::new("SNOWC", 0, 1, 201),
+            239u8 => Grib1Parameter::new("SNOT", 0, 1, 208),
+            240u8 => Grib1Parameter::new("POROS", 2, 3, 197),
+            241u8 => Grib1Parameter::new("WCCONV", 2, 0, 223),
+            242u8 => Grib1Parameter::new("WVUFLX", 2, 0, 224),
+            243u8 => Grib1Parameter::new("WVVFLX", 2, 0, 225),
+            244u8 => Grib1Parameter::new("WCUFLX", 2, 0, 226),
+            245u8 => Grib1Parameter::new("WCVFLX", 2, 0, 227),
+            246u8 => Grib1Parameter::new("RCS", 2, 0, 202),
+            247u8 => Grib1Parameter::new("RCT", 2, 0, 203),
+            248u8 => Grib1Parameter::new("RCQ", 2, 0, 204),
+            249u8 => Grib1Parameter::new("RCSOL", 2, 0, 205),
+            250u8 => Grib1Parameter::new("SWHR", 0, 4, 197),
+            251u8 => Grib1Parameter::new("LWHR", 0, 5, 194),
+            252u8 => Grib1Parameter::new("CD", 0, 2, 196),
+            253u8 => Grib1Parameter::new("FRICV", 0, 2, 197),
+            254u8 => Grib1Parameter::new("RI", 0, 7, 194),
+        };
+        &NCEP_131
+    }
+}
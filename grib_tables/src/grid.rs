@@ -0,0 +1,306 @@
+use crate::{
+    error::Error,
+    message::{Section, SectionWriter},
+};
+
+/// The grid which a field is defined on, from section 3 (the grid definition section).
+///
+/// Angles are in degrees. GRIB2 stores them in millionths of a degree, so they round-trip through
+/// a message exactly as long as they have at most 6 decimal places.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Grid {
+    /// Grid definition template 3.0.
+    LatLon(LatLonGrid),
+
+    /// Grid definition template 3.20.
+    PolarStereographic(PolarStereographicGrid),
+
+    /// Grid definition template 3.30.
+    LambertConformal(LambertConformalGrid),
+    // etc.
+}
+
+/// A regular latitude/longitude grid, also known as an equidistant cylindrical grid.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LatLonGrid {
+    /// Code table 3.2, e.g. 6 for a spherical Earth with a radius of 6,371,229 m.
+    pub shape_of_earth: u8,
+
+    /// The number of points along a parallel.
+    pub ni: u32,
+
+    /// The number of points along a meridian.
+    pub nj: u32,
+    pub first_lat: f64,
+    pub first_lon: f64,
+
+    /// Flag table 3.3.
+    pub resolution_and_component_flags: u8,
+    pub last_lat: f64,
+    pub last_lon: f64,
+
+    /// The increment between points along a parallel.
+    pub di: f64,
+
+    /// The increment between points along a meridian.
+    pub dj: f64,
+
+    /// Flag table 3.4.
+    pub scanning_mode: u8,
+}
+
+/// A polar stereographic grid, e.g. NCEP's grids over Alaska and the Arctic.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PolarStereographicGrid {
+    /// Code table 3.2.
+    pub shape_of_earth: u8,
+    pub nx: u32,
+    pub ny: u32,
+    pub first_lat: f64,
+    pub first_lon: f64,
+
+    /// Flag table 3.3.
+    pub resolution_and_component_flags: u8,
+
+    /// The latitude where `dx` and `dy` are specified.
+    pub lad: f64,
+
+    /// The longitude of the meridian which is parallel to the y axis.
+    pub lov: f64,
+
+    /// In metres. GRIB2 stores these in millimetres.
+    pub dx: f64,
+    pub dy: f64,
+
+    /// Flag table 3.5. Bit 1 is set if the south pole is on the projection plane.
+    pub projection_centre: u8,
+
+    /// Flag table 3.4.
+    pub scanning_mode: u8,
+}
+
+/// A Lambert conformal grid, e.g. the HRRR's.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LambertConformalGrid {
+    /// Code table 3.2.
+    pub shape_of_earth: u8,
+    pub nx: u32,
+    pub ny: u32,
+    pub first_lat: f64,
+    pub first_lon: f64,
+
+    /// Flag table 3.3. Says whether vector components are relative to the grid or to the Earth.
+    pub resolution_and_component_flags: u8,
+
+    /// The latitude where `dx` and `dy` are specified.
+    pub lad: f64,
+
+    /// The longitude of the meridian which is parallel to the y axis.
+    pub lov: f64,
+
+    /// In metres. GRIB2 stores these in millimetres.
+    pub dx: f64,
+    pub dy: f64,
+
+    /// Flag table 3.5.
+    pub projection_centre: u8,
+
+    /// Flag table 3.4.
+    pub scanning_mode: u8,
+
+    /// The latitudes where the secant cone cuts the sphere. Equal for a tangent cone.
+    pub latin1: f64,
+    pub latin2: f64,
+    pub south_pole_lat: f64,
+    pub south_pole_lon: f64,
+}
+
+impl Grid {
+    pub(crate) fn parse(section: &Section) -> Result<Self, Error> {
+        let template_num = section.u16(13)?;
+        match template_num {
+            0 => Ok(Grid::LatLon(LatLonGrid {
+                shape_of_earth: section.u8(15)?,
+                ni: section.u32(31)?,
+                nj: section.u32(35)?,
+                first_lat: degrees(section, 47)?,
+                first_lon: degrees(section, 51)?,
+                resolution_and_component_flags: section.u8(55)?,
+                last_lat: degrees(section, 56)?,
+                last_lon: degrees(section, 60)?,
+                di: degrees(section, 64)?,
+                dj: degrees(section, 68)?,
+                scanning_mode: section.u8(72)?,
+            })),
+            20 => Ok(Grid::PolarStereographic(PolarStereographicGrid {
+                shape_of_earth: section.u8(15)?,
+                nx: section.u32(31)?,
+                ny: section.u32(35)?,
+                first_lat: degrees(section, 39)?,
+                first_lon: degrees(section, 43)?,
+                resolution_and_component_flags: section.u8(47)?,
+                lad: degrees(section, 48)?,
+                lov: degrees(section, 52)?,
+                dx: metres(section, 56)?,
+                dy: metres(section, 60)?,
+                projection_centre: section.u8(64)?,
+                scanning_mode: section.u8(65)?,
+            })),
+            30 => Ok(Grid::LambertConformal(LambertConformalGrid {
+                shape_of_earth: section.u8(15)?,
+                nx: section.u32(31)?,
+                ny: section.u32(35)?,
+                first_lat: degrees(section, 39)?,
+                first_lon: degrees(section, 43)?,
+                resolution_and_component_flags: section.u8(47)?,
+                lad: degrees(section, 48)?,
+                lov: degrees(section, 52)?,
+                dx: metres(section, 56)?,
+                dy: metres(section, 60)?,
+                projection_centre: section.u8(64)?,
+                scanning_mode: section.u8(65)?,
+                latin1: degrees(section, 66)?,
+                latin2: degrees(section, 70)?,
+                south_pole_lat: degrees(section, 74)?,
+                south_pole_lon: degrees(section, 78)?,
+            })),
+            // etc.
+            _ => Err(Error::UnsupportedTemplate {
+                section_num: 3,
+                template_num,
+            }),
+        }
+    }
+
+    /// The number of points along the i (or x) axis and along the j (or y) axis.
+    pub fn dimensions(&self) -> (u32, u32) {
+        match self {
+            Grid::LatLon(grid) => (grid.ni, grid.nj),
+            Grid::PolarStereographic(grid) => (grid.nx, grid.ny),
+            Grid::LambertConformal(grid) => (grid.nx, grid.ny),
+        }
+    }
+
+    /// The number of points in the grid.
+    pub fn num_points(&self) -> u64 {
+        let (ni, nj) = self.dimensions();
+        u64::from(ni) * u64::from(nj)
+    }
+
+    /// Write the whole of section 3.
+    pub(crate) fn write(&self) -> Result<Vec<u8>, Error> {
+        let num_points = u32::try_from(self.num_points()).map_err(|_| Error::CannotEncode {
+            reason: "the grid has more than 2^32 points",
+        })?;
+        let template_num = match self {
+            Grid::LatLon(_) => 0,
+            Grid::PolarStereographic(_) => 20,
+            Grid::LambertConformal(_) => 30,
+        };
+        let mut section = SectionWriter::new(3);
+        section
+            .u8(0) // Source of grid definition: code table 3.1.
+            .u32(num_points)
+            .u8(0) // No list of numbers of points.
+            .u8(0)
+            .u16(template_num);
+        match self {
+            Grid::LatLon(grid) => {
+                write_shape_of_earth(&mut section, grid.shape_of_earth)?;
+                section
+                    .u32(grid.ni)
+                    .u32(grid.nj)
+                    .u32(0) // Basic angle of the initial production domain.
+                    .u32(u32::MAX); // Subdivisions of basic angle: missing.
+                write_degrees(&mut section, grid.first_lat)?;
+                write_degrees(&mut section, grid.first_lon)?;
+                section.u8(grid.resolution_and_component_flags);
+                for degrees in [grid.last_lat, grid.last_lon, grid.di, grid.dj] {
+                    write_degrees(&mut section, degrees)?;
+                }
+                section.u8(grid.scanning_mode);
+            }
+            Grid::PolarStereographic(grid) => {
+                write_shape_of_earth(&mut section, grid.shape_of_earth)?;
+                section.u32(grid.nx).u32(grid.ny);
+                write_degrees(&mut section, grid.first_lat)?;
+                write_degrees(&mut section, grid.first_lon)?;
+                section.u8(grid.resolution_and_component_flags);
+                write_degrees(&mut section, grid.lad)?;
+                write_degrees(&mut section, grid.lov)?;
+                write_metres(&mut section, grid.dx)?;
+                write_metres(&mut section, grid.dy)?;
+                section.u8(grid.projection_centre).u8(grid.scanning_mode);
+            }
+            Grid::LambertConformal(grid) => {
+                write_shape_of_earth(&mut section, grid.shape_of_earth)?;
+                section.u32(grid.nx).u32(grid.ny);
+                write_degrees(&mut section, grid.first_lat)?;
+                write_degrees(&mut section, grid.first_lon)?;
+                section.u8(grid.resolution_and_component_flags);
+                write_degrees(&mut section, grid.lad)?;
+                write_degrees(&mut section, grid.lov)?;
+                write_metres(&mut section, grid.dx)?;
+                write_metres(&mut section, grid.dy)?;
+                section.u8(grid.projection_centre).u8(grid.scanning_mode);
+                for degrees in [
+                    grid.latin1,
+                    grid.latin2,
+                    grid.south_pole_lat,
+                    grid.south_pole_lon,
+                ] {
+                    write_degrees(&mut section, degrees)?;
+                }
+            }
+        }
+        Ok(section.finish())
+    }
+}
+
+/// Read an angle stored in millionths of a degree.
+fn degrees(section: &Section, octet: usize) -> Result<f64, Error> {
+    Ok(f64::from(section.i32(octet)?) / 1e6)
+}
+
+/// Read a distance stored in millimetres.
+fn metres(section: &Section, octet: usize) -> Result<f64, Error> {
+    Ok(f64::from(section.u32(octet)?) / 1e3)
+}
+
+fn write_degrees(section: &mut SectionWriter, degrees: f64) -> Result<(), Error> {
+    let micro_degrees = (degrees * 1e6).round();
+    if micro_degrees.abs() > f64::from(i32::MAX) || micro_degrees.is_nan() {
+        return Err(Error::CannotEncode {
+            reason: "an angle in the grid definition is out of range",
+        });
+    }
+    section.i32(micro_degrees as i32);
+    Ok(())
+}
+
+fn write_metres(section: &mut SectionWriter, metres: f64) -> Result<(), Error> {
+    let millimetres = (metres * 1e3).round();
+    if !(0.0..=f64::from(u32::MAX)).contains(&millimetres) {
+        return Err(Error::CannotEncode {
+            reason: "a grid length is out of range",
+        });
+    }
+    section.u32(millimetres as u32);
+    Ok(())
+}
+
+/// Write the shape of the Earth, followed by its radius and axes, which are all missing: we only
+/// write shapes whose size is implied by the code table.
+fn write_shape_of_earth(section: &mut SectionWriter, shape_of_earth: u8) -> Result<(), Error> {
+    if matches!(shape_of_earth, 1 | 3 | 7) {
+        return Err(Error::CannotEncode {
+            reason:
+                "shapes of the Earth with a radius or axes given in the message aren't supported",
+        });
+    }
+    section.u8(shape_of_earth);
+    for _ in 0..3 {
+        section.u8(u8::MAX).u32(u32::MAX);
+    }
+    Ok(())
+}
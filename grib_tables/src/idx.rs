@@ -0,0 +1,256 @@
+use std::{
+    fmt,
+    io::{Read, Seek, Write},
+};
+
+use crate::{
+    code_table::CodeValue,
+    constituent::Constituent,
+    ensemble::Ensemble,
+    error::Error,
+    message::{Field, Message, ReferenceTime},
+    product::Product,
+    product_definition::ProductDefinition,
+    reader::{self, MessageReader},
+};
+
+/// One record (one line) of a `.idx` inventory, in the format written by `wgrib2 -s`. e.g.
+///
+/// `4:1234567:d=2024010100:TMP:2 m above ground:6 hour fcst:`
+///
+/// The level and step are kept as the text which `wgrib2` prints, so that records round-trip
+/// exactly.
+pub(crate) struct IdxRecord {
+    pub(crate) message_num: u32,
+
+    /// Only set when the message holds more than one field, e.g. the `2` in `4.2`.
+    pub(crate) field_num: Option<u32>,
+
+    /// The position of the start of the message, in bytes from the start of the file.
+    pub(crate) offset: u64,
+    pub(crate) reference_time: ReferenceTime,
+    pub(crate) abbrev: String,
+    pub(crate) level: String,
+    pub(crate) step: String,
+
+    /// Any fields after the step, e.g. `ENS=+1` or `aerosol=Dust Dry`.
+    pub(crate) extra: Vec<String>,
+}
+
+impl IdxRecord {
+    /// One record per field in `message`. `message_num` starts at 1.
+    pub(crate) fn from_message(message: &Message, message_num: u32) -> Result<Vec<Self>, Error> {
+        message
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(i, field)| Self::from_field(message, message_num, i, field))
+            .collect()
+    }
+
+    /// The record for the `i`th field (starting at 0) of `message`.
+    fn from_field(
+        message: &Message,
+        message_num: u32,
+        i: usize,
+        field: &Field,
+    ) -> Result<Self, Error> {
+        let product_definition =
+            ProductDefinition::parse(&field.product_definition, field.reference_time)?;
+        let abbrev = match Product::from_discipline_and_category_and_parameter_numbers(
+            message.indicator().discipline,
+            product_definition.category,
+            product_definition.parameter,
+            message.identification().center_and_table_versions(),
+        ) {
+            CodeValue::Known(product) => product.abbrev().to_string(),
+            _ => Self::unknown_abbrev(
+                message,
+                product_definition.category,
+                product_definition.parameter,
+            ),
+        };
+        Ok(Self {
+            message_num,
+            field_num: (message.fields().len() > 1).then_some(i as u32 + 1),
+            offset: message.offset(),
+            reference_time: message.identification().reference_time,
+            abbrev,
+            level: product_definition.level.to_string(),
+            step: product_definition.step.to_string(),
+            extra: product_definition
+                .constituent
+                .map_or_else(Vec::new, Constituent::to_idx_fields)
+                .into_iter()
+                .chain(
+                    product_definition
+                        .ensemble
+                        .map_or_else(Vec::new, Ensemble::to_idx_fields),
+                )
+                .collect(),
+        })
+    }
+
+    /// A record for a field whose product definition we can't decode (e.g. an unsupported
+    /// template), so that the inventory still lists it. Only the parameter numbers are read, and
+    /// the level and step are left empty.
+    fn fallback(
+        message: &Message,
+        message_num: u32,
+        i: usize,
+        field: &Field,
+    ) -> Result<Self, Error> {
+        // Octets 10 and 11 hold the parameter category and number in every template.
+        let category = field.product_definition.u8(10)?;
+        let parameter = field.product_definition.u8(11)?;
+        Ok(Self {
+            message_num,
+            field_num: (message.fields().len() > 1).then_some(i as u32 + 1),
+            offset: message.offset(),
+            reference_time: message.identification().reference_time,
+            abbrev: Self::unknown_abbrev(message, category, parameter),
+            level: String::new(),
+            step: String::new(),
+            extra: Vec::new(),
+        })
+    }
+
+    /// What `wgrib2` prints for parameters it doesn't know.
+    fn unknown_abbrev(message: &Message, category: u8, parameter: u8) -> String {
+        let identification = message.identification();
+        format!(
+            "var discipline={} center={} local_table={} parmcat={category} parm={parameter}",
+            message.indicator().discipline,
+            identification.center,
+            identification.local_table_version,
+        )
+    }
+
+    /// Parse one line of a `.idx` file written by `wgrib2 -s`.
+    pub(crate) fn parse(line: &str) -> Result<Self, Error> {
+        let invalid = |reason| Error::InvalidIdxRecord {
+            line: line.to_string(),
+            reason,
+        };
+        let line_without_newline = line.trim_end_matches(['\r', '\n']);
+        let mut fields: Vec<&str> = line_without_newline.split(':').collect();
+        if fields.last() == Some(&"") {
+            fields.pop();
+        }
+        let [num, offset, date, abbrev, level, step, extra @ ..] = fields.as_slice() else {
+            return Err(invalid("expected at least 6 colon-separated fields"));
+        };
+
+        let (message_num, field_num) = match num.split_once('.') {
+            Some((message_num, field_num)) => (message_num, Some(field_num)),
+            None => (*num, None),
+        };
+        let message_num = message_num
+            .parse()
+            .map_err(|_| invalid("invalid message number"))?;
+        let field_num = field_num
+            .map(|field_num| field_num.parse())
+            .transpose()
+            .map_err(|_| invalid("invalid field number"))?;
+        let offset = offset.parse().map_err(|_| invalid("invalid offset"))?;
+        let reference_time =
+            ReferenceTime::from_idx_str(date).ok_or_else(|| invalid("expected d=YYYYMMDDHH"))?;
+
+        Ok(Self {
+            message_num,
+            field_num,
+            offset,
+            reference_time,
+            abbrev: abbrev.to_string(),
+            level: level.to_string(),
+            step: step.to_string(),
+            extra: extra.iter().map(|extra| extra.to_string()).collect(),
+        })
+    }
+}
+
+impl fmt::Display for IdxRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message_num)?;
+        if let Some(field_num) = self.field_num {
+            write!(f, ".{field_num}")?;
+        }
+        let t = &self.reference_time;
+        write!(
+            f,
+            ":{}:d={:04}{:02}{:02}{:02}:{}:{}:{}:",
+            self.offset, t.year, t.month, t.day, t.hour, self.abbrev, self.level, self.step
+        )?;
+        for extra in &self.extra {
+            write!(f, "{extra}:")?;
+        }
+        Ok(())
+    }
+}
+
+/// Write an inventory of every field in `grib` (the bytes of a whole GRIB2 file), line-for-line
+/// compatible with `wgrib2 -s`.
+///
+/// A field whose product definition can't be decoded (e.g. one using a template we don't
+/// support) still gets a line, with its parameter numbers and an empty level and step.
+pub fn write_inventory<W: Write>(grib: &[u8], mut writer: W) -> Result<(), Error> {
+    for (i, message) in reader::messages(grib).enumerate() {
+        write_message_inventory(&message?, i as u32 + 1, &mut writer)?;
+    }
+    Ok(())
+}
+
+/// Like [`write_inventory`], but reads one message at a time from `reader`.
+pub fn write_inventory_from_reader<R: Read + Seek, W: Write>(
+    reader: R,
+    mut writer: W,
+) -> Result<(), Error> {
+    for (i, message_buf) in MessageReader::new(reader).enumerate() {
+        write_message_inventory(&message_buf?.message(), i as u32 + 1, &mut writer)?;
+    }
+    Ok(())
+}
+
+fn write_message_inventory<W: Write>(
+    message: &Message,
+    message_num: u32,
+    writer: &mut W,
+) -> Result<(), Error> {
+    for (i, field) in message.fields().iter().enumerate() {
+        let record = IdxRecord::from_field(message, message_num, i, field)
+            .or_else(|_| IdxRecord::fallback(message, message_num, i, field))?;
+        writeln!(writer, "{record}")?;
+    }
+    Ok(())
+}
+
+/// Format a number the same way as C's `printf("%g")`, which is what `wgrib2` uses for levels.
+pub(crate) fn format_g(value: f64) -> String {
+    const PRECISION: i32 = 6;
+    if value == 0.0 {
+        return "0".to_string();
+    }
+    let exponent = value.abs().log10().floor() as i32;
+    if !(-4..PRECISION).contains(&exponent) {
+        let formatted = format!("{:.*e}", (PRECISION - 1) as usize, value);
+        let (mantissa, exponent) = formatted.split_once('e').unwrap();
+        let exponent: i32 = exponent.parse().unwrap();
+        let sign = if exponent < 0 { '-' } else { '+' };
+        format!(
+            "{}e{sign}{:02}",
+            trim_trailing_zeros(mantissa),
+            exponent.abs()
+        )
+    } else {
+        let decimals = (PRECISION - 1 - exponent).max(0) as usize;
+        trim_trailing_zeros(&format!("{value:.decimals$}")).to_string()
+    }
+}
+
+fn trim_trailing_zeros(number: &str) -> &str {
+    if number.contains('.') {
+        number.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        number
+    }
+}
@@ -0,0 +1,187 @@
+//! Select records from a `.idx` inventory, and find the byte ranges of the GRIB2 file to fetch,
+//! like `wgrib2 -match` or Herbie's `searchString`.
+
+use std::ops::RangeInclusive;
+
+use crate::{ensemble::Ensemble, error::Error, idx::IdxRecord, level::Level, step::Step};
+
+/// The parsed `.idx` inventory of one GRIB2 file.
+pub struct Inventory {
+    entries: Vec<Entry>,
+}
+
+struct Entry {
+    line: String,
+    record: IdxRecord,
+    range: ByteRange,
+}
+
+/// Which records to select. Each filter which is empty (or `None`) matches every record, so
+/// `Query::default()` matches everything. e.g. "UGRD and VGRD at 10 m above ground for steps 0 to
+/// 48 hours" is one query, and adding "TMP at 2 m above ground" needs a second query.
+#[derive(Clone, Debug, Default)]
+pub struct Query {
+    /// Product abbreviations, e.g. "TMP".
+    pub abbrevs: Vec<String>,
+    pub levels: Vec<Level>,
+
+    /// Matches steps which end within this range of hours, e.g. a "6 hour fcst" or a "0-6 hour
+    /// acc fcst" for `6..=6`. Steps in calendar units (months and longer) never match.
+    pub forecast_hours: Option<RangeInclusive<u32>>,
+
+    /// `None` selects deterministic forecasts.
+    pub ensembles: Vec<Option<Ensemble>>,
+}
+
+/// A range of bytes in a GRIB2 file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ByteRange {
+    pub start: u64,
+
+    /// One past the last byte. `None` if the range runs to the end of the file, because it ends
+    /// with the last message and the length of the file isn't known.
+    pub end: Option<u64>,
+}
+
+/// The records selected from an [`Inventory`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Selection<'a> {
+    /// The selected lines of the `.idx` file, in order.
+    pub lines: Vec<&'a str>,
+
+    /// The messages which hold the selected records, merged into the fewest contiguous ranges.
+    pub byte_ranges: Vec<ByteRange>,
+}
+
+impl Inventory {
+    /// `idx` is the contents of a `.idx` file. `grib_len` is the length of the GRIB2 file in
+    /// bytes, if known. It's only used to find the end of the last message.
+    ///
+    /// Returns an error if any line isn't a valid `.idx` record.
+    pub fn parse(idx: &str, grib_len: Option<u64>) -> Result<Self, Error> {
+        let records = idx
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| Ok((line, IdxRecord::parse(line)?)))
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        // Fields in the same message share an offset, so each message ends where the next
+        // distinct offset begins.
+        let mut offsets: Vec<u64> = records.iter().map(|(_, record)| record.offset).collect();
+        offsets.sort_unstable();
+        offsets.dedup();
+        let entries = records
+            .into_iter()
+            .map(|(line, record)| {
+                let i = offsets.partition_point(|&offset| offset <= record.offset);
+                Entry {
+                    line: line.to_string(),
+                    range: ByteRange {
+                        start: record.offset,
+                        end: offsets.get(i).copied().or(grib_len),
+                    },
+                    record,
+                }
+            })
+            .collect();
+        Ok(Self { entries })
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Select the records which match any of `queries`.
+    pub fn select(&self, queries: &[Query]) -> Selection<'_> {
+        // Compare levels as text, which is cheaper than parsing every record's level.
+        let levels: Vec<Vec<String>> = queries
+            .iter()
+            .map(|query| query.levels.iter().map(Level::to_string).collect())
+            .collect();
+        self.select_by(|entry| {
+            queries
+                .iter()
+                .zip(&levels)
+                .any(|(query, levels)| query.matches(&entry.record, levels))
+        })
+    }
+
+    /// Select the records whose line matches `regex`, like `wgrib2 -match`. e.g.
+    /// `":(UGRD|VGRD):10 m above ground:"` works with Herbie's `searchString` too.
+    #[cfg(feature = "regex")]
+    pub fn select_regex(&self, regex: &regex::Regex) -> Selection<'_> {
+        self.select_by(|entry| regex.is_match(&entry.line))
+    }
+
+    fn select_by(&self, mut predicate: impl FnMut(&Entry) -> bool) -> Selection<'_> {
+        let selected: Vec<&Entry> = self
+            .entries
+            .iter()
+            .filter(|entry| predicate(entry))
+            .collect();
+        Selection {
+            lines: selected.iter().map(|entry| entry.line.as_str()).collect(),
+            byte_ranges: merge(selected.iter().map(|entry| entry.range).collect()),
+        }
+    }
+}
+
+impl Query {
+    fn matches(&self, record: &IdxRecord, levels: &[String]) -> bool {
+        let abbrev_matches = self.abbrevs.is_empty() || self.abbrevs.contains(&record.abbrev);
+        let level_matches = levels.is_empty() || levels.contains(&record.level);
+        abbrev_matches
+            && level_matches
+            && self.forecast_hours_match(&record.step)
+            && self.ensemble_matches(&record.extra)
+    }
+
+    fn forecast_hours_match(&self, step: &str) -> bool {
+        let Some(forecast_hours) = &self.forecast_hours else {
+            return true;
+        };
+        Step::from_idx_str(step)
+            .and_then(|step| step.time_unit.known()?.minutes()?.checked_mul(step.end()?))
+            .is_some_and(|minutes| {
+                minutes.is_multiple_of(60) && forecast_hours.contains(&(minutes / 60))
+            })
+    }
+
+    fn ensemble_matches(&self, extra: &[String]) -> bool {
+        self.ensembles.is_empty()
+            || Ensemble::from_idx_fields(extra)
+                .is_ok_and(|ensemble| self.ensembles.contains(&ensemble))
+    }
+}
+
+impl ByteRange {
+    /// The value of an HTTP `Range` header for this range, e.g. `bytes=0-99`.
+    pub fn http_range(&self) -> String {
+        match self.end {
+            Some(end) => format!("bytes={}-{}", self.start, end.saturating_sub(1)),
+            None => format!("bytes={}-", self.start),
+        }
+    }
+}
+
+/// Merge overlapping and adjacent ranges.
+fn merge(mut ranges: Vec<ByteRange>) -> Vec<ByteRange> {
+    ranges.sort_unstable();
+    let mut merged: Vec<ByteRange> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if last.end.is_none_or(|end| range.start <= end) => {
+                last.end = match (last.end, range.end) {
+                    (Some(last_end), Some(end)) => Some(last_end.max(end)),
+                    _ => None,
+                };
+            }
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
@@ -0,0 +1,1512 @@
+//! A decoder for the JPEG 2000 code streams (ITU-T T.800) in data representation template 5.40.
+//!
+//! GRIB2 only needs a small part of JPEG 2000: a single component, and (in practice) lossless
+//! compression with the reversible 5/3 wavelet, which is what NCEP's g2lib and ecCodes write. We
+//! decode any number of tiles, resolution levels, quality layers and precincts, but not the
+//! irreversible 9/7 wavelet, regions of interest, progression order changes or the arithmetic
+//! coder bypass. Section numbers in the comments refer to T.800 (2002).
+
+/// Decode a code stream into its `num_values` samples, in raster order. We check the image size
+/// against `num_values` before allocating anything, so a corrupt header can't make us run out of
+/// memory.
+pub(crate) fn decode(codestream: &[u8], num_values: usize) -> Result<Vec<i32>, &'static str> {
+    let header = Header::parse(codestream)?;
+    let siz = &header.siz;
+    let width = ceil_div(siz.x1, siz.xr) - ceil_div(siz.x0, siz.xr);
+    let height = ceil_div(siz.y1, siz.yr) - ceil_div(siz.y0, siz.yr);
+    if width
+        .checked_mul(height)
+        .and_then(|size| usize::try_from(size).ok())
+        != Some(num_values)
+    {
+        return Err("the JPEG 2000 image doesn't have the number of values in section 5");
+    }
+    let mut image = vec![0; num_values];
+    for (tile_index, tile) in header.tiles.iter().enumerate() {
+        if let Some(tile) = tile {
+            decode_tile(siz, tile, tile_index, width, &mut image)?;
+        }
+    }
+    Ok(image)
+}
+
+// Marker codes (A.2).
+const SOC: u16 = 0xFF4F;
+const SIZ: u16 = 0xFF51;
+const COD: u16 = 0xFF52;
+const COC: u16 = 0xFF53;
+const QCD: u16 = 0xFF5C;
+const QCC: u16 = 0xFF5D;
+const RGN: u16 = 0xFF5E;
+const POC: u16 = 0xFF5F;
+const PPM: u16 = 0xFF60;
+const PPT: u16 = 0xFF61;
+const SOT: u16 = 0xFF90;
+const SOP: u16 = 0xFF91;
+const EPH: u16 = 0xFF92;
+const SOD: u16 = 0xFF93;
+const EOC: u16 = 0xFFD9;
+
+/// The image and tile size (A.5.1). Coordinates are on the reference grid.
+struct Siz {
+    x1: i64,
+    y1: i64,
+    x0: i64,
+    y0: i64,
+    tile_width: i64,
+    tile_height: i64,
+    tile_x0: i64,
+    tile_y0: i64,
+    depth: u8,
+    signed: bool,
+    xr: i64,
+    yr: i64,
+}
+
+/// From the COD and COC markers (A.6.1 and A.6.2).
+#[derive(Clone)]
+struct CodingStyle {
+    sop: bool,
+    eph: bool,
+    progression_order: u8,
+    num_layers: u16,
+    num_levels: u8,
+
+    /// Code-block width and height exponents.
+    xcb: u8,
+    ycb: u8,
+    code_block_style: u8,
+    reversible: bool,
+
+    /// Precinct width and height exponents for each resolution level.
+    precinct_sizes: Vec<(u8, u8)>,
+}
+
+/// From the QCD and QCC markers (A.6.4 and A.6.5).
+#[derive(Clone)]
+struct Quantization {
+    guard_bits: u8,
+    style: u8,
+
+    /// The exponent of each subband, in the order LL, then HL, LH and HH from the lowest
+    /// resolution to the highest.
+    exponents: Vec<u8>,
+}
+
+/// A tile's coding parameters, and its data from every tile-part.
+#[derive(Clone)]
+struct Tile {
+    coding_style: CodingStyle,
+    quantization: Quantization,
+    data: Vec<u8>,
+}
+
+struct Header {
+    siz: Siz,
+
+    /// Indexed by tile number. `None` for tiles which have no tile-parts.
+    tiles: Vec<Option<Tile>>,
+}
+
+// Code-block styles (table A.19).
+const BYPASS: u8 = 0x01;
+const RESET: u8 = 0x02;
+const TERMINATE_ALL: u8 = 0x04;
+const VERTICALLY_CAUSAL: u8 = 0x08;
+const SEGMENTATION_SYMBOLS: u8 = 0x20;
+
+impl Header {
+    fn parse(codestream: &[u8]) -> Result<Self, &'static str> {
+        let mut reader = Reader::new(codestream);
+        if reader.u16()? != SOC {
+            return Err("JPEG 2000 code stream doesn't start with SOC");
+        }
+        let mut siz = None;
+        let mut coding_style = None;
+        let mut quantization = None;
+        let mut tiles: Vec<Option<Tile>> = Vec::new();
+        loop {
+            let marker = reader.u16()?;
+            match marker {
+                EOC => break,
+                SOT => {
+                    let siz: &Siz = siz.as_ref().ok_or("JPEG 2000 SOT before SIZ")?;
+                    let start = reader.pos - 2;
+                    let _len = reader.u16()?;
+                    let tile_index = usize::from(reader.u16()?);
+                    let tile_part_len = reader.u32()? as usize;
+                    let _tile_part_index = reader.u8()?;
+                    let _num_tile_parts = reader.u8()?;
+                    if tiles.is_empty() {
+                        tiles.resize(num_tiles(siz)?, None);
+                    }
+                    let tile = tiles
+                        .get_mut(tile_index)
+                        .ok_or("JPEG 2000 tile index is out of range")?;
+                    let tile = tile.get_or_insert_with(|| Tile {
+                        coding_style: coding_style.clone().unwrap_or_else(CodingStyle::missing),
+                        quantization: quantization.clone().unwrap_or_else(Quantization::missing),
+                        data: Vec::new(),
+                    });
+                    loop {
+                        let marker = reader.u16()?;
+                        match marker {
+                            SOD => break,
+                            COD => tile.coding_style = CodingStyle::parse(reader.segment()?)?,
+                            COC => tile.coding_style.parse_coc(reader.segment()?)?,
+                            QCD => tile.quantization = Quantization::parse(reader.segment()?)?,
+                            QCC => tile.quantization = Quantization::parse_qcc(reader.segment()?)?,
+                            _ => skip_other_marker(marker, &mut reader)?,
+                        }
+                    }
+                    let end = match tile_part_len {
+                        // The last tile-part runs to the EOC marker.
+                        0 => codestream.len().saturating_sub(2).max(reader.pos),
+                        len => start + len,
+                    };
+                    let data = codestream
+                        .get(reader.pos..end)
+                        .ok_or("JPEG 2000 tile-part is truncated")?;
+                    tile.data.extend_from_slice(data);
+                    reader.pos = end;
+                }
+                SIZ => siz = Some(Siz::parse(reader.segment()?)?),
+                COD => coding_style = Some(CodingStyle::parse(reader.segment()?)?),
+                COC => coding_style
+                    .as_mut()
+                    .ok_or("JPEG 2000 COC before COD")?
+                    .parse_coc(reader.segment()?)?,
+                QCD => quantization = Some(Quantization::parse(reader.segment()?)?),
+                QCC => quantization = Some(Quantization::parse_qcc(reader.segment()?)?),
+                _ => skip_other_marker(marker, &mut reader)?,
+            }
+            if reader.pos >= codestream.len() {
+                break; // Some encoders leave out EOC.
+            }
+        }
+        let siz = siz.ok_or("JPEG 2000 code stream has no SIZ marker")?;
+        for tile in tiles.iter().flatten() {
+            tile.check_supported()?;
+        }
+        Ok(Self { siz, tiles })
+    }
+}
+
+/// Skip markers which don't affect decoding, and reject those we don't support.
+fn skip_other_marker(marker: u16, reader: &mut Reader) -> Result<(), &'static str> {
+    match marker {
+        RGN => Err("JPEG 2000 regions of interest aren't supported"),
+        POC => Err("JPEG 2000 progression order changes aren't supported"),
+        PPM | PPT => Err("JPEG 2000 packed packet headers aren't supported"),
+        // Markers without a segment.
+        0xFF30..=0xFF3F => Ok(()),
+        // e.g. COM, TLM, PLM, PLT and CRG.
+        _ if marker >> 8 == 0xFF => reader.segment().map(|_| ()),
+        _ => Err("invalid JPEG 2000 marker"),
+    }
+}
+
+fn num_tiles(siz: &Siz) -> Result<usize, &'static str> {
+    if siz.tile_width <= 0 || siz.tile_height <= 0 {
+        return Err("JPEG 2000 tile size is zero");
+    }
+    let across = ceil_div(siz.x1 - siz.tile_x0, siz.tile_width);
+    let down = ceil_div(siz.y1 - siz.tile_y0, siz.tile_height);
+    // Tile indices are 16 bits (A.4.2), so there can't be more than 65535 tiles.
+    across
+        .checked_mul(down)
+        .filter(|num_tiles| (1..=i64::from(u16::MAX)).contains(num_tiles))
+        .map(|num_tiles| num_tiles as usize)
+        .ok_or("JPEG 2000 tile grid is invalid")
+}
+
+impl Siz {
+    fn parse(segment: &[u8]) -> Result<Self, &'static str> {
+        let mut reader = Reader::new(segment);
+        let _capabilities = reader.u16()?;
+        let mut u32 = || reader.u32().map(i64::from);
+        let (x1, y1, x0, y0) = (u32()?, u32()?, u32()?, u32()?);
+        let (tile_width, tile_height, tile_x0, tile_y0) = (u32()?, u32()?, u32()?, u32()?);
+        if reader.u16()? != 1 {
+            return Err("only single-component JPEG 2000 images are supported");
+        }
+        let precision = reader.u8()?;
+        let (xr, yr) = (i64::from(reader.u8()?), i64::from(reader.u8()?));
+        if x1 <= x0 || y1 <= y0 || xr == 0 || yr == 0 || tile_x0 > x0 || tile_y0 > y0 {
+            return Err("JPEG 2000 image size is invalid");
+        }
+        Ok(Self {
+            x1,
+            y1,
+            x0,
+            y0,
+            tile_width,
+            tile_height,
+            tile_x0,
+            tile_y0,
+            depth: (precision & 0x7F) + 1,
+            signed: precision & 0x80 != 0,
+            xr,
+            yr,
+        })
+    }
+}
+
+impl CodingStyle {
+    /// The values a tile would get if the main header had no COD marker, which is invalid.
+    fn missing() -> Self {
+        Self {
+            sop: false,
+            eph: false,
+            progression_order: 0,
+            num_layers: 0,
+            num_levels: 0,
+            xcb: 0,
+            ycb: 0,
+            code_block_style: 0,
+            reversible: false,
+            precinct_sizes: Vec::new(),
+        }
+    }
+
+    fn parse(segment: &[u8]) -> Result<Self, &'static str> {
+        let mut reader = Reader::new(segment);
+        let style = reader.u8()?;
+        let progression_order = reader.u8()?;
+        let num_layers = reader.u16()?;
+        let _multiple_component_transform = reader.u8()?;
+        let mut coding_style = Self {
+            sop: style & 0x02 != 0,
+            eph: style & 0x04 != 0,
+            progression_order,
+            num_layers,
+            ..Self::missing()
+        };
+        coding_style.parse_component_style(style, &mut reader)?;
+        Ok(coding_style)
+    }
+
+    fn parse_coc(&mut self, segment: &[u8]) -> Result<(), &'static str> {
+        let mut reader = Reader::new(segment);
+        let _component = reader.u8()?;
+        let style = reader.u8()?;
+        self.parse_component_style(style, &mut reader)
+    }
+
+    /// SPcod or SPcoc.
+    fn parse_component_style(
+        &mut self,
+        style: u8,
+        reader: &mut Reader,
+    ) -> Result<(), &'static str> {
+        self.num_levels = reader.u8()?;
+        self.xcb = reader.u8()? + 2;
+        self.ycb = reader.u8()? + 2;
+        self.code_block_style = reader.u8()?;
+        self.reversible = reader.u8()? == 1;
+        if self.num_levels > 32 || self.xcb > 10 || self.ycb > 10 || self.xcb + self.ycb > 12 {
+            return Err("JPEG 2000 coding style is invalid");
+        }
+        self.precinct_sizes = (0..=self.num_levels)
+            .map(|_| match style & 0x01 {
+                0 => Ok((15, 15)),
+                _ => reader.u8().map(|size| (size & 0x0F, size >> 4)),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(())
+    }
+}
+
+impl Quantization {
+    fn missing() -> Self {
+        Self {
+            guard_bits: 0,
+            style: 0,
+            exponents: Vec::new(),
+        }
+    }
+
+    fn parse(segment: &[u8]) -> Result<Self, &'static str> {
+        let style = *segment.first().ok_or("JPEG 2000 QCD is empty")?;
+        let exponents = match style & 0x1F {
+            0 => segment[1..].iter().map(|byte| byte >> 3).collect(),
+            // Scalar quantization: 16 bits per subband, or just for LL.
+            1 | 2 => segment[1..].chunks(2).map(|step| step[0] >> 3).collect(),
+            _ => return Err("JPEG 2000 quantization style is invalid"),
+        };
+        Ok(Self {
+            guard_bits: style >> 5,
+            style: style & 0x1F,
+            exponents,
+        })
+    }
+
+    fn parse_qcc(segment: &[u8]) -> Result<Self, &'static str> {
+        Self::parse(segment.get(1..).ok_or("JPEG 2000 QCC is empty")?)
+    }
+}
+
+impl Tile {
+    fn check_supported(&self) -> Result<(), &'static str> {
+        let coding_style = &self.coding_style;
+        if coding_style.num_layers == 0 || coding_style.precinct_sizes.is_empty() {
+            return Err("JPEG 2000 code stream has no COD marker");
+        }
+        if !coding_style.reversible || self.quantization.style != 0 {
+            return Err("only lossless JPEG 2000 (the reversible 5/3 wavelet) is supported");
+        }
+        if coding_style.code_block_style & BYPASS != 0 {
+            return Err("JPEG 2000 arithmetic coder bypass isn't supported");
+        }
+        if coding_style.progression_order > 4 {
+            return Err("JPEG 2000 progression order is invalid");
+        }
+        if self.quantization.exponents.len() < 1 + 3 * usize::from(coding_style.num_levels) {
+            return Err("JPEG 2000 QCD has too few subbands");
+        }
+        Ok(())
+    }
+}
+
+/// Big-endian reader for marker segments.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], &'static str> {
+        let bytes = self
+            .bytes
+            .get(self.pos..self.pos + len)
+            .ok_or("JPEG 2000 code stream is truncated")?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, &'static str> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, &'static str> {
+        Ok(u16::from_be_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, &'static str> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    /// The rest of a marker segment, after its length.
+    fn segment(&mut self) -> Result<&'a [u8], &'static str> {
+        let len = usize::from(self.u16()?);
+        self.take(
+            len.checked_sub(2)
+                .ok_or("JPEG 2000 marker segment is too short")?,
+        )
+    }
+}
+
+/// A rectangle on the reference grid, or in a resolution level or subband, as `[x0, x1)` and
+/// `[y0, y1)`.
+#[derive(Clone, Copy)]
+struct Rect {
+    x0: i64,
+    y0: i64,
+    x1: i64,
+    y1: i64,
+}
+
+impl Rect {
+    fn width(&self) -> usize {
+        (self.x1 - self.x0).max(0) as usize
+    }
+
+    fn height(&self) -> usize {
+        (self.y1 - self.y0).max(0) as usize
+    }
+
+    fn intersect(&self, other: &Rect) -> Rect {
+        Rect {
+            x0: self.x0.max(other.x0),
+            y0: self.y0.max(other.y0),
+            x1: self.x1.min(other.x1),
+            y1: self.y1.min(other.y1),
+        }
+    }
+
+    /// Divide by `2^shift`, rounding each edge up, e.g. to find a resolution level (B-14).
+    fn scale_down(&self, shift: u32) -> Rect {
+        let d = 1 << shift;
+        Rect {
+            x0: ceil_div(self.x0, d),
+            y0: ceil_div(self.y0, d),
+            x1: ceil_div(self.x1, d),
+            y1: ceil_div(self.y1, d),
+        }
+    }
+}
+
+/// Subband orientations, which select the zero coding contexts.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Orientation {
+    LL,
+    HL,
+    LH,
+    HH,
+}
+
+struct Band {
+    rect: Rect,
+    orientation: Orientation,
+
+    /// The number of magnitude bit-planes, Mb (E-2).
+    num_bitplanes: u8,
+    coefficients: Vec<i32>,
+}
+
+struct Resolution {
+    rect: Rect,
+    bands: Vec<Band>,
+    precincts: Vec<Precinct>,
+}
+
+struct Precinct {
+    /// One for each band of the resolution.
+    bands: Vec<PrecinctBand>,
+}
+
+struct PrecinctBand {
+    code_blocks: Vec<CodeBlock>,
+    inclusion: TagTree,
+    zero_bitplanes: TagTree,
+}
+
+struct CodeBlock {
+    /// In the coordinates of the subband.
+    rect: Rect,
+    included: bool,
+    zero_bitplanes: u8,
+    lblock: u32,
+    num_passes: usize,
+    data: Vec<u8>,
+
+    /// The length of each pass, when every pass is terminated.
+    pass_lengths: Vec<usize>,
+}
+
+fn decode_tile(
+    siz: &Siz,
+    tile: &Tile,
+    tile_index: usize,
+    image_width: i64,
+    image: &mut [i32],
+) -> Result<(), &'static str> {
+    let coding_style = &tile.coding_style;
+    let tiles_across = ceil_div(siz.x1 - siz.tile_x0, siz.tile_width);
+    let (p, q) = (
+        tile_index as i64 % tiles_across,
+        tile_index as i64 / tiles_across,
+    );
+    let tile_rect = Rect {
+        x0: (siz.tile_x0 + p * siz.tile_width).max(siz.x0),
+        y0: (siz.tile_y0 + q * siz.tile_height).max(siz.y0),
+        x1: (siz.tile_x0 + (p + 1) * siz.tile_width).min(siz.x1),
+        y1: (siz.tile_y0 + (q + 1) * siz.tile_height).min(siz.y1),
+    };
+    // The tile in the component's coordinates (B-12).
+    let component_rect = Rect {
+        x0: ceil_div(tile_rect.x0, siz.xr),
+        y0: ceil_div(tile_rect.y0, siz.yr),
+        x1: ceil_div(tile_rect.x1, siz.xr),
+        y1: ceil_div(tile_rect.y1, siz.yr),
+    };
+
+    let mut resolutions = (0..=coding_style.num_levels)
+        .map(|r| resolution(component_rect, r, tile))
+        .collect::<Result<Vec<_>, _>>()?;
+    read_packets(&mut resolutions, coding_style, &tile.data)?;
+    for resolution in &mut resolutions {
+        for (band_index, band) in resolution.bands.iter_mut().enumerate() {
+            for precinct in &resolution.precincts {
+                for code_block in &precinct.bands[band_index].code_blocks {
+                    decode_code_block(code_block, band, coding_style.code_block_style)?;
+                }
+            }
+        }
+    }
+
+    let (samples, rect) = inverse_wavelet_transform(resolutions);
+    let dc_offset = if siz.signed { 0 } else { 1 << (siz.depth - 1) };
+    let image_x0 = ceil_div(siz.x0, siz.xr);
+    let image_y0 = ceil_div(siz.y0, siz.yr);
+    for (row, y) in (rect.y0..rect.y1).enumerate() {
+        for (column, x) in (rect.x0..rect.x1).enumerate() {
+            let sample = samples[row * rect.width() + column] + dc_offset;
+            image[((y - image_y0) * image_width + x - image_x0) as usize] = sample;
+        }
+    }
+    Ok(())
+}
+
+/// Lay out resolution level `r` of a tile, with its subbands, precincts and code-blocks.
+fn resolution(component_rect: Rect, r: u8, tile: &Tile) -> Result<Resolution, &'static str> {
+    let coding_style = &tile.coding_style;
+    let num_levels = coding_style.num_levels;
+    let rect = component_rect.scale_down(u32::from(num_levels - r));
+
+    // Subbands (B-15). The LL band is only in resolution 0, and the others are at decomposition
+    // level `num_levels - r + 1`.
+    let orientations: &[(Orientation, i64, i64)] = if r == 0 {
+        &[(Orientation::LL, 0, 0)]
+    } else {
+        &[
+            (Orientation::HL, 1, 0),
+            (Orientation::LH, 0, 1),
+            (Orientation::HH, 1, 1),
+        ]
+    };
+    let level = u32::from(if r == 0 {
+        num_levels
+    } else {
+        num_levels - r + 1
+    });
+    let quantization = &tile.quantization;
+    let bands = orientations
+        .iter()
+        .enumerate()
+        .map(|(i, &(orientation, xo, yo))| {
+            let (dx, dy) = match level {
+                0 => (0, 0),
+                _ => (xo << (level - 1), yo << (level - 1)),
+            };
+            let rect = Rect {
+                x0: component_rect.x0 - dx,
+                y0: component_rect.y0 - dy,
+                x1: component_rect.x1 - dx,
+                y1: component_rect.y1 - dy,
+            }
+            .scale_down(level);
+            let band_index = if r == 0 {
+                0
+            } else {
+                3 * usize::from(r - 1) + 1 + i
+            };
+            let num_bitplanes = (quantization.guard_bits + quantization.exponents[band_index])
+                .checked_sub(1)
+                .filter(|&num_bitplanes| num_bitplanes <= 30)
+                .ok_or("JPEG 2000 subband has an invalid number of bit-planes")?;
+            Ok(Band {
+                rect,
+                orientation,
+                num_bitplanes,
+                coefficients: vec![0; rect.width() * rect.height()],
+            })
+        })
+        .collect::<Result<Vec<_>, &'static str>>()?;
+
+    // Precincts (B-16) and code-blocks (B-17). Both partitions are anchored at 0, and each
+    // code-block lies within one precinct.
+    let (ppx, ppy) = coding_style.precinct_sizes[usize::from(r)];
+    let (band_ppx, band_ppy) = match r {
+        0 => (ppx, ppy),
+        _ => (
+            ppx.checked_sub(1)
+                .ok_or("JPEG 2000 precinct is too small")?,
+            ppy.checked_sub(1)
+                .ok_or("JPEG 2000 precinct is too small")?,
+        ),
+    };
+    let xcb = coding_style.xcb.min(band_ppx);
+    let ycb = coding_style.ycb.min(band_ppy);
+    let (precincts_x0, precincts_y0) = (rect.x0 >> ppx, rect.y0 >> ppy);
+    let (precincts_across, precincts_down) = if rect.width() == 0 || rect.height() == 0 {
+        (0, 0)
+    } else {
+        (
+            ceil_div(rect.x1, 1 << ppx) - precincts_x0,
+            ceil_div(rect.y1, 1 << ppy) - precincts_y0,
+        )
+    };
+    let mut precincts = Vec::new();
+    for py in precincts_y0..precincts_y0 + precincts_down {
+        for px in precincts_x0..precincts_x0 + precincts_across {
+            let bands = bands
+                .iter()
+                .map(|band| {
+                    let precinct_rect = Rect {
+                        x0: px << band_ppx,
+                        y0: py << band_ppy,
+                        x1: (px + 1) << band_ppx,
+                        y1: (py + 1) << band_ppy,
+                    }
+                    .intersect(&band.rect);
+                    precinct_band(precinct_rect, xcb, ycb)
+                })
+                .collect();
+            precincts.push(Precinct { bands });
+        }
+    }
+    Ok(Resolution {
+        rect,
+        bands,
+        precincts,
+    })
+}
+
+fn precinct_band(rect: Rect, xcb: u8, ycb: u8) -> PrecinctBand {
+    let (across, down) = if rect.width() == 0 || rect.height() == 0 {
+        (0, 0)
+    } else {
+        (
+            ceil_div(rect.x1, 1 << xcb) - (rect.x0 >> xcb),
+            ceil_div(rect.y1, 1 << ycb) - (rect.y0 >> ycb),
+        )
+    };
+    let mut code_blocks = Vec::new();
+    for cy in 0..down {
+        for cx in 0..across {
+            let x0 = ((rect.x0 >> xcb) + cx) << xcb;
+            let y0 = ((rect.y0 >> ycb) + cy) << ycb;
+            code_blocks.push(CodeBlock {
+                rect: Rect {
+                    x0,
+                    y0,
+                    x1: x0 + (1 << xcb),
+                    y1: y0 + (1 << ycb),
+                }
+                .intersect(&rect),
+                included: false,
+                zero_bitplanes: 0,
+                lblock: 3,
+                num_passes: 0,
+                data: Vec::new(),
+                pass_lengths: Vec::new(),
+            });
+        }
+    }
+    PrecinctBand {
+        code_blocks,
+        inclusion: TagTree::new(across as usize, down as usize),
+        zero_bitplanes: TagTree::new(across as usize, down as usize),
+    }
+}
+
+/// Read every packet of a tile, in the tile's progression order (B.12).
+fn read_packets(
+    resolutions: &mut [Resolution],
+    coding_style: &CodingStyle,
+    data: &[u8],
+) -> Result<(), &'static str> {
+    let num_layers = usize::from(coding_style.num_layers);
+    let mut packets = Vec::new();
+    match coding_style.progression_order {
+        // Layer-resolution-component-position.
+        0 => {
+            for layer in 0..num_layers {
+                for (r, resolution) in resolutions.iter().enumerate() {
+                    for precinct in 0..resolution.precincts.len() {
+                        packets.push((layer, r, precinct));
+                    }
+                }
+            }
+        }
+        // Resolution-layer-component-position.
+        1 => {
+            for (r, resolution) in resolutions.iter().enumerate() {
+                for layer in 0..num_layers {
+                    for precinct in 0..resolution.precincts.len() {
+                        packets.push((layer, r, precinct));
+                    }
+                }
+            }
+        }
+        // Resolution-position-component-layer. With one component, precincts are in raster
+        // order within each resolution.
+        2 => {
+            for (r, resolution) in resolutions.iter().enumerate() {
+                for precinct in 0..resolution.precincts.len() {
+                    for layer in 0..num_layers {
+                        packets.push((layer, r, precinct));
+                    }
+                }
+            }
+        }
+        // Position-component-resolution-layer and component-position-resolution-layer. These
+        // interleave resolutions by position, which only matters if there's more than one
+        // precinct in a resolution.
+        _ => {
+            if resolutions.iter().any(|r| r.precincts.len() > 1) {
+                return Err("JPEG 2000 position-first progression orders with several precincts aren't supported");
+            }
+            for (r, resolution) in resolutions.iter().enumerate() {
+                for layer in 0..num_layers {
+                    for precinct in 0..resolution.precincts.len() {
+                        packets.push((layer, r, precinct));
+                    }
+                }
+            }
+        }
+    }
+
+    let mut pos = 0;
+    for (layer, r, precinct) in packets {
+        if pos >= data.len() {
+            break; // The rest of the packets were truncated away, e.g. to save space.
+        }
+        let precinct = &mut resolutions[r].precincts[precinct];
+        pos = read_packet(precinct, layer, coding_style, data, pos)?;
+    }
+    Ok(())
+}
+
+/// Read one packet (B.10), starting at `pos`, and return the position after it.
+fn read_packet(
+    precinct: &mut Precinct,
+    layer: usize,
+    coding_style: &CodingStyle,
+    data: &[u8],
+    mut pos: usize,
+) -> Result<usize, &'static str> {
+    if coding_style.sop && data[pos..].starts_with(&SOP.to_be_bytes()) {
+        pos += 6;
+    }
+    let terminate_all = coding_style.code_block_style & TERMINATE_ALL != 0;
+    let mut bits = BitReader::new(data, pos);
+    let mut contributions = Vec::new();
+    if bits.bit()? == 1 {
+        for (band_index, band) in precinct.bands.iter_mut().enumerate() {
+            for (i, code_block) in band.code_blocks.iter_mut().enumerate() {
+                let included = if code_block.included {
+                    bits.bit()? == 1
+                } else {
+                    band.inclusion.decode(&mut bits, i, layer as u32 + 1)?
+                };
+                if !included {
+                    continue;
+                }
+                if !code_block.included {
+                    let mut threshold = 1;
+                    while !band.zero_bitplanes.decode(&mut bits, i, threshold)? {
+                        threshold += 1;
+                    }
+                    code_block.zero_bitplanes = u8::try_from(threshold - 1)
+                        .map_err(|_| "JPEG 2000 code-block is invalid")?;
+                    code_block.included = true;
+                }
+                let num_passes = bits.num_passes()?;
+                while bits.bit()? == 1 {
+                    code_block.lblock += 1;
+                }
+                let lengths = if terminate_all {
+                    (0..num_passes)
+                        .map(|_| bits.bits(code_block.lblock))
+                        .collect::<Result<Vec<_>, _>>()?
+                } else {
+                    vec![bits.bits(code_block.lblock + num_passes.ilog2())?]
+                };
+                contributions.push((band_index, i, num_passes, lengths));
+            }
+        }
+    }
+    pos = bits.align();
+    if coding_style.eph && data[pos.min(data.len())..].starts_with(&EPH.to_be_bytes()) {
+        pos += 2;
+    }
+
+    for (band_index, i, num_passes, lengths) in contributions {
+        let code_block = &mut precinct.bands[band_index].code_blocks[i];
+        for len in lengths {
+            let len = len as usize;
+            let bytes = data
+                .get(pos..pos + len)
+                .ok_or("JPEG 2000 packet is truncated")?;
+            code_block.data.extend_from_slice(bytes);
+            if terminate_all {
+                code_block.pass_lengths.push(len);
+            }
+            pos += len;
+        }
+        code_block.num_passes += num_passes as usize;
+    }
+    Ok(pos)
+}
+
+/// Reads packet headers, which skip the most significant bit of each byte after an 0xFF (B.10.1).
+struct BitReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    byte: u8,
+    bits_left: u32,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(bytes: &'a [u8], pos: usize) -> Self {
+        Self {
+            bytes,
+            pos,
+            byte: 0,
+            bits_left: 0,
+        }
+    }
+
+    fn bit(&mut self) -> Result<u32, &'static str> {
+        if self.bits_left == 0 {
+            self.bits_left = if self.byte == 0xFF { 7 } else { 8 };
+            self.byte = *self
+                .bytes
+                .get(self.pos)
+                .ok_or("JPEG 2000 packet header is truncated")?;
+            self.pos += 1;
+        }
+        self.bits_left -= 1;
+        Ok(u32::from(self.byte >> self.bits_left) & 1)
+    }
+
+    fn bits(&mut self, n: u32) -> Result<u32, &'static str> {
+        if n > 32 {
+            return Err("JPEG 2000 code-block is too long");
+        }
+        (0..n).try_fold(0, |value, _| Ok(value << 1 | self.bit()?))
+    }
+
+    /// The number of coding passes (table B.4).
+    fn num_passes(&mut self) -> Result<u32, &'static str> {
+        Ok(if self.bit()? == 0 {
+            1
+        } else if self.bit()? == 0 {
+            2
+        } else {
+            match self.bits(2)? {
+                3 => match self.bits(5)? {
+                    31 => 37 + self.bits(7)?,
+                    n => 6 + n,
+                },
+                n => 3 + n,
+            }
+        })
+    }
+
+    /// Skip to the end of the byte, and the byte after that if it's stuffed, and return the
+    /// position of the next byte.
+    fn align(&mut self) -> usize {
+        if self.byte == 0xFF {
+            self.pos += 1;
+        }
+        self.bits_left = 0;
+        self.pos
+    }
+}
+
+/// A tag tree (B.10.2) for one precinct band: a quad tree of minimums over a grid of values.
+struct TagTree {
+    /// Each level's width and the start of its nodes in `nodes`, from the leaves to the root.
+    levels: Vec<(usize, usize)>,
+    nodes: Vec<TagTreeNode>,
+}
+
+#[derive(Clone, Copy)]
+struct TagTreeNode {
+    value: u32,
+    low: u32,
+}
+
+impl TagTree {
+    fn new(width: usize, height: usize) -> Self {
+        let mut levels = Vec::new();
+        let (mut w, mut h, mut start) = (width, height, 0);
+        loop {
+            levels.push((w, start));
+            start += w * h;
+            if w <= 1 && h <= 1 {
+                break;
+            }
+            (w, h) = (w.div_ceil(2), h.div_ceil(2));
+        }
+        let node = TagTreeNode {
+            value: u32::MAX,
+            low: 0,
+        };
+        Self {
+            levels,
+            nodes: vec![node; start],
+        }
+    }
+
+    /// Read bits until we know whether the value of `leaf` is less than `threshold`.
+    fn decode(
+        &mut self,
+        bits: &mut BitReader,
+        leaf: usize,
+        threshold: u32,
+    ) -> Result<bool, &'static str> {
+        let width = self.levels[0].0.max(1);
+        let (x, y) = (leaf % width, leaf / width);
+        let path: Vec<usize> = self
+            .levels
+            .iter()
+            .enumerate()
+            .map(|(level, &(w, start))| start + (y >> level) * w + (x >> level))
+            .collect();
+        let mut low = 0;
+        for &i in path.iter().rev() {
+            let node = &mut self.nodes[i];
+            low = low.max(node.low);
+            while low < threshold && low < node.value {
+                if bits.bit()? == 1 {
+                    node.value = low;
+                } else {
+                    low += 1;
+                }
+            }
+            node.low = low;
+        }
+        Ok(self.nodes[path[0]].value < threshold)
+    }
+}
+
+// Context labels (D.3).
+const RUN_LENGTH: usize = 17;
+const UNIFORM: usize = 18;
+
+// Per-coefficient state in a code-block.
+const SIGNIFICANT: u8 = 1;
+const NEGATIVE: u8 = 2;
+const VISITED: u8 = 4;
+const REFINED: u8 = 8;
+
+/// Decode a code-block's coding passes (annex D) into its subband's coefficients.
+fn decode_code_block(
+    code_block: &CodeBlock,
+    band: &mut Band,
+    style: u8,
+) -> Result<(), &'static str> {
+    if code_block.num_passes == 0 {
+        return Ok(());
+    }
+    let num_planes = band
+        .num_bitplanes
+        .checked_sub(code_block.zero_bitplanes)
+        .filter(|&planes| 3 * usize::from(planes) >= code_block.num_passes + 2)
+        .ok_or("JPEG 2000 code-block has too many coding passes")?;
+
+    let mut decoder = CodeBlockDecoder::new(code_block.rect, band.orientation, style);
+    // With every pass terminated, each pass has its own codeword segment.
+    let mut segments = Vec::new();
+    let mut rest = code_block.data.as_slice();
+    for &len in &code_block.pass_lengths {
+        let (segment, after) = rest.split_at(len.min(rest.len()));
+        segments.push(segment);
+        rest = after;
+    }
+    if segments.is_empty() {
+        segments.push(rest);
+    }
+    let mut segments = segments.into_iter();
+    decoder.mq.start(segments.next().unwrap());
+    let mut plane = num_planes - 1;
+    for pass in 0..code_block.num_passes {
+        if pass > 0 && !code_block.pass_lengths.is_empty() {
+            decoder.mq.start(segments.next().unwrap_or_default());
+        }
+        match pass % 3 {
+            0 => {
+                decoder.cleanup(plane);
+                plane = plane.saturating_sub(1);
+            }
+            1 => decoder.significance_propagation(plane),
+            _ => decoder.magnitude_refinement(plane),
+        }
+        if style & RESET != 0 {
+            decoder.mq.reset_contexts();
+        }
+    }
+
+    // The decoder works with one extra bit of precision, which rounds partially decoded
+    // coefficients to the middle of their interval.
+    let rect = code_block.rect;
+    let band_width = band.rect.width();
+    for y in 0..rect.height() {
+        for x in 0..rect.width() {
+            let i = (rect.y0 - band.rect.y0) as usize + y;
+            let j = (rect.x0 - band.rect.x0) as usize + x;
+            band.coefficients[i * band_width + j] = decoder.data[y * decoder.width + x] / 2;
+        }
+    }
+    Ok(())
+}
+
+struct CodeBlockDecoder<'a> {
+    width: usize,
+    height: usize,
+    orientation: Orientation,
+    vertically_causal: bool,
+    segmentation_symbols: bool,
+
+    /// With a border of one coefficient, so neighbours are never out of bounds.
+    flags: Vec<u8>,
+    data: Vec<i32>,
+    mq: MqDecoder<'a>,
+}
+
+impl<'a> CodeBlockDecoder<'a> {
+    fn new(rect: Rect, orientation: Orientation, style: u8) -> Self {
+        let (width, height) = (rect.width(), rect.height());
+        Self {
+            width,
+            height,
+            orientation,
+            vertically_causal: style & VERTICALLY_CAUSAL != 0,
+            segmentation_symbols: style & SEGMENTATION_SYMBOLS != 0,
+            flags: vec![0; (width + 2) * (height + 2)],
+            data: vec![0; width * height],
+            mq: MqDecoder::new(),
+        }
+    }
+
+    fn flag_index(&self, x: usize, y: usize) -> usize {
+        (y + 1) * (self.width + 2) + x + 1
+    }
+
+    /// The flags of the eight neighbours: left, right, up, down, then the diagonals.
+    fn neighbours(&self, x: usize, y: usize) -> [u8; 8] {
+        let i = self.flag_index(x, y);
+        let row = self.width + 2;
+        let mut neighbours = [
+            self.flags[i - 1],
+            self.flags[i + 1],
+            self.flags[i - row],
+            self.flags[i + row],
+            self.flags[i - row - 1],
+            self.flags[i - row + 1],
+            self.flags[i + row - 1],
+            self.flags[i + row + 1],
+        ];
+        // In vertically causal mode, the next stripe doesn't count.
+        if self.vertically_causal && y % 4 == 3 {
+            for flags in [3, 6, 7] {
+                neighbours[flags] = 0;
+            }
+        }
+        neighbours
+    }
+
+    /// Table D.1.
+    fn zero_coding_context(&self, x: usize, y: usize) -> usize {
+        let neighbours = self.neighbours(x, y);
+        let count = |range: std::ops::Range<usize>| {
+            neighbours[range]
+                .iter()
+                .filter(|&&flags| flags & SIGNIFICANT != 0)
+                .count()
+        };
+        let (mut h, mut v, d) = (count(0..2), count(2..4), count(4..8));
+        match self.orientation {
+            Orientation::HH => match (d, h + v) {
+                (0, 0) => 0,
+                (0, 1) => 1,
+                (0, _) => 2,
+                (1, 0) => 3,
+                (1, 1) => 4,
+                (1, _) => 5,
+                (2, 0) => 6,
+                (2, _) => 7,
+                _ => 8,
+            },
+            orientation => {
+                if orientation == Orientation::HL {
+                    (h, v) = (v, h);
+                }
+                match (h, v, d) {
+                    (0, 0, 0) => 0,
+                    (0, 0, 1) => 1,
+                    (0, 0, _) => 2,
+                    (0, 1, _) => 3,
+                    (0, _, _) => 4,
+                    (1, 0, 0) => 5,
+                    (1, 0, _) => 6,
+                    (1, _, _) => 7,
+                    _ => 8,
+                }
+            }
+        }
+    }
+
+    fn has_significant_neighbour(&self, x: usize, y: usize) -> bool {
+        self.neighbours(x, y)
+            .iter()
+            .any(|&flags| flags & SIGNIFICANT != 0)
+    }
+
+    /// Decode the sign of a coefficient which has just become significant (table D.3), and
+    /// set its magnitude to 1.5 times `2^plane`.
+    fn decode_sign(&mut self, x: usize, y: usize, plane: u8) {
+        let neighbours = self.neighbours(x, y);
+        let contribution = |flags: u8| match (flags & SIGNIFICANT != 0, flags & NEGATIVE != 0) {
+            (false, _) => 0,
+            (true, false) => 1,
+            (true, true) => -1,
+        };
+        let h = (contribution(neighbours[0]) + contribution(neighbours[1])).clamp(-1, 1);
+        let v = (contribution(neighbours[2]) + contribution(neighbours[3])).clamp(-1, 1);
+        let (context, xor) = match (h, v) {
+            (1, 1) => (13, 0),
+            (1, 0) => (12, 0),
+            (1, _) => (11, 0),
+            (0, 1) => (10, 0),
+            (0, 0) => (9, 0),
+            (0, _) => (10, 1),
+            (_, 1) => (11, 1),
+            (_, 0) => (12, 1),
+            _ => (13, 1),
+        };
+        let negative = self.mq.decode(context) ^ xor == 1;
+        let magnitude = 3 << plane;
+        self.data[y * self.width + x] = if negative { -magnitude } else { magnitude };
+        let i = self.flag_index(x, y);
+        self.flags[i] |= SIGNIFICANT | if negative { NEGATIVE } else { 0 };
+    }
+
+    /// The coefficients in stripe order: stripes of four rows, column by column (D.1).
+    fn stripe_columns(&self) -> impl Iterator<Item = (usize, std::ops::Range<usize>)> {
+        let (width, height) = (self.width, self.height);
+        (0..height)
+            .step_by(4)
+            .flat_map(move |y0| (0..width).map(move |x| (x, y0..(y0 + 4).min(height))))
+    }
+
+    /// D.3.1.
+    fn significance_propagation(&mut self, plane: u8) {
+        for (x, rows) in self.stripe_columns().collect::<Vec<_>>() {
+            for y in rows {
+                let i = self.flag_index(x, y);
+                if self.flags[i] & SIGNIFICANT != 0 || !self.has_significant_neighbour(x, y) {
+                    continue;
+                }
+                let context = self.zero_coding_context(x, y);
+                if self.mq.decode(context) == 1 {
+                    self.decode_sign(x, y, plane);
+                }
+                self.flags[i] |= VISITED;
+            }
+        }
+    }
+
+    /// D.3.3.
+    fn magnitude_refinement(&mut self, plane: u8) {
+        for (x, rows) in self.stripe_columns().collect::<Vec<_>>() {
+            for y in rows {
+                let i = self.flag_index(x, y);
+                if self.flags[i] & (SIGNIFICANT | VISITED) != SIGNIFICANT {
+                    continue;
+                }
+                let context = if self.flags[i] & REFINED != 0 {
+                    16
+                } else if self.has_significant_neighbour(x, y) {
+                    15
+                } else {
+                    14
+                };
+                let half = 1 << plane;
+                let value = &mut self.data[y * self.width + x];
+                let correction = if self.mq.decode(context) == 1 {
+                    half
+                } else {
+                    -half
+                };
+                *value += value.signum() * correction;
+                self.flags[i] |= REFINED;
+            }
+        }
+    }
+
+    /// D.3.4.
+    fn cleanup(&mut self, plane: u8) {
+        for (x, rows) in self.stripe_columns().collect::<Vec<_>>() {
+            let mut start = rows.start;
+            let run_length = rows.len() == 4
+                && rows.clone().all(|y| {
+                    self.flags[self.flag_index(x, y)] == 0 && !self.has_significant_neighbour(x, y)
+                });
+            if run_length {
+                if self.mq.decode(RUN_LENGTH) == 0 {
+                    continue;
+                }
+                let offset = self.mq.decode(UNIFORM) << 1 | self.mq.decode(UNIFORM);
+                let y = rows.start + offset as usize;
+                self.decode_sign(x, y, plane);
+                start = y + 1;
+            }
+            for y in start..rows.end {
+                let i = self.flag_index(x, y);
+                if self.flags[i] & (SIGNIFICANT | VISITED) != 0 {
+                    continue;
+                }
+                let context = self.zero_coding_context(x, y);
+                if self.mq.decode(context) == 1 {
+                    self.decode_sign(x, y, plane);
+                }
+            }
+        }
+        if self.segmentation_symbols {
+            for _ in 0..4 {
+                self.mq.decode(UNIFORM);
+            }
+        }
+        for flags in &mut self.flags {
+            *flags &= !VISITED;
+        }
+    }
+}
+
+/// The MQ arithmetic decoder (annex C), with the software conventions of C.3.
+struct MqDecoder<'a> {
+    data: &'a [u8],
+    pos: usize,
+    c: u32,
+    a: u32,
+    ct: u32,
+
+    /// The state (an index into `QE`) and most probable symbol of each context.
+    contexts: [(u8, u8); 19],
+}
+
+/// Qe, the next states after an MPS and after an LPS, and whether to switch the MPS (table C.2).
+const QE: [(u32, u8, u8, bool); 47] = [
+    (0x5601, 1, 1, true),
+    (0x3401, 2, 6, false),
+    (0x1801, 3, 9, false),
+    (0x0AC1, 4, 12, false),
+    (0x0521, 5, 29, false),
+    (0x0221, 38, 33, false),
+    (0x5601, 7, 6, true),
+    (0x5401, 8, 14, false),
+    (0x4801, 9, 14, false),
+    (0x3801, 10, 14, false),
+    (0x3001, 11, 17, false),
+    (0x2401, 12, 18, false),
+    (0x1C01, 13, 20, false),
+    (0x1601, 29, 21, false),
+    (0x5601, 15, 14, true),
+    (0x5401, 16, 14, false),
+    (0x5101, 17, 15, false),
+    (0x4801, 18, 16, false),
+    (0x3801, 19, 17, false),
+    (0x3401, 20, 18, false),
+    (0x3001, 21, 19, false),
+    (0x2801, 22, 19, false),
+    (0x2401, 23, 20, false),
+    (0x2201, 24, 21, false),
+    (0x1C01, 25, 22, false),
+    (0x1801, 26, 23, false),
+    (0x1601, 27, 24, false),
+    (0x1401, 28, 25, false),
+    (0x1201, 29, 26, false),
+    (0x1101, 30, 27, false),
+    (0x0AC1, 31, 28, false),
+    (0x09C1, 32, 29, false),
+    (0x08A1, 33, 30, false),
+    (0x0521, 34, 31, false),
+    (0x0441, 35, 32, false),
+    (0x02A1, 36, 33, false),
+    (0x0221, 37, 34, false),
+    (0x0141, 38, 35, false),
+    (0x0111, 39, 36, false),
+    (0x0085, 40, 37, false),
+    (0x0049, 41, 38, false),
+    (0x0025, 42, 39, false),
+    (0x0015, 43, 40, false),
+    (0x0009, 44, 41, false),
+    (0x0005, 45, 42, false),
+    (0x0001, 45, 43, false),
+    (0x5601, 46, 46, false),
+];
+
+impl<'a> MqDecoder<'a> {
+    fn new() -> Self {
+        let mut decoder = Self {
+            data: &[],
+            pos: 0,
+            c: 0,
+            a: 0,
+            ct: 0,
+            contexts: [(0, 0); 19],
+        };
+        decoder.reset_contexts();
+        decoder
+    }
+
+    /// Table D.7.
+    fn reset_contexts(&mut self) {
+        self.contexts = [(0, 0); 19];
+        self.contexts[0] = (4, 0);
+        self.contexts[RUN_LENGTH] = (3, 0);
+        self.contexts[UNIFORM] = (46, 0);
+    }
+
+    /// INITDEC, for a new codeword segment. The contexts carry on from the previous segment.
+    fn start(&mut self, data: &'a [u8]) {
+        self.data = data;
+        self.pos = 0;
+        self.c = u32::from(self.byte(0)) << 16;
+        self.byte_in();
+        self.c <<= 7;
+        self.ct -= 7;
+        self.a = 0x8000;
+    }
+
+    /// Past the end of the data, the decoder sees 0xFF bytes, like the end of a segment.
+    fn byte(&self, pos: usize) -> u8 {
+        self.data.get(pos).copied().unwrap_or(0xFF)
+    }
+
+    fn byte_in(&mut self) {
+        if self.byte(self.pos) == 0xFF {
+            if self.byte(self.pos + 1) > 0x8F {
+                self.c += 0xFF00;
+                self.ct = 8;
+            } else {
+                self.pos += 1;
+                self.c += u32::from(self.byte(self.pos)) << 9;
+                self.ct = 7;
+            }
+        } else {
+            self.pos += 1;
+            self.c += u32::from(self.byte(self.pos)) << 8;
+            self.ct = 8;
+        }
+    }
+
+    fn decode(&mut self, context: usize) -> u8 {
+        let (state, mps) = self.contexts[context];
+        let (qe, next_mps, next_lps, switch) = QE[usize::from(state)];
+        self.a -= qe;
+        // The LPS is the bottom part of the interval (C.3.2).
+        let decision;
+        if (self.c >> 16) < qe {
+            // LPS_EXCHANGE.
+            if self.a < qe {
+                decision = mps;
+                self.contexts[context] = (next_mps, mps);
+            } else {
+                decision = 1 - mps;
+                self.contexts[context] = (next_lps, if switch { 1 - mps } else { mps });
+            }
+            self.a = qe;
+        } else {
+            self.c -= qe << 16;
+            if self.a & 0x8000 != 0 {
+                return mps;
+            }
+            // MPS_EXCHANGE.
+            if self.a < qe {
+                decision = 1 - mps;
+                self.contexts[context] = (next_lps, if switch { 1 - mps } else { mps });
+            } else {
+                decision = mps;
+                self.contexts[context] = (next_mps, mps);
+            }
+        }
+        // RENORMD.
+        loop {
+            if self.ct == 0 {
+                self.byte_in();
+            }
+            self.a <<= 1;
+            self.c <<= 1;
+            self.ct -= 1;
+            if self.a & 0x8000 != 0 {
+                break;
+            }
+        }
+        decision
+    }
+}
+
+/// Reconstruct the tile-component from its subbands with the reversible 5/3 wavelet (F.3).
+/// Returns the samples, in raster order, and where they are.
+fn inverse_wavelet_transform(resolutions: Vec<Resolution>) -> (Vec<i32>, Rect) {
+    let mut resolutions = resolutions.into_iter();
+    let lowest = resolutions.next().unwrap();
+    let mut rect = lowest.rect;
+    let mut samples = lowest.bands.into_iter().next().unwrap().coefficients;
+    for resolution in resolutions {
+        let low = rect;
+        rect = resolution.rect;
+        let (width, height) = (rect.width(), rect.height());
+        let mut interleaved = vec![0; width * height];
+
+        // 2D_INTERLEAVE (F.3.3): low-pass coefficients go at even coordinates.
+        let [hl, lh, hh] = <[Band; 3]>::try_from(resolution.bands).ok().unwrap();
+        for (row, y) in (rect.y0..rect.y1).enumerate() {
+            for (column, x) in (rect.x0..rect.x1).enumerate() {
+                let (band, band_rect) = match (x % 2 == 0, y % 2 == 0) {
+                    (true, true) => (&samples, low),
+                    (false, true) => (&hl.coefficients, hl.rect),
+                    (true, false) => (&lh.coefficients, lh.rect),
+                    (false, false) => (&hh.coefficients, hh.rect),
+                };
+                let (bx, by) = (
+                    x.div_euclid(2) - band_rect.x0,
+                    y.div_euclid(2) - band_rect.y0,
+                );
+                interleaved[row * width + column] =
+                    band[by as usize * band_rect.width() + bx as usize];
+            }
+        }
+
+        // HOR_SR (F.3.4), then VER_SR (F.3.5).
+        for row in interleaved.chunks_mut(width.max(1)) {
+            inverse_53(row, rect.x0);
+        }
+        let mut column = vec![0; height];
+        for x in 0..width {
+            for y in 0..height {
+                column[y] = interleaved[y * width + x];
+            }
+            inverse_53(&mut column, rect.y0);
+            for y in 0..height {
+                interleaved[y * width + x] = column[y];
+            }
+        }
+        samples = interleaved;
+    }
+    (samples, rect)
+}
+
+/// 1D_SR with the reversible 5/3 filter (F.3.8.1), in place. `i0` is the coordinate of the
+/// first sample, whose parity says whether it's low-pass or high-pass.
+fn inverse_53(x: &mut [i32], i0: i64) {
+    let n = x.len() as isize;
+    if n == 1 {
+        if i0 % 2 != 0 {
+            x[0] /= 2;
+        }
+        return;
+    }
+    // Symmetric extension (F.3.7).
+    let at = |x: &[i32], i: isize| {
+        let i = if i < 0 {
+            -i
+        } else if i >= n {
+            2 * (n - 1) - i
+        } else {
+            i
+        };
+        x[i as usize]
+    };
+    let first_even = (i0 % 2) as isize;
+    for i in (first_even..n).step_by(2) {
+        x[i as usize] -= (at(x, i - 1) + at(x, i + 1) + 2) >> 2;
+    }
+    for i in (1 - first_even..n).step_by(2) {
+        x[i as usize] += (at(x, i - 1) + at(x, i + 1)) >> 1;
+    }
+}
+
+fn ceil_div(n: i64, d: i64) -> i64 {
+    (n + d - 1).div_euclid(d)
+}
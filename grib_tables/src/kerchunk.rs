@@ -0,0 +1,507 @@
+//! Export a [`DatasetIndex`] as [kerchunk](https://fsspec.github.io/kerchunk/) references, so
+//! that xarray can open a whole collection of GRIB2 files as one Zarr dataset without copying
+//! any data.
+
+use std::{
+    collections::{HashMap, HashSet},
+    io::Write,
+};
+
+use serde_json::{json, Map, Value};
+
+use crate::{
+    constituent::Constituent,
+    dataset::{CellIndex, DatasetIndex, Location},
+    error::Error,
+    grid::Grid,
+    level::{FixedSurface, Level},
+    AbbrevToProduct,
+};
+
+/// Write version 1 kerchunk references to `writer`, as JSON.
+///
+/// There is one array per product (and constituent) and type of level, e.g. `TMP_isobaric` and
+/// `TMP_heightAboveGround`. Every array has the dimensions `init_time`, `step`, a member
+/// dimension, a level dimension, `y` and `x`, and each chunk is one field, i.e. one GRIB2
+/// message. Cells which no `.idx` file mentions are missing chunks, which read as NaN. The level
+/// dimension is named after the type of level, and is shared by every array with the same levels.
+///
+/// The member dimension only holds the ensemble members, derived forecasts or probabilities which
+/// [`DatasetIndex::expected_ensembles`] expects for the array, so that (for example) a
+/// deterministic forecast isn't padded out to the thresholds of another product's probabilities.
+/// It's called `member`, with a numeric suffix for each different set of entries.
+///
+/// Each chunk is decoded by kerchunk's `grib` codec, which only decodes the first field of a
+/// message.
+///
+/// Returns an error if the end of a message isn't known (because the length of its file wasn't
+/// given to [`DatasetIndexer::add_idx`](crate::dataset::DatasetIndexer::add_idx)), if a step is in
+/// calendar units, which can't be stored as a number of minutes, or if several fields share a
+/// message (such as `UGRD` and `VGRD` in records `3.1` and `3.2` of a `.idx` file), because the
+/// codec would decode the first field for all of them.
+pub fn write_references<W: Write>(
+    index: &DatasetIndex,
+    grid: &Grid,
+    writer: W,
+) -> Result<(), Error> {
+    let references = json!({ "version": 1, "refs": refs(index, grid)? });
+    serde_json::to_writer(writer, &references).map_err(|err| Error::Io(err.into()))
+}
+
+fn refs(index: &DatasetIndex, grid: &Grid) -> Result<Map<String, Value>, Error> {
+    let mut refs = Map::new();
+    refs.insert(
+        ".zgroup".to_string(),
+        json_string(&json!({ "zarr_format": 2 })),
+    );
+    check_one_field_per_message(index)?;
+    add_dimensions(&mut refs, index)?;
+
+    let (nx, ny) = grid.dimensions();
+    let mut member_dims = MemberDimensions::default();
+    let mut level_dims = LevelDimensions::default();
+    for variable in variables(index) {
+        let member_dim = member_dims.name(&mut refs, index, &variable.ensembles);
+        let level_dim = level_dims.name(&mut refs, index, &variable.levels);
+        let (abbrev, constituent) = index.products()[variable.product];
+        let level_type = level_type_name(&index.levels()[variable.levels[0]]);
+        let name = variable_name(&refs, abbrev, constituent, &level_type);
+        let shape = [
+            index.init_times().len(),
+            index.steps().len(),
+            variable.ensembles.len(),
+            variable.levels.len(),
+            ny as usize,
+            nx as usize,
+        ];
+        let mut attrs = json!({
+            "_ARRAY_DIMENSIONS": ["init_time", "step", member_dim, level_dim, "y", "x"],
+            "GRIB_abbrev": abbrev,
+            "GRIB_typeOfLevel": level_type,
+        });
+        if let Some(product) = index.center_and_table_versions().abbrev_to_product(abbrev) {
+            attrs["long_name"] = product.name().into();
+            attrs["units"] = product.unit().into();
+            attrs["GRIB_discipline"] = product.discipline_num().into();
+            attrs["GRIB_category"] = product.category_num().into();
+            attrs["GRIB_parameter"] = product.parameter_num().into();
+        }
+        if let Some(constituent) = constituent {
+            attrs["GRIB_constituent"] = constituent.to_idx_fields().join(":").into();
+        }
+        add_array(
+            &mut refs,
+            &name,
+            json!({
+                "shape": shape,
+                "chunks": [1, 1, 1, 1, ny, nx],
+                "dtype": "<f8",
+                "compressor": null,
+                "fill_value": "NaN",
+                "filters": [{ "id": "grib", "var": abbrev }],
+                "order": "C",
+                "zarr_format": 2,
+            }),
+            attrs,
+        );
+        for (cell_index, location) in variable.cells {
+            let [init_time, step, ensemble, level, _] = *cell_index;
+            let ensemble = variable.ensembles.binary_search(&ensemble).unwrap();
+            let level = variable.levels.iter().position(|&l| l == level).unwrap();
+            refs.insert(
+                format!("{name}/{init_time}.{step}.{ensemble}.{level}.0.0"),
+                chunk(index, location)?,
+            );
+        }
+    }
+    Ok(refs)
+}
+
+/// The cells of one array.
+struct Variable<'a> {
+    /// Index into [`DatasetIndex::products`].
+    product: usize,
+
+    /// Indices into [`DatasetIndex::ensembles`], in order.
+    ensembles: Vec<usize>,
+
+    /// Indices into [`DatasetIndex::levels`], in order.
+    levels: Vec<usize>,
+    cells: Vec<(&'a CellIndex, &'a Location)>,
+}
+
+/// Group the cells by product and type of level, in the order in which the products and levels
+/// were first seen.
+fn variables(index: &DatasetIndex) -> Vec<Variable<'_>> {
+    let level_types: Vec<_> = index.levels().iter().map(level_type).collect();
+    let mut variables: Vec<Variable> = Vec::new();
+    let mut positions: HashMap<(usize, (u8, Option<u8>)), usize> = HashMap::new();
+    let mut cells: Vec<_> = index.cells().collect();
+    cells.sort_unstable_by_key(|(&[_, _, _, level, product], _)| (product, level));
+    for (cell_index, location) in cells {
+        let [_, _, _, level, product] = *cell_index;
+        let i = *positions
+            .entry((product, level_types[level]))
+            .or_insert_with(|| {
+                variables.push(Variable {
+                    product,
+                    ensembles: Vec::new(),
+                    levels: Vec::new(),
+                    cells: Vec::new(),
+                });
+                variables.len() - 1
+            });
+        let variable = &mut variables[i];
+        if variable.levels.last() != Some(&level) {
+            variable.levels.push(level);
+        }
+        if !variable.ensembles.contains(&cell_index[2]) {
+            variable.ensembles.push(cell_index[2]);
+        }
+        variable.cells.push((cell_index, location));
+    }
+    for variable in &mut variables {
+        variable.ensembles = index.expected_ensembles(&variable.ensembles);
+    }
+    variables
+}
+
+/// Member dimensions, keyed by the ensemble entries along them.
+#[derive(Default)]
+struct MemberDimensions {
+    names: HashMap<Vec<usize>, String>,
+}
+
+impl MemberDimensions {
+    /// The name of the dimension with exactly `ensembles`, adding it to `refs` if it's new.
+    fn name(
+        &mut self,
+        refs: &mut Map<String, Value>,
+        index: &DatasetIndex,
+        ensembles: &[usize],
+    ) -> String {
+        if let Some(name) = self.names.get(ensembles) {
+            return name.clone();
+        }
+        let name = unique_name(refs, "member");
+        let members: Vec<String> = ensembles
+            .iter()
+            .map(|&i| {
+                index.ensembles()[i]
+                    .map_or_else(String::new, |ensemble| ensemble.to_idx_fields().join(":"))
+            })
+            .collect();
+        add_string_coordinate(
+            refs,
+            &name,
+            &members,
+            json!({ "long_name": "ensemble member, derived forecast or probability" }),
+        );
+        self.names.insert(ensembles.to_vec(), name.clone());
+        name
+    }
+}
+
+/// Level dimensions, keyed by the levels along them.
+#[derive(Default)]
+struct LevelDimensions {
+    names: HashMap<Vec<usize>, String>,
+}
+
+impl LevelDimensions {
+    /// The name of the dimension with exactly `levels`, adding it to `refs` if it's new.
+    /// Different sets of levels of the same type get a numeric suffix, e.g. `isobaric` and
+    /// `isobaric_2`.
+    fn name(
+        &mut self,
+        refs: &mut Map<String, Value>,
+        index: &DatasetIndex,
+        levels: &[usize],
+    ) -> String {
+        if let Some(name) = self.names.get(levels) {
+            return name.clone();
+        }
+        let levels_of_type: Vec<_> = levels.iter().map(|&i| index.levels()[i]).collect();
+        let base = level_type_name(&levels_of_type[0]);
+        let name = unique_name(refs, &base);
+        add_level_coordinate(refs, &name, &base, &levels_of_type);
+        self.names.insert(levels.to_vec(), name.clone());
+        name
+    }
+}
+
+/// e.g. `TMP_heightAboveGround`, or `MASSDEN_ozone_heightAboveGround` if the product has a
+/// constituent.
+fn variable_name(
+    refs: &Map<String, Value>,
+    abbrev: &str,
+    constituent: Option<Constituent>,
+    level_type: &str,
+) -> String {
+    let base = match constituent {
+        Some(constituent) => {
+            let constituent = constituent.name().to_lowercase().replace(' ', "_");
+            format!("{abbrev}_{constituent}_{level_type}")
+        }
+        None => format!("{abbrev}_{level_type}"),
+    };
+    // e.g. the same aerosol with different ranges of particle sizes.
+    unique_name(refs, &base)
+}
+
+/// `base`, or `base` with a numeric suffix if an array called `base` already exists.
+fn unique_name(refs: &Map<String, Value>, base: &str) -> String {
+    (1..)
+        .map(|n| match n {
+            1 => base.to_string(),
+            n => format!("{base}_{n}"),
+        })
+        .find(|name| !refs.contains_key(&format!("{name}/.zarray")))
+        .unwrap()
+}
+
+fn add_dimensions(refs: &mut Map<String, Value>, index: &DatasetIndex) -> Result<(), Error> {
+    let seconds: Vec<i64> = index
+        .init_times()
+        .iter()
+        .map(|init_time| init_time.seconds_since_epoch())
+        .collect();
+    add_coordinate(
+        refs,
+        "init_time",
+        "<i8",
+        &seconds
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect::<Vec<_>>(),
+        seconds.len(),
+        json!({
+            "units": "seconds since 1970-01-01T00:00:00",
+            "calendar": "proleptic_gregorian",
+            "long_name": "initial time of forecast",
+        }),
+    );
+
+    let minutes = index
+        .steps()
+        .iter()
+        .map(|step| {
+            let minutes_per_unit = step
+                .time_unit
+                .known()
+                .and_then(|time_unit| time_unit.minutes())
+                .ok_or(Error::CannotExport {
+                    reason: "steps in calendar units, or in units which aren't in code table 4.4, \
+                             can't be converted to minutes",
+                })?;
+            let end = step.end().ok_or(Error::CannotExport {
+                reason: "a step's time range ends after the largest forecast time GRIB2 allows",
+            })?;
+            Ok(i64::from(minutes_per_unit) * i64::from(end))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    add_coordinate(
+        refs,
+        "step",
+        "<i8",
+        &minutes
+            .iter()
+            .flat_map(|m| m.to_le_bytes())
+            .collect::<Vec<_>>(),
+        minutes.len(),
+        json!({
+            "units": "minutes",
+            "long_name": "time since forecast_reference_time, at the end of any time range",
+        }),
+    );
+
+    Ok(())
+}
+
+/// Numbers if every level is a single surface with a value (e.g. 500 in hPa for "500 mb"), or
+/// the text which `wgrib2` prints for each level otherwise.
+fn add_level_coordinate(
+    refs: &mut Map<String, Value>,
+    name: &str,
+    level_type: &str,
+    levels: &[Level],
+) {
+    let values: Option<Vec<f64>> = levels
+        .iter()
+        .map(|level| match level.second {
+            None => level.first.value.map(|value| value.value()),
+            Some(_) => None,
+        })
+        .collect();
+    let long_name = FixedSurface {
+        type_num: levels[0].first.type_num,
+        value: None,
+    }
+    .to_string();
+    match values {
+        Some(values) => add_coordinate(
+            refs,
+            name,
+            "<f8",
+            &values
+                .iter()
+                .flat_map(|v| v.to_le_bytes())
+                .collect::<Vec<_>>(),
+            values.len(),
+            json!({ "long_name": long_name, "GRIB_typeOfLevel": level_type }),
+        ),
+        None => add_string_coordinate(
+            refs,
+            name,
+            &levels.iter().map(Level::to_string).collect::<Vec<_>>(),
+            json!({ "long_name": long_name, "GRIB_typeOfLevel": level_type }),
+        ),
+    }
+}
+
+/// A one dimensional coordinate array whose only chunk is stored inline.
+fn add_coordinate(
+    refs: &mut Map<String, Value>,
+    name: &str,
+    dtype: &str,
+    bytes: &[u8],
+    len: usize,
+    mut attrs: Value,
+) {
+    attrs["_ARRAY_DIMENSIONS"] = json!([name]);
+    add_array(
+        refs,
+        name,
+        json!({
+            "shape": [len],
+            "chunks": [len.max(1)],
+            "dtype": dtype,
+            "compressor": null,
+            "fill_value": null,
+            "filters": null,
+            "order": "C",
+            "zarr_format": 2,
+        }),
+        attrs,
+    );
+    refs.insert(
+        format!("{name}/0"),
+        format!("base64:{}", base64(bytes)).into(),
+    );
+}
+
+/// Fixed-width byte strings, padded with zeros.
+fn add_string_coordinate(
+    refs: &mut Map<String, Value>,
+    name: &str,
+    values: &[String],
+    attrs: Value,
+) {
+    let width = values.iter().map(String::len).max().unwrap_or(0).max(1);
+    let mut bytes = Vec::with_capacity(values.len() * width);
+    for value in values {
+        bytes.extend(value.bytes());
+        bytes.resize(bytes.len() + width - value.len(), 0);
+    }
+    add_coordinate(
+        refs,
+        name,
+        &format!("|S{width}"),
+        &bytes,
+        values.len(),
+        attrs,
+    );
+}
+
+fn add_array(refs: &mut Map<String, Value>, name: &str, zarray: Value, zattrs: Value) {
+    refs.insert(format!("{name}/.zarray"), json_string(&zarray));
+    refs.insert(format!("{name}/.zattrs"), json_string(&zattrs));
+}
+
+/// `[url, offset, length]`.
+fn check_one_field_per_message(index: &DatasetIndex) -> Result<(), Error> {
+    let mut messages = HashSet::new();
+    for (_, location) in index.cells() {
+        if !messages.insert((location.file_index, location.start)) {
+            return Err(Error::CannotExport {
+                reason: "several fields share a message, and kerchunk's grib codec only decodes \
+                         the first",
+            });
+        }
+    }
+    Ok(())
+}
+
+fn chunk(index: &DatasetIndex, location: &Location) -> Result<Value, Error> {
+    let end = location.end.ok_or(Error::CannotExport {
+        reason: "the length of the last message in a file isn't known",
+    })?;
+    Ok(json!([
+        index.files()[location.file_index],
+        location.start,
+        end - location.start,
+    ]))
+}
+
+/// Zarr stores metadata as JSON documents, which kerchunk embeds as strings.
+fn json_string(value: &Value) -> Value {
+    Value::String(value.to_string())
+}
+
+/// The types of the first and second fixed surfaces.
+fn level_type(level: &Level) -> (u8, Option<u8>) {
+    (
+        level.first.type_num,
+        level.second.map(|second| second.type_num),
+    )
+}
+
+/// The name of the level's type, like ecCodes' `typeOfLevel`, e.g. "heightAboveGround", or
+/// "depthBelowLandLayer" for a layer between two surfaces of the same type.
+fn level_type_name(level: &Level) -> String {
+    let name = |type_num| match type_num {
+        1 => "surface".to_string(),
+        7 => "tropopause".to_string(),
+        8 => "nominalTop".to_string(),
+        10 => "atmosphere".to_string(),
+        100 => "isobaric".to_string(),
+        101 => "meanSea".to_string(),
+        102 => "heightAboveSea".to_string(),
+        103 => "heightAboveGround".to_string(),
+        104 => "sigma".to_string(),
+        105 => "hybrid".to_string(),
+        106 => "depthBelowLand".to_string(),
+        107 => "theta".to_string(),
+        108 => "pressureFromGround".to_string(),
+        200 => "atmosphereSingleLayer".to_string(),
+        // etc.
+        type_num => format!("level{type_num}"),
+    };
+    match level.second {
+        Some(second) if second.type_num == level.first.type_num => {
+            format!("{}Layer", name(level.first.type_num))
+        }
+        Some(second) => format!("{}_{}", name(level.first.type_num), name(second.type_num)),
+        None => name(level.first.type_num),
+    }
+}
+
+/// Standard base64, with padding.
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for group in bytes.chunks(3) {
+        let n = group
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &byte)| n | u32::from(byte) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= group.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
@@ -0,0 +1,335 @@
+use std::fmt;
+
+use crate::{code_table::CodeValue, error::Error, idx::format_g, message::Section};
+
+/// A number stored in GRIB2 as `scaled_value * 10^-scale_factor`.
+///
+/// The same number can be encoded in many ways (e.g. 2 m could be stored as 2 with scale factor 0
+/// or as 20 with scale factor 1), so we normalise on construction. This means that values which
+/// are numerically equal always compare (and hash) equal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ScaledValue {
+    scale_factor: i8,
+    scaled_value: i32,
+}
+
+impl ScaledValue {
+    pub fn new(mut scale_factor: i8, mut scaled_value: i32) -> Self {
+        if scaled_value == 0 {
+            scale_factor = 0;
+        }
+        while scaled_value != 0 && scaled_value % 10 == 0 && scale_factor > i8::MIN {
+            scaled_value /= 10;
+            scale_factor -= 1;
+        }
+        Self {
+            scale_factor,
+            scaled_value,
+        }
+    }
+
+    /// Find the scaled representation of a decimal number, e.g. one parsed from a `.idx` file.
+    /// Returns `None` if `value` can't be represented with at most 9 decimal places.
+    pub(crate) fn from_f64(value: f64) -> Option<Self> {
+        (0..=9).find_map(|scale_factor: i8| {
+            let scaled = value * 10f64.powi(scale_factor.into());
+            let rounded = scaled.round();
+            let tolerance = 1e-9 * rounded.abs().max(1.0);
+            ((scaled - rounded).abs() <= tolerance && rounded.abs() <= i32::MAX as f64)
+                .then(|| Self::new(scale_factor, rounded as i32))
+        })
+    }
+
+    /// Read a scale factor (1 octet) followed by a scaled value (4 octets).
+    /// Returns `None` if either is set to "missing" (all bits set to 1).
+    pub(crate) fn parse(section: &Section, first_octet: usize) -> Result<Option<Self>, Error> {
+        if section.u8(first_octet)? == u8::MAX || section.u32(first_octet + 1)? == u32::MAX {
+            return Ok(None);
+        }
+        Ok(Some(Self::new(
+            section.i8(first_octet)?,
+            section.i32(first_octet + 1)?,
+        )))
+    }
+
+    pub fn scale_factor(&self) -> i8 {
+        self.scale_factor
+    }
+
+    pub fn scaled_value(&self) -> i32 {
+        self.scaled_value
+    }
+
+    pub fn value(&self) -> f64 {
+        // Dividing by an exact power of ten is more accurate than multiplying by an inexact one,
+        // e.g. 25 / 1e7 is exactly the nearest f64 to 2.5e-6.
+        let scale_factor = i32::from(self.scale_factor);
+        if scale_factor >= 0 {
+            self.scaled_value as f64 / 10f64.powi(scale_factor)
+        } else {
+            self.scaled_value as f64 * 10f64.powi(-scale_factor)
+        }
+    }
+}
+
+/// A fixed surface from section 4, e.g. "500 mb" or "2 m above ground".
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FixedSurface {
+    /// Code table 4.5.
+    pub type_num: u8,
+
+    /// In the SI unit given by code table 4.5. e.g. Pa for isobaric surfaces.
+    pub value: Option<ScaledValue>,
+}
+
+/// A vertical level: either a single fixed surface, or a layer between two fixed surfaces.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Level {
+    pub first: FixedSurface,
+    pub second: Option<FixedSurface>,
+}
+
+impl Level {
+    /// Read the type of first fixed surface (at `first_octet`) and the five fields which follow
+    /// it. In product definition template 4.0 and friends, `first_octet` is 23.
+    pub(crate) fn parse(section: &Section, first_octet: usize) -> Result<Self, Error> {
+        let first = FixedSurface {
+            type_num: section.u8(first_octet)?,
+            value: ScaledValue::parse(section, first_octet + 1)?,
+        };
+        let second_type_num = section.u8(first_octet + 6)?;
+        let second = match second_type_num {
+            255 => None, // 255 means "missing"
+            _ => Some(FixedSurface {
+                type_num: second_type_num,
+                value: ScaledValue::parse(section, first_octet + 7)?,
+            }),
+        };
+        Ok(Self { first, second })
+    }
+
+    /// Parse the text which `wgrib2` prints for a level (the inverse of `Display`).
+    /// Returns `None` if we don't recognise the text.
+    pub fn from_idx_str(s: &str) -> Option<Self> {
+        if let Some(first) = FixedSurface::from_idx_str(s) {
+            return Some(Self {
+                first,
+                second: None,
+            });
+        }
+        if let Some(layer) = Self::layer_from_idx_str(s) {
+            return Some(layer);
+        }
+        let (first, second) = s.split_once(" - ")?;
+        Some(Self {
+            first: FixedSurface::from_idx_str(first)?,
+            second: Some(FixedSurface::from_idx_str(second)?),
+        })
+    }
+
+    /// Parse a layer between two surfaces of the same type, e.g. "0-0.1 m below ground".
+    fn layer_from_idx_str(s: &str) -> Option<Self> {
+        let (values, suffix) = s.split_once(' ')?;
+
+        // Skip the first character, which might be a minus sign. And don't split "1e-05".
+        let split_at = values
+            .char_indices()
+            .skip(1)
+            .find(|&(i, c)| c == '-' && !values[..i].ends_with(['e', 'E']))?
+            .0;
+        let first: f64 = values[..split_at].parse().ok()?;
+        let second: f64 = values[split_at + 1..].parse().ok()?;
+
+        let (type_num, divisor) = (0..=u8::MAX).find_map(|type_num| {
+            match SurfaceDescription::from_type_num(type_num)? {
+                SurfaceDescription::Valued { layer, divisor, .. } if layer == suffix => {
+                    Some((type_num, divisor))
+                }
+                _ => None,
+            }
+        })?;
+        let surface = |value: f64| {
+            Some(FixedSurface {
+                type_num,
+                value: Some(ScaledValue::from_f64(value * divisor)?),
+            })
+        };
+        Some(Self {
+            first: surface(first)?,
+            second: Some(surface(second)?),
+        })
+    }
+}
+
+impl FixedSurface {
+    /// The type of surface (code table 4.5): `Known` with `type_num` if we know how to describe
+    /// it.
+    pub fn surface_type(&self) -> CodeValue<u8> {
+        let describe = |type_num| SurfaceDescription::from_type_num(type_num).map(|_| type_num);
+        CodeValue::new(self.type_num, describe, describe)
+    }
+
+    /// Parse the text which `wgrib2` prints for a single fixed surface, e.g. "500 mb".
+    fn from_idx_str(s: &str) -> Option<Self> {
+        if let Some(rest) = s.strip_prefix("level type ") {
+            return Some(match rest.split_once('=') {
+                Some((type_num, value)) => FixedSurface {
+                    type_num: type_num.parse().ok()?,
+                    value: Some(ScaledValue::from_f64(value.parse().ok()?)?),
+                },
+                None => FixedSurface {
+                    type_num: rest.parse().ok()?,
+                    value: None,
+                },
+            });
+        }
+        (0..=u8::MAX).find_map(
+            |type_num| match SurfaceDescription::from_type_num(type_num)? {
+                SurfaceDescription::Named(name) if name == s => Some(FixedSurface {
+                    type_num,
+                    value: None,
+                }),
+                SurfaceDescription::Valued { level, .. } if level == s => Some(FixedSurface {
+                    type_num,
+                    value: None,
+                }),
+                SurfaceDescription::Valued { level, divisor, .. } => {
+                    let value: f64 = s.strip_suffix(level)?.strip_suffix(' ')?.parse().ok()?;
+                    Some(FixedSurface {
+                        type_num,
+                        value: Some(ScaledValue::from_f64(value * divisor)?),
+                    })
+                }
+                _ => None,
+            },
+        )
+    }
+}
+
+/// How `wgrib2` describes each type of fixed surface.
+enum SurfaceDescription {
+    /// A surface which doesn't need a value, e.g. "surface" or "tropopause".
+    Named(&'static str),
+
+    /// A surface described by a value, printed as "{value / divisor} {level}" for a single
+    /// surface, or "{first}-{second} {layer}" for a layer between two surfaces of the same type.
+    Valued {
+        level: &'static str,
+        layer: &'static str,
+        divisor: f64,
+    },
+}
+
+impl SurfaceDescription {
+    /// Types 200 and above are local to NCEP, but everyone who writes `.idx` files uses them.
+    fn from_type_num(type_num: u8) -> Option<Self> {
+        use SurfaceDescription::{Named, Valued};
+        let valued = |level, layer| Valued {
+            level,
+            layer,
+            divisor: 1.0,
+        };
+        let description = match type_num {
+            1 => Named("surface"),
+            2 => Named("cloud base"),
+            3 => Named("cloud top"),
+            4 => Named("0C isotherm"),
+            5 => Named("level of adiabatic condensation from sfc"),
+            6 => Named("max wind"),
+            7 => Named("tropopause"),
+            8 => Named("top of atmosphere"),
+            9 => Named("sea bottom"),
+            10 => Named("entire atmosphere"),
+            11 => Named("cumulonimbus base"),
+            12 => Named("cumulonimbus top"),
+            14 => Named("level of free convection"),
+            20 => valued("K level", "K layer"),
+            100 => Valued {
+                level: "mb",
+                layer: "mb",
+                divisor: 100.0,
+            },
+            101 => Named("mean sea level"),
+            102 => valued("m above mean sea level", "m above mean sea level"),
+            103 => valued("m above ground", "m above ground"),
+            104 => valued("sigma level", "sigma layer"),
+            105 => valued("hybrid level", "hybrid layer"),
+            106 => valued("m below ground", "m below ground"),
+            107 => valued("K isentropic level", "K isentropic layer"),
+            108 => Valued {
+                level: "mb above ground",
+                layer: "mb above ground",
+                divisor: 100.0,
+            },
+            111 => valued("eta level", "eta layer"),
+            160 => valued("m below sea level", "m below sea level"),
+            200 => Named("entire atmosphere (considered as a single layer)"),
+            204 => Named("highest tropospheric freezing level"),
+            211 => Named("boundary layer cloud layer"),
+            212 => Named("low cloud bottom level"),
+            213 => Named("low cloud top level"),
+            214 => Named("low cloud layer"),
+            215 => Named("cloud ceiling"),
+            220 => Named("planetary boundary layer"),
+            222 => Named("middle cloud bottom level"),
+            223 => Named("middle cloud top level"),
+            224 => Named("middle cloud layer"),
+            232 => Named("high cloud bottom level"),
+            233 => Named("high cloud top level"),
+            234 => Named("high cloud layer"),
+            242 => Named("convective cloud bottom level"),
+            243 => Named("convective cloud top level"),
+            244 => Named("convective cloud layer"),
+            // etc.
+            _ => return None,
+        };
+        Some(description)
+    }
+}
+
+impl fmt::Display for FixedSurface {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (SurfaceDescription::from_type_num(self.type_num), self.value) {
+            (Some(SurfaceDescription::Named(name)), _) => f.write_str(name),
+            (Some(SurfaceDescription::Valued { level, divisor, .. }), Some(value)) => {
+                write!(f, "{} {level}", format_g(value.value() / divisor))
+            }
+            (Some(SurfaceDescription::Valued { level, .. }), None) => f.write_str(level),
+            (None, Some(value)) => write!(
+                f,
+                "level type {}={}",
+                self.type_num,
+                format_g(value.value())
+            ),
+            (None, None) => write!(f, "level type {}", self.type_num),
+        }
+    }
+}
+
+/// Formats levels the same way as `wgrib2`, e.g. "2 m above ground" or "0-0.1 m below ground".
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(second) = self.second else {
+            return write!(f, "{}", self.first);
+        };
+        if second.type_num == self.first.type_num {
+            if let (
+                Some(SurfaceDescription::Valued { layer, divisor, .. }),
+                Some(first_value),
+                Some(second_value),
+            ) = (
+                SurfaceDescription::from_type_num(self.first.type_num),
+                self.first.value,
+                second.value,
+            ) {
+                return write!(
+                    f,
+                    "{}-{} {layer}",
+                    format_g(first_value.value() / divisor),
+                    format_g(second_value.value() / divisor)
+                );
+            }
+        }
+        write!(f, "{} - {}", self.first, second)
+    }
+}
@@ -1,10 +1,73 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use core::ops::RangeInclusive;
+
 use center_and_table_versions::CenterAndTableVersions;
+use code_table::CodeValue;
+use semantics::Semantics;
 
+pub use product::Product;
+
+pub(crate) mod abbrev_table;
 pub(crate) mod category;
-pub(crate) mod center_and_table_versions;
+pub mod center_and_table_versions;
+pub mod code_table;
+#[cfg(feature = "std")]
+pub mod constituent;
+#[cfg(feature = "std")]
+pub(crate) mod csv;
+#[cfg(feature = "std")]
+pub(crate) mod data_representation;
+#[cfg(feature = "std")]
+pub mod dataset;
+#[cfg(feature = "std")]
+pub mod describe;
+#[cfg(feature = "std")]
+pub mod encoder;
+#[cfg(feature = "std")]
+pub mod ensemble;
+pub mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "std")]
+pub mod generating_process;
+pub mod grib1;
+#[cfg(feature = "std")]
+pub mod grid;
+#[cfg(feature = "std")]
+pub mod idx;
+#[cfg(feature = "std")]
+pub mod inventory;
+#[cfg(feature = "std")]
+pub(crate) mod jpeg2000;
+#[cfg(feature = "kerchunk")]
+pub mod kerchunk;
+#[cfg(feature = "std")]
+pub mod level;
+#[cfg(feature = "runtime-tables")]
+pub mod local_tables;
 pub(crate) mod master_table;
+#[cfg(feature = "std")]
+pub mod message;
 pub(crate) mod originating_center;
+#[cfg(feature = "std")]
+pub mod point;
 pub(crate) mod product;
+#[cfg(feature = "std")]
+pub(crate) mod product_definition;
+pub mod product_id;
+#[cfg(feature = "std")]
+pub mod reader;
+pub mod semantics;
+pub mod step;
+#[cfg(feature = "std")]
+pub mod table_diff;
+#[cfg(feature = "std")]
+pub mod time;
+#[cfg(feature = "std")]
+pub mod validate;
 
 pub trait AbbrevToProduct {
     fn abbrev_to_product(&self, abbrev: &str) -> Option<&'static crate::product::Product>;
@@ -13,12 +76,20 @@ pub trait AbbrevToProduct {
 trait Parameter {
     fn from_parameter_num(
         parameter_num: u8,
-        center_and_table_versions: CenterAndTableVersions,
-    ) -> Option<Self>
+        center_and_table_versions: &CenterAndTableVersions,
+    ) -> CodeValue<Self>
     where
         Self: Sized;
 
+    /// The inverse of `from_parameter_num`.
+    fn parameter_num(&self) -> u8;
     fn abbrev(&self) -> &'static str;
     fn name(&self) -> &'static str;
     fn unit(&self) -> &'static str;
+
+    /// How this parameter usually combines along the time dimension.
+    fn semantics(&self) -> Semantics;
+
+    /// The range of physically plausible values, in `unit`. `None` if there's no useful range.
+    fn plausible_range(&self) -> Option<RangeInclusive<f64>>;
 }
@@ -0,0 +1,219 @@
+//! Local parameter tables loaded at runtime, for centres whose tables aren't compiled into this
+//! crate.
+//!
+//! Registered parameters live for the rest of the program (they're leaked), so that lookups can
+//! return `&'static` products just like the compiled-in tables do. Each distinct parameter is only
+//! leaked once, however many times it's registered, so reloading a table doesn't use more memory.
+
+use std::{
+    collections::HashMap,
+    io::Read,
+    sync::{OnceLock, RwLock},
+};
+
+use serde::Deserialize;
+
+use crate::{center_and_table_versions::LocalTableVersion, csv, error::Error, product::Product};
+
+/// One parameter from a local table.
+///
+/// In CSV files, the columns are
+/// `center,local_table_version,discipline,category,parameter,abbrev,name,unit`, with a header
+/// row. JSON files hold an array of objects with the same keys.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub struct LocalParameter {
+    /// Common code table C-11.
+    pub center: u16,
+
+    /// `None` if this parameter means the same thing in every version of the center's local
+    /// tables. Empty in CSV files.
+    ///
+    /// Like the compiled-in tables, a message whose version has no parameters registered uses
+    /// the nearest earlier version which does.
+    #[serde(default)]
+    pub local_table_version: Option<u8>,
+    pub discipline: u8,
+    pub category: u8,
+    pub parameter: u8,
+    pub abbrev: String,
+    pub name: String,
+    pub unit: String,
+}
+
+/// A local table which has been read from a file, but not yet registered.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LocalTable {
+    parameters: Vec<LocalParameter>,
+}
+
+impl LocalTable {
+    pub fn from_csv<R: Read>(mut reader: R) -> Result<Self, Error> {
+        let mut csv = String::new();
+        reader.read_to_string(&mut csv)?;
+        let parameters = csv
+            .lines()
+            .enumerate()
+            .skip(1) // The header row.
+            .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+            .map(|(i, line)| parse_csv_row(line).map_err(|reason| invalid(i + 1, reason)))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { parameters })
+    }
+
+    pub fn from_json<R: Read>(reader: R) -> Result<Self, Error> {
+        let parameters =
+            serde_json::from_reader(reader).map_err(|err| invalid(err.line(), err.to_string()))?;
+        Ok(Self { parameters })
+    }
+
+    pub fn parameters(&self) -> &[LocalParameter] {
+        &self.parameters
+    }
+
+    /// Make this table's parameters available to every lookup from now on. Lookups consult the
+    /// compiled-in tables first. If two registered parameters clash, the one registered last wins.
+    pub fn register(self) {
+        let mut registry = registry().write().unwrap_or_else(|err| err.into_inner());
+        for parameter in self.parameters {
+            let (parameter, product) = match registry.interned.get(&parameter) {
+                Some(&interned) => interned,
+                None => {
+                    let parameter: &'static LocalParameter = Box::leak(Box::new(parameter));
+                    let product: &'static Product =
+                        Box::leak(Box::new(Product::Dynamic(parameter)));
+                    registry
+                        .interned
+                        .insert(parameter.clone(), (parameter, product));
+                    (parameter, product)
+                }
+            };
+            let version = parameter.local_table_version;
+            if let Some(version) = version {
+                let versions = registry.versions.entry(parameter.center).or_default();
+                if let Err(i) = versions.binary_search_by_key(&version, |v| v.first_version) {
+                    versions.insert(
+                        i,
+                        LocalTableVersion {
+                            first_version: version,
+                            subcenters: &[],
+                        },
+                    );
+                }
+            }
+            registry.by_numbers.insert(
+                (
+                    parameter.center,
+                    version,
+                    parameter.discipline,
+                    parameter.category,
+                    parameter.parameter,
+                ),
+                parameter,
+            );
+            registry.by_abbrev.insert(
+                (parameter.center, version, parameter.abbrev.clone()),
+                product,
+            );
+        }
+    }
+}
+
+#[derive(Default)]
+struct Registry {
+    /// Every parameter which has ever been registered, so that registering it again doesn't leak
+    /// it again.
+    interned: HashMap<LocalParameter, (&'static LocalParameter, &'static Product)>,
+    by_numbers: HashMap<(u16, Option<u8>, u8, u8, u8), &'static LocalParameter>,
+    by_abbrev: HashMap<(u16, Option<u8>, String), &'static Product>,
+
+    /// The local table versions which have parameters registered for each center, oldest first.
+    versions: HashMap<u16, Vec<LocalTableVersion>>,
+}
+
+impl Registry {
+    /// Which of the registered versions of `center_num`'s local tables to use for
+    /// `local_table_version`, or `None` to only use parameters which apply to every version.
+    fn select_version(&self, center_num: u16, local_table_version: u8) -> Option<u8> {
+        let versions = self.versions.get(&center_num)?;
+        LocalTableVersion::select(versions, 0, local_table_version)
+            .map(|version| version.first_version)
+    }
+}
+
+fn registry() -> &'static RwLock<Registry> {
+    static REGISTRY: OnceLock<RwLock<Registry>> = OnceLock::new();
+    REGISTRY.get_or_init(Default::default)
+}
+
+/// Look up a registered parameter, preferring one registered for the selected local table version
+/// over one which applies to every version.
+pub(crate) fn parameter_from_numbers(
+    center_num: u16,
+    local_table_version: u8,
+    discipline_num: u8,
+    category_num: u8,
+    parameter_num: u8,
+) -> Option<&'static LocalParameter> {
+    let registry = registry().read().unwrap_or_else(|err| err.into_inner());
+    let version = registry.select_version(center_num, local_table_version);
+    [version, None]
+        .into_iter()
+        .find_map(|version| {
+            registry.by_numbers.get(&(
+                center_num,
+                version,
+                discipline_num,
+                category_num,
+                parameter_num,
+            ))
+        })
+        .copied()
+}
+
+pub(crate) fn product_from_abbrev(
+    center_num: u16,
+    local_table_version: u8,
+    abbrev: &str,
+) -> Option<&'static Product> {
+    let registry = registry().read().unwrap_or_else(|err| err.into_inner());
+    let version = registry.select_version(center_num, local_table_version);
+    [version, None]
+        .into_iter()
+        .find_map(|version| {
+            registry
+                .by_abbrev
+                .get(&(center_num, version, abbrev.to_string()))
+        })
+        .copied()
+}
+
+fn parse_csv_row(line: &str) -> Result<LocalParameter, String> {
+    let fields = csv::split_row(line)?;
+    let [center, local_table_version, discipline, category, parameter, abbrev, name, unit] =
+        <[String; 8]>::try_from(fields)
+            .map_err(|fields| format!("expected 8 columns, found {}", fields.len()))?;
+    Ok(LocalParameter {
+        center: number("center", &center)?,
+        local_table_version: match local_table_version.trim() {
+            "" => None,
+            version => Some(number("local_table_version", version)?),
+        },
+        discipline: number("discipline", &discipline)?,
+        category: number("category", &category)?,
+        parameter: number("parameter", &parameter)?,
+        abbrev: abbrev.trim().to_string(),
+        name: name.trim().to_string(),
+        unit: unit.trim().to_string(),
+    })
+}
+
+fn number<T: std::str::FromStr>(column: &str, value: &str) -> Result<T, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid {column} \"{value}\""))
+}
+
+fn invalid(line: usize, reason: String) -> Error {
+    Error::InvalidLocalTable { line, reason }
+}
@@ -1,5 +1,15 @@
 use crate::{
-    category::meteorological::{temperature::TemperatureParameter, MeteorologicalCategory},
+    abbrev_table::{abbrev_table, AbbrevTable},
+    category::hydrological::{
+        hydrology_basic::HydrologyBasicParameter,
+        hydrology_probabilities::HydrologyProbabilitiesParameter, HydrologicalCategory,
+    },
+    category::meteorological::{
+        atmospheric_chemistry::AtmosphericChemistryParameter, mass::MassParameter,
+        moisture::MoistureParameter, momentum::MomentumParameter,
+        temperature::TemperatureParameter, trace_gases::TraceGasesParameter,
+        MeteorologicalCategory,
+    },
     product::Product,
     AbbrevToProduct,
 };
@@ -9,6 +19,42 @@ pub(crate) enum MasterTable {
     V33,
 }
 
+impl MasterTable {
+    /// The most recent master table version known to this crate.
+    pub(crate) const LATEST: MasterTable = MasterTable::V33;
+
+    /// `version_num` is from octet 10 of section 1 (code table 1.0).
+    pub(crate) fn from_version_num(version_num: u8) -> MasterTable {
+        match version_num {
+            32 => MasterTable::V32,
+            33 => MasterTable::V33,
+
+            // WMO only ever adds entries to the master tables, so the latest table can decode
+            // messages which declare an older version. Many operational files still declare
+            // version 2!
+            _ => MasterTable::LATEST,
+        }
+    }
+
+    /// Every product in this version of the master tables, in no particular order.
+    pub(crate) fn products(&self) -> impl Iterator<Item = &'static Product> {
+        let version_specific = match self {
+            MasterTable::V32 => Self::abbrev_to_product_master_table_v32(),
+            MasterTable::V33 => Self::abbrev_to_product_master_table_v33(),
+        };
+        Self::abbrev_to_product_master_table_common()
+            .values()
+            .chain(version_specific.values())
+    }
+
+    pub(crate) fn version_num(&self) -> u8 {
+        match self {
+            MasterTable::V32 => 32,
+            MasterTable::V33 => 33,
+        }
+    }
+}
+
 impl AbbrevToProduct for MasterTable {
     fn abbrev_to_product(&self, abbrev: &str) -> Option<&'static Product> {
         MasterTable::abbrev_to_product_master_table_common()
@@ -16,7 +62,7 @@ impl AbbrevToProduct for MasterTable {
             .or_else(|| {
                 match self {
                     MasterTable::V32 => Self::abbrev_to_product_master_table_v32(),
-                    _ => todo!(),
+                    MasterTable::V33 => Self::abbrev_to_product_master_table_v33(),
                 }
                 .get(abbrev)
             })
@@ -24,15 +70,12 @@ impl AbbrevToProduct for MasterTable {
 }
 
 impl MasterTable {
-    fn abbrev_to_product_master_table_common() -> &'static phf::Map<&'static str, Product> {
+    fn abbrev_to_product_master_table_common() -> &'static AbbrevTable {
         /// All the abbreviations which are common across all centers and all table versions.
         ///
-        /// To decode .idx files, we need a single hashmap which holds every abbreviation string.
-        /// So the values of the hashmap have to all be the same type.
-        ///
-        /// `phf::Map` is compiled to a perfect hash table, which is O(1). In contrast,
-        /// matching strings compiles code which checks each string in turn, which is O(n).
-        static ABBREV_TO_PRODUCT_COMMON: phf::Map<&'static str, Product> = phf::phf_map! {
+        /// To decode .idx files, we need a single table which holds every abbreviation string.
+        /// So the values of the table have to all be the same type.
+        static ABBREV_TO_PRODUCT_COMMON: AbbrevTable = abbrev_table! {
             "TMP" => Product::Meteorological(MeteorologicalCategory::Temperature(TemperatureParameter::Temperature)),
             "VTMP" => Product::Meteorological(MeteorologicalCategory::Temperature(TemperatureParameter::VirtualTemperature)),
             "POT" => Product::Meteorological(MeteorologicalCategory::Temperature(TemperatureParameter::PotentialTemperature)),
@@ -42,14 +85,91 @@ impl MasterTable {
             "DPT" => Product::Meteorological(MeteorologicalCategory::Temperature(TemperatureParameter::DewPointTemperature)),
             "DEPR" => Product::Meteorological(MeteorologicalCategory::Temperature(TemperatureParameter::DewPointDepression)),
             "LAPR" => Product::Meteorological(MeteorologicalCategory::Temperature(TemperatureParameter::LapseRate)),
+            "TMPA" => Product::Meteorological(MeteorologicalCategory::Temperature(TemperatureParameter::TemperatureAnomaly)),
+            "LHTFL" => Product::Meteorological(MeteorologicalCategory::Temperature(TemperatureParameter::LatentHeatNetFlux)),
+            "SHTFL" => Product::Meteorological(MeteorologicalCategory::Temperature(TemperatureParameter::SensibleHeatNetFlux)),
+            "SPFH" => Product::Meteorological(MeteorologicalCategory::Moisture(MoistureParameter::SpecificHumidity)),
+            "RH" => Product::Meteorological(MeteorologicalCategory::Moisture(MoistureParameter::RelativeHumidity)),
+            "MIXR" => Product::Meteorological(MeteorologicalCategory::Moisture(MoistureParameter::HumidityMixingRatio)),
+            "PWAT" => Product::Meteorological(MeteorologicalCategory::Moisture(MoistureParameter::PrecipitableWater)),
+            "VAPP" => Product::Meteorological(MeteorologicalCategory::Moisture(MoistureParameter::VapourPressure)),
+            "SATD" => Product::Meteorological(MeteorologicalCategory::Moisture(MoistureParameter::SaturationDeficit)),
+            "EVP" => Product::Meteorological(MeteorologicalCategory::Moisture(MoistureParameter::Evaporation)),
+            "PRATE" => Product::Meteorological(MeteorologicalCategory::Moisture(MoistureParameter::PrecipitationRate)),
+            "APCP" => Product::Meteorological(MeteorologicalCategory::Moisture(MoistureParameter::TotalPrecipitation)),
+            "NCPCP" => Product::Meteorological(MeteorologicalCategory::Moisture(MoistureParameter::LargeScalePrecipitation)),
+            "ACPCP" => Product::Meteorological(MeteorologicalCategory::Moisture(MoistureParameter::ConvectivePrecipitation)),
+            "SNOD" => Product::Meteorological(MeteorologicalCategory::Moisture(MoistureParameter::SnowDepth)),
+            "SRWEQ" => Product::Meteorological(MeteorologicalCategory::Moisture(MoistureParameter::SnowfallRateWaterEquivalent)),
+            "WEASD" => Product::Meteorological(MeteorologicalCategory::Moisture(MoistureParameter::WaterEquivalentOfAccumulatedSnowDepth)),
+            "SNOC" => Product::Meteorological(MeteorologicalCategory::Moisture(MoistureParameter::ConvectiveSnow)),
+            "SNOL" => Product::Meteorological(MeteorologicalCategory::Moisture(MoistureParameter::LargeScaleSnow)),
+            "SNOM" => Product::Meteorological(MeteorologicalCategory::Moisture(MoistureParameter::SnowMelt)),
+            "WDIR" => Product::Meteorological(MeteorologicalCategory::Momentum(MomentumParameter::WindDirection)),
+            "WIND" => Product::Meteorological(MeteorologicalCategory::Momentum(MomentumParameter::WindSpeed)),
+            "UGRD" => Product::Meteorological(MeteorologicalCategory::Momentum(MomentumParameter::UComponentOfWind)),
+            "VGRD" => Product::Meteorological(MeteorologicalCategory::Momentum(MomentumParameter::VComponentOfWind)),
+            "STRM" => Product::Meteorological(MeteorologicalCategory::Momentum(MomentumParameter::StreamFunction)),
+            "VPOT" => Product::Meteorological(MeteorologicalCategory::Momentum(MomentumParameter::VelocityPotential)),
+            "MNTSF" => Product::Meteorological(MeteorologicalCategory::Momentum(MomentumParameter::MontgomeryStreamFunction)),
+            "SGCVV" => Product::Meteorological(MeteorologicalCategory::Momentum(MomentumParameter::SigmaCoordinateVerticalVelocity)),
+            "VVEL" => Product::Meteorological(MeteorologicalCategory::Momentum(MomentumParameter::VerticalVelocityPressure)),
+            "DZDT" => Product::Meteorological(MeteorologicalCategory::Momentum(MomentumParameter::VerticalVelocityGeometric)),
+            "ABSV" => Product::Meteorological(MeteorologicalCategory::Momentum(MomentumParameter::AbsoluteVorticity)),
+            "ABSD" => Product::Meteorological(MeteorologicalCategory::Momentum(MomentumParameter::AbsoluteDivergence)),
+            "RELV" => Product::Meteorological(MeteorologicalCategory::Momentum(MomentumParameter::RelativeVorticity)),
+            "RELD" => Product::Meteorological(MeteorologicalCategory::Momentum(MomentumParameter::RelativeDivergence)),
+            "PVORT" => Product::Meteorological(MeteorologicalCategory::Momentum(MomentumParameter::PotentialVorticity)),
+            "PRES" => Product::Meteorological(MeteorologicalCategory::Mass(MassParameter::Pressure)),
+            "PRMSL" => Product::Meteorological(MeteorologicalCategory::Mass(MassParameter::PressureReducedToMsl)),
+            "PTEND" => Product::Meteorological(MeteorologicalCategory::Mass(MassParameter::PressureTendency)),
+            "ICAHT" => Product::Meteorological(MeteorologicalCategory::Mass(MassParameter::IcaoStandardAtmosphereReferenceHeight)),
+            "GP" => Product::Meteorological(MeteorologicalCategory::Mass(MassParameter::Geopotential)),
+            "HGT" => Product::Meteorological(MeteorologicalCategory::Mass(MassParameter::GeopotentialHeight)),
+            "DIST" => Product::Meteorological(MeteorologicalCategory::Mass(MassParameter::GeometricHeight)),
+            "HSTDV" => Product::Meteorological(MeteorologicalCategory::Mass(MassParameter::StandardDeviationOfHeight)),
+            "PRESA" => Product::Meteorological(MeteorologicalCategory::Mass(MassParameter::PressureAnomaly)),
+            "GPA" => Product::Meteorological(MeteorologicalCategory::Mass(MassParameter::GeopotentialHeightAnomaly)),
+            "TOZNE" => Product::Meteorological(MeteorologicalCategory::TraceGases(TraceGasesParameter::TotalOzone)),
+            "O3MR" => Product::Meteorological(MeteorologicalCategory::TraceGases(TraceGasesParameter::OzoneMixingRatio)),
+            "TCIOZ" => Product::Meteorological(MeteorologicalCategory::TraceGases(TraceGasesParameter::TotalColumnIntegratedOzone)),
+            "MASSDEN" => Product::Meteorological(MeteorologicalCategory::AtmosphericChemistry(AtmosphericChemistryParameter::MassDensity)),
+            "COLMD" => Product::Meteorological(MeteorologicalCategory::AtmosphericChemistry(AtmosphericChemistryParameter::ColumnIntegratedMassDensity)),
+            "MASSMR" => Product::Meteorological(MeteorologicalCategory::AtmosphericChemistry(AtmosphericChemistryParameter::MassMixingRatio)),
+            "AEMFLX" => Product::Meteorological(MeteorologicalCategory::AtmosphericChemistry(AtmosphericChemistryParameter::AtmosphereEmissionMassFlux)),
+            "ANPMFLX" => Product::Meteorological(MeteorologicalCategory::AtmosphericChemistry(AtmosphericChemistryParameter::AtmosphereNetProductionMassFlux)),
+            "ANPEMFLX" => Product::Meteorological(MeteorologicalCategory::AtmosphericChemistry(AtmosphericChemistryParameter::AtmosphereNetProductionAndEmissionMassFlux)),
+            "SDDMFLX" => Product::Meteorological(MeteorologicalCategory::AtmosphericChemistry(AtmosphericChemistryParameter::SurfaceDryDepositionMassFlux)),
+            "SWDMFLX" => Product::Meteorological(MeteorologicalCategory::AtmosphericChemistry(AtmosphericChemistryParameter::SurfaceWetDepositionMassFlux)),
+            "AREMFLX" => Product::Meteorological(MeteorologicalCategory::AtmosphericChemistry(AtmosphericChemistryParameter::AtmosphereReEmissionMassFlux)),
+            "SADEN" => Product::Meteorological(MeteorologicalCategory::AtmosphericChemistry(AtmosphericChemistryParameter::SurfaceAreaDensity)),
+            "ATMTK" => Product::Meteorological(MeteorologicalCategory::AtmosphericChemistry(AtmosphericChemistryParameter::VerticalVisualRange)),
+            "AOTK" => Product::Meteorological(MeteorologicalCategory::AtmosphericChemistry(AtmosphericChemistryParameter::AtmosphereOpticalThickness)),
+            "SSALBK" => Product::Meteorological(MeteorologicalCategory::AtmosphericChemistry(AtmosphericChemistryParameter::SingleScatteringAlbedo)),
+            "ASYSFK" => Product::Meteorological(MeteorologicalCategory::AtmosphericChemistry(AtmosphericChemistryParameter::AsymmetryFactor)),
+            "FFLDG" => Product::Hydrological(HydrologicalCategory::HydrologyBasic(HydrologyBasicParameter::FlashFloodGuidance)),
+            "FFLDRO" => Product::Hydrological(HydrologicalCategory::HydrologyBasic(HydrologyBasicParameter::FlashFloodRunoff)),
+            "RSSC" => Product::Hydrological(HydrologicalCategory::HydrologyBasic(HydrologyBasicParameter::RemotelySensedSnowCover)),
+            "ESCT" => Product::Hydrological(HydrologicalCategory::HydrologyBasic(HydrologyBasicParameter::ElevationOfSnowCoveredTerrain)),
+            "SWEPON" => Product::Hydrological(HydrologicalCategory::HydrologyBasic(HydrologyBasicParameter::SnowWaterEquivalentPercentOfNormal)),
+            "BGRUN" => Product::Hydrological(HydrologicalCategory::HydrologyBasic(HydrologyBasicParameter::BaseflowGroundwaterRunoff)),
+            "SSRUN" => Product::Hydrological(HydrologicalCategory::HydrologyBasic(HydrologyBasicParameter::StormSurfaceRunoff)),
+            "CPPOP" => Product::Hydrological(HydrologicalCategory::HydrologyProbabilities(HydrologyProbabilitiesParameter::ConditionalPercentPrecipitationAmountFractile)),
+            "PPOSP" => Product::Hydrological(HydrologicalCategory::HydrologyProbabilities(HydrologyProbabilitiesParameter::PercentPrecipitationInSubPeriod)),
+            "POP" => Product::Hydrological(HydrologicalCategory::HydrologyProbabilities(HydrologyProbabilitiesParameter::ProbabilityOfPrecipitation)),
         };
         &ABBREV_TO_PRODUCT_COMMON
     }
 
-    fn abbrev_to_product_master_table_v32() -> &'static phf::Map<&'static str, Product> {
+    fn abbrev_to_product_master_table_v32() -> &'static AbbrevTable {
         // Contains only the diff between master table V32 and the common abbreviations.
-        static ABBREV_TO_PRODUCT_MASTER_TABLE_V32: phf::Map<&'static str, Product> =
-            phf::phf_map! {}; // TODO: Fill in this map!
+        static ABBREV_TO_PRODUCT_MASTER_TABLE_V32: AbbrevTable = abbrev_table! {}; // TODO: Fill in this table!
         &ABBREV_TO_PRODUCT_MASTER_TABLE_V32
     }
+
+    fn abbrev_to_product_master_table_v33() -> &'static AbbrevTable {
+        // Contains only the diff between master table V33 and the common abbreviations.
+        static ABBREV_TO_PRODUCT_MASTER_TABLE_V33: AbbrevTable = abbrev_table! {}; // TODO: Fill in this table!
+        &ABBREV_TO_PRODUCT_MASTER_TABLE_V33
+    }
 }
@@ -0,0 +1,542 @@
+use crate::{
+    center_and_table_versions::CenterAndTableVersions,
+    data_representation::DataRepresentation,
+    error::Error,
+    generating_process::{GeneratingProcess, Model, TypeOfGeneratingProcess},
+    grid::Grid,
+    point::Interpolation,
+    product_definition::ProductDefinition,
+};
+
+/// The length of section 0 (the indicator section) in a GRIB2 message.
+pub(crate) const INDICATOR_LEN: usize = 16;
+
+/// Section 8 (the end section) is just these four bytes.
+pub(crate) const END_SECTION: &[u8; 4] = b"7777";
+
+/// One GRIB2 section, including its 5-byte header.
+///
+/// The accessors take the 1-based octet numbers used in the GRIB2 spec, so code which reads a
+/// template can be checked against the spec line by line. All accessors are bounds-checked.
+#[derive(Clone, Copy)]
+pub(crate) struct Section<'a> {
+    bytes: &'a [u8],
+
+    /// The offset of the start of this section, in bytes from the start of the file.
+    offset: u64,
+}
+
+impl<'a> Section<'a> {
+    pub(crate) fn new(bytes: &'a [u8], offset: u64) -> Self {
+        Self { bytes, offset }
+    }
+
+    pub(crate) fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    pub(crate) fn section_num(&self) -> u8 {
+        self.bytes.get(4).copied().unwrap_or(0)
+    }
+
+    pub(crate) fn u8(&self, octet: usize) -> Result<u8, Error> {
+        Ok(u8::from_be_bytes(self.octets(octet)?))
+    }
+
+    pub(crate) fn u16(&self, octet: usize) -> Result<u16, Error> {
+        Ok(u16::from_be_bytes(self.octets(octet)?))
+    }
+
+    pub(crate) fn u32(&self, octet: usize) -> Result<u32, Error> {
+        Ok(u32::from_be_bytes(self.octets(octet)?))
+    }
+
+    pub(crate) fn u64(&self, octet: usize) -> Result<u64, Error> {
+        Ok(u64::from_be_bytes(self.octets(octet)?))
+    }
+
+    /// GRIB2 stores signed integers as sign-and-magnitude, not two's complement.
+    pub(crate) fn i8(&self, octet: usize) -> Result<i8, Error> {
+        let raw = self.u8(octet)?;
+        let magnitude = (raw & 0x7F) as i8;
+        Ok(if raw & 0x80 == 0 {
+            magnitude
+        } else {
+            -magnitude
+        })
+    }
+
+    /// GRIB2 stores signed integers as sign-and-magnitude, not two's complement.
+    pub(crate) fn i16(&self, octet: usize) -> Result<i16, Error> {
+        let raw = self.u16(octet)?;
+        let magnitude = (raw & 0x7FFF) as i16;
+        Ok(if raw & 0x8000 == 0 {
+            magnitude
+        } else {
+            -magnitude
+        })
+    }
+
+    /// GRIB2 stores signed integers as sign-and-magnitude, not two's complement.
+    pub(crate) fn i32(&self, octet: usize) -> Result<i32, Error> {
+        let raw = self.u32(octet)?;
+        let magnitude = (raw & 0x7FFF_FFFF) as i32;
+        Ok(if raw & 0x8000_0000 == 0 {
+            magnitude
+        } else {
+            -magnitude
+        })
+    }
+
+    /// IEEE 754 single precision.
+    pub(crate) fn f32(&self, octet: usize) -> Result<f32, Error> {
+        Ok(f32::from_be_bytes(self.octets(octet)?))
+    }
+
+    /// Everything from `octet` to the end of the section, e.g. the packed values in section 7.
+    pub(crate) fn bytes_from(&self, octet: usize) -> Result<&'a [u8], Error> {
+        self.bytes.get(octet - 1..).ok_or_else(|| self.too_short())
+    }
+
+    fn octets<const N: usize>(&self, octet: usize) -> Result<[u8; N], Error> {
+        self.bytes
+            .get(octet - 1..octet - 1 + N)
+            .map(|bytes| bytes.try_into().unwrap())
+            .ok_or_else(|| self.too_short())
+    }
+
+    fn too_short(&self) -> Error {
+        Error::InvalidSection {
+            offset: self.offset,
+            section_num: self.section_num(),
+            reason: "section is too short for its template",
+        }
+    }
+}
+
+/// Builds one GRIB2 section: the inverse of [`Section`].
+///
+/// Values are appended in order, so code which writes a template can be checked against the spec
+/// line by line, just like code which reads one. `finish` fills in the section length.
+pub(crate) struct SectionWriter {
+    bytes: Vec<u8>,
+}
+
+impl SectionWriter {
+    pub(crate) fn new(section_num: u8) -> Self {
+        Self {
+            bytes: vec![0, 0, 0, 0, section_num],
+        }
+    }
+
+    pub(crate) fn u8(&mut self, value: u8) -> &mut Self {
+        self.bytes(&[value])
+    }
+
+    pub(crate) fn u16(&mut self, value: u16) -> &mut Self {
+        self.bytes(&value.to_be_bytes())
+    }
+
+    pub(crate) fn u32(&mut self, value: u32) -> &mut Self {
+        self.bytes(&value.to_be_bytes())
+    }
+
+    /// Sign-and-magnitude, like `Section::i8`.
+    pub(crate) fn i8(&mut self, value: i8) -> &mut Self {
+        let sign = if value < 0 { 0x80 } else { 0 };
+        self.u8(sign | value.unsigned_abs())
+    }
+
+    /// Sign-and-magnitude, like `Section::i16`.
+    pub(crate) fn i16(&mut self, value: i16) -> &mut Self {
+        let sign = if value < 0 { 0x8000 } else { 0 };
+        self.u16(sign | value.unsigned_abs())
+    }
+
+    /// Sign-and-magnitude, like `Section::i32`.
+    pub(crate) fn i32(&mut self, value: i32) -> &mut Self {
+        let sign = if value < 0 { 0x8000_0000 } else { 0 };
+        self.u32(sign | value.unsigned_abs())
+    }
+
+    pub(crate) fn f32(&mut self, value: f32) -> &mut Self {
+        self.bytes(&value.to_be_bytes())
+    }
+
+    pub(crate) fn bytes(&mut self, bytes: &[u8]) -> &mut Self {
+        self.bytes.extend_from_slice(bytes);
+        self
+    }
+
+    pub(crate) fn finish(mut self) -> Vec<u8> {
+        let len = self.bytes.len() as u32;
+        self.bytes[..4].copy_from_slice(&len.to_be_bytes());
+        self.bytes
+    }
+}
+
+/// Section 0: the indicator section.
+pub(crate) struct Indicator {
+    /// Code table 0.0.
+    pub(crate) discipline: u8,
+    pub(crate) edition: u8,
+    pub(crate) total_length: u64,
+}
+
+impl Indicator {
+    /// `bytes` must start at the first byte of a GRIB message. `offset` is only used for errors.
+    pub(crate) fn parse(bytes: &[u8], offset: u64) -> Result<Self, Error> {
+        if !bytes.starts_with(b"GRIB") {
+            return Err(Error::NotGrib { offset });
+        }
+        if bytes.len() < INDICATOR_LEN {
+            return Err(Error::Truncated {
+                offset,
+                expected_len: INDICATOR_LEN as u64,
+                available_len: bytes.len() as u64,
+            });
+        }
+        let section = Section::new(&bytes[..INDICATOR_LEN], offset);
+        let edition = section.u8(8)?;
+        if edition != 2 {
+            return Err(Error::UnsupportedEdition { offset, edition });
+        }
+        // Every message holds at least section 0 and the "7777" of section 8. Anything shorter
+        // would stop us from finding the start of the next message.
+        let total_length = section.u64(9)?;
+        if total_length < (INDICATOR_LEN + 4) as u64 {
+            return Err(Error::InvalidSection {
+                offset,
+                section_num: 0,
+                reason: "total length is shorter than sections 0 and 8",
+            });
+        }
+        Ok(Self {
+            discipline: section.u8(7)?,
+            edition,
+            total_length,
+        })
+    }
+}
+
+/// The reference time from section 1. All values are UTC.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ReferenceTime {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+}
+
+/// Section 1: the identification section.
+pub(crate) struct Identification {
+    /// Common code table C-11.
+    pub(crate) center: u16,
+    pub(crate) subcenter: u16,
+    pub(crate) master_table_version: u8,
+    pub(crate) local_table_version: u8,
+
+    /// Code table 1.2.
+    pub(crate) significance_of_reference_time: u8,
+    pub(crate) reference_time: ReferenceTime,
+
+    /// Code table 1.3.
+    pub(crate) production_status: u8,
+
+    /// Code table 1.4.
+    pub(crate) type_of_data: u8,
+}
+
+impl Identification {
+    pub(crate) fn parse(section: &Section) -> Result<Self, Error> {
+        Ok(Self {
+            center: section.u16(6)?,
+            subcenter: section.u16(8)?,
+            master_table_version: section.u8(10)?,
+            local_table_version: section.u8(11)?,
+            significance_of_reference_time: section.u8(12)?,
+            reference_time: ReferenceTime {
+                year: section.u16(13)?,
+                month: section.u8(15)?,
+                day: section.u8(16)?,
+                hour: section.u8(17)?,
+                minute: section.u8(18)?,
+                second: section.u8(19)?,
+            },
+            production_status: section.u8(20)?,
+            type_of_data: section.u8(21)?,
+        })
+    }
+
+    pub(crate) fn center_and_table_versions(&self) -> CenterAndTableVersions {
+        CenterAndTableVersions::from_numbers(
+            self.center,
+            self.subcenter,
+            self.local_table_version,
+            self.master_table_version,
+        )
+    }
+}
+
+/// One field (one set of sections 4 to 7) from a GRIB2 message.
+///
+/// A message may hold several fields. Sections 2 and 3 may be shared with earlier fields in the
+/// same message, because GRIB2 only repeats sections which change.
+#[derive(Clone, Copy)]
+pub struct Field<'a> {
+    /// From section 1. Statistically processed templates may give the end of their time range
+    /// as a date, which we need this to convert into a step.
+    pub(crate) reference_time: ReferenceTime,
+    pub(crate) local_use: Option<Section<'a>>,
+    pub(crate) grid_definition: Section<'a>,
+    pub(crate) product_definition: Section<'a>,
+    pub(crate) data_representation: Section<'a>,
+    pub(crate) bitmap: Section<'a>,
+    pub(crate) data: Section<'a>,
+}
+
+impl<'a> Field<'a> {
+    /// The contents of section 2 (after its 5 octet header), which centers use for whatever they
+    /// like. `None` if the message has no section 2.
+    pub fn local_use(&self) -> Option<&'a [u8]> {
+        self.local_use
+            .and_then(|section| section.bytes_from(6).ok())
+    }
+
+    pub fn grid(&self) -> Result<Grid, Error> {
+        Grid::parse(&self.grid_definition)
+    }
+
+    /// The values at each of `locations` (latitude and longitude, in degrees), e.g. weather
+    /// stations. NaN for locations outside the grid.
+    pub fn values_at(
+        &self,
+        locations: &[(f64, f64)],
+        interpolation: Interpolation,
+    ) -> Result<Vec<f32>, Error> {
+        let grid = self.grid()?;
+        let values = self.values()?;
+        Ok(locations
+            .iter()
+            .map(|&(lat, lon)| {
+                grid.weights(lat, lon, interpolation)
+                    .map_or(f32::NAN, |weights| weights.interpolate(&values))
+            })
+            .collect())
+    }
+
+    /// The type of generating process and the model, decoded with the tables of the message's
+    /// center (see [`Message::center_and_table_versions`]).
+    pub fn generating_process(
+        &self,
+        center_and_table_versions: &CenterAndTableVersions,
+    ) -> Result<GeneratingProcess, Error> {
+        let product_definition =
+            ProductDefinition::parse(&self.product_definition, self.reference_time)?;
+        Ok(GeneratingProcess {
+            type_of_generating_process: TypeOfGeneratingProcess::from_num(
+                product_definition.type_of_generating_process,
+                center_and_table_versions,
+            ),
+            model: Model::from_num(
+                product_definition.generating_process_identifier,
+                center_and_table_versions,
+            ),
+        })
+    }
+
+    /// Unpack the values of this field, in the order given by the grid's scanning mode. Points
+    /// which the bitmap marks as missing are NaN.
+    pub fn values(&self) -> Result<Vec<f32>, Error> {
+        let num_points = self.grid_definition.u32(7)? as usize;
+        let values =
+            DataRepresentation::parse(&self.data_representation)?.unpack(&self.data, num_points)?;
+        let invalid = |reason| Error::InvalidSection {
+            offset: self.bitmap.offset(),
+            section_num: 6,
+            reason,
+        };
+        match self.bitmap.u8(6)? {
+            // No bitmap: every point has a value.
+            255 if values.len() == num_points => Ok(values),
+            255 => Err(invalid(
+                "section 5 and section 3 have different numbers of points",
+            )),
+            0 => {
+                let bitmap = self.bitmap.bytes_from(7)?;
+                if bitmap.len() * 8 < num_points {
+                    return Err(invalid("bitmap is shorter than the grid"));
+                }
+                let mut values = values.into_iter();
+                let unpacked = (0..num_points)
+                    .map(|i| match bitmap[i / 8] & (0x80 >> (i % 8)) {
+                        0 => Some(f32::NAN),
+                        _ => values.next(),
+                    })
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(|| invalid("bitmap has more points than section 5"))?;
+                Ok(unpacked)
+            }
+            // etc.
+            _ => Err(invalid(
+                "only bitmaps which are given in section 6 are supported",
+            )),
+        }
+    }
+}
+
+/// One complete GRIB2 message, borrowed from the bytes of a GRIB2 file.
+pub struct Message<'a> {
+    bytes: &'a [u8],
+    offset: u64,
+    indicator: Indicator,
+    identification: Identification,
+    fields: Vec<Field<'a>>,
+}
+
+impl<'a> Message<'a> {
+    /// `bytes` must start at the first byte of a GRIB2 message, and may continue past the end of
+    /// the message. `offset` is the position of `bytes` in the file.
+    pub(crate) fn parse(bytes: &'a [u8], offset: u64) -> Result<Self, Error> {
+        let indicator = Indicator::parse(bytes, offset)?;
+        if (bytes.len() as u64) < indicator.total_length {
+            return Err(Error::Truncated {
+                offset,
+                expected_len: indicator.total_length,
+                available_len: bytes.len() as u64,
+            });
+        }
+        if indicator.total_length < (INDICATOR_LEN + END_SECTION.len()) as u64 {
+            return Err(Error::InvalidSection {
+                offset,
+                section_num: 0,
+                reason: "message is too short to hold sections 0 and 8",
+            });
+        }
+        let bytes = &bytes[..indicator.total_length as usize];
+
+        let mut identification = None;
+        let mut local_use = None;
+        let mut grid_definition = None;
+        let mut product_definition = None;
+        let mut data_representation = None;
+        let mut bitmap = None;
+        let mut fields = Vec::new();
+
+        let mut pos = INDICATOR_LEN;
+        while !bytes[pos..].starts_with(END_SECTION) {
+            let section_offset = offset + pos as u64;
+            let header = Section::new(&bytes[pos..], section_offset);
+            let section_len = header.u32(1).map_err(|_| Error::Truncated {
+                offset: section_offset,
+                expected_len: 5,
+                available_len: (bytes.len() - pos) as u64,
+            })? as usize;
+            let section_num = header.u8(5).map_err(|_| Error::Truncated {
+                offset: section_offset,
+                expected_len: 5,
+                available_len: (bytes.len() - pos) as u64,
+            })?;
+            if section_len < 5 {
+                return Err(Error::InvalidSection {
+                    offset: section_offset,
+                    section_num,
+                    reason: "section length is less than 5 bytes",
+                });
+            }
+            if pos + section_len > bytes.len() {
+                return Err(Error::Truncated {
+                    offset: section_offset,
+                    expected_len: section_len as u64,
+                    available_len: (bytes.len() - pos) as u64,
+                });
+            }
+            let section = Section::new(&bytes[pos..pos + section_len], section_offset);
+
+            let out_of_order = |reason| Error::InvalidSection {
+                offset: section_offset,
+                section_num,
+                reason,
+            };
+            match section_num {
+                1 => identification = Some(Identification::parse(&section)?),
+                2 => local_use = Some(section),
+                3 => grid_definition = Some(section),
+                4 => product_definition = Some(section),
+                5 => data_representation = Some(section),
+                6 => bitmap = Some(section),
+                7 => fields.push(Field {
+                    reference_time: identification
+                        .as_ref()
+                        .ok_or_else(|| out_of_order("section 1 must come first"))?
+                        .reference_time,
+                    local_use,
+                    grid_definition: grid_definition
+                        .ok_or_else(|| out_of_order("section 7 before section 3"))?,
+                    product_definition: product_definition
+                        .take()
+                        .ok_or_else(|| out_of_order("section 7 before section 4"))?,
+                    data_representation: data_representation
+                        .take()
+                        .ok_or_else(|| out_of_order("section 7 before section 5"))?,
+                    bitmap: bitmap
+                        .take()
+                        .ok_or_else(|| out_of_order("section 7 before section 6"))?,
+                    data: section,
+                }),
+                _ => return Err(out_of_order("unknown section number")),
+            }
+            if identification.is_none() {
+                return Err(out_of_order("section 1 must come first"));
+            }
+            pos += section_len;
+        }
+
+        Ok(Self {
+            bytes,
+            offset,
+            indicator,
+            identification: identification.ok_or(Error::InvalidSection {
+                offset,
+                section_num: 1,
+                reason: "message has no identification section",
+            })?,
+            fields,
+        })
+    }
+
+    /// The bytes of the whole message, from "GRIB" to "7777".
+    pub fn bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// The position of the start of this message, in bytes from the start of the file.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// The length of the whole message, in bytes.
+    pub fn total_length(&self) -> u64 {
+        self.bytes.len() as u64
+    }
+
+    pub(crate) fn indicator(&self) -> &Indicator {
+        &self.indicator
+    }
+
+    pub(crate) fn identification(&self) -> &Identification {
+        &self.identification
+    }
+
+    /// The center which made this message, and the table versions it uses.
+    pub fn center_and_table_versions(&self) -> CenterAndTableVersions {
+        self.identification.center_and_table_versions()
+    }
+
+    /// Each message holds one or more fields.
+    pub fn fields(&self) -> &[Field<'a>] {
+        &self.fields
+    }
+}
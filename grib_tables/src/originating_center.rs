@@ -1,32 +1,169 @@
+use core::borrow::Borrow;
+
+use crate::abbrev_table::{abbrev_table, AbbrevTable};
+use crate::category::hydrological::hydrology_probabilities::HydrologyProbabilitiesParameter;
+use crate::category::hydrological::HydrologicalCategory;
+use crate::category::meteorological::mass::MassParameter;
+use crate::category::meteorological::moisture::MoistureParameter;
+use crate::category::meteorological::momentum::MomentumParameter;
 use crate::category::meteorological::temperature::TemperatureParameter;
+use crate::category::meteorological::trace_gases::TraceGasesParameter;
 use crate::category::meteorological::MeteorologicalCategory;
+use crate::center_and_table_versions::LocalTableVersion;
 use crate::product::Product;
 use crate::AbbrevToProduct;
 
 /// Identification of originating/generating center.
 pub(crate) enum OriginatingCenter {
-    NCEP { local_table_version: u8 },
+    Ncep {
+        /// ON388 table C, e.g. 4 for EMC. NCEP's sub-centres don't all use the same local tables.
+        subcenter: u16,
+        local_table_version: u8,
+    },
+
+    /// A center whose local tables aren't compiled into this crate.
+    Other {
+        center_num: u16,
+    },
 }
 
 impl AbbrevToProduct for OriginatingCenter {
     fn abbrev_to_product(&self, abbrev: &str) -> Option<&'static Product> {
-        match self {
-            OriginatingCenter::NCEP {
-                local_table_version,
-            } => OriginatingCenter::abbrev_to_product_ncep(*local_table_version),
-        }
-        .get(abbrev)
+        self.local_table()?.get(abbrev)
     }
 }
 
 impl OriginatingCenter {
-    fn abbrev_to_product_ncep(
-        _local_table_version: u8,
-    ) -> &'static phf::Map<&'static str, Product> {
-        static ABBREV_TO_PRODUCT_NCEP: phf::Map<&'static str, Product> = phf::phf_map! {
-            "SNOHF" => Product::Meteorological(MeteorologicalCategory::Temperature(TemperatureParameter::NcepSnowPhaseChangeHeatFlux)),
-            "TTRAD" => Product::Meteorological(MeteorologicalCategory::Temperature(TemperatureParameter::NcepTemperatureTendencyByAllRadiation)),
+    /// `center_num` is from Common Code Table C-11 (GRIB1 table 0 uses the same numbers).
+    pub(crate) fn from_center_num(
+        center_num: u16,
+        subcenter: u16,
+        local_table_version: u8,
+    ) -> Self {
+        match center_num {
+            7 => OriginatingCenter::Ncep {
+                subcenter,
+                local_table_version,
+            },
+            _ => OriginatingCenter::Other { center_num },
+        }
+    }
+
+    pub(crate) fn center_num(&self) -> u16 {
+        match self {
+            OriginatingCenter::Ncep { .. } => 7,
+            OriginatingCenter::Other { center_num } => *center_num,
+        }
+    }
+
+    /// Every product in this center's local tables, in no particular order.
+    pub(crate) fn products(&self) -> impl Iterator<Item = &'static Product> {
+        self.local_table()
+            .into_iter()
+            .flat_map(|local_table| local_table.values())
+    }
+
+    /// Whether the local tables which this center declares have the parameter abbreviated
+    /// `abbrev`. The category enums list every local parameter we know of, whichever version of
+    /// the tables introduced it, so local lookups by number must check this.
+    pub(crate) fn has_local_parameter(&self, abbrev: &str) -> bool {
+        self.abbrev_to_product(abbrev).is_some()
+    }
+
+    /// Every local table we have for this center, oldest first for each group of sub-centres.
+    pub(crate) fn local_tables(&self) -> &'static [LocalTable] {
+        match self {
+            OriginatingCenter::Ncep { .. } => Self::ncep_local_tables(),
+            OriginatingCenter::Other { .. } => &[],
+        }
+    }
+
+    /// The sub-centre whose own local tables we use, or 0 if we use the center's main tables.
+    pub(crate) fn local_table_subcenter(&self) -> u16 {
+        let OriginatingCenter::Ncep { subcenter, .. } = *self else {
+            return 0;
         };
-        &ABBREV_TO_PRODUCT_NCEP
+        match self.selected_local_table() {
+            Some(table) if !table.version.subcenters.is_empty() => subcenter,
+            _ => 0,
+        }
+    }
+
+    /// The local table for this center's sub-centre, in the version it declares.
+    fn local_table(&self) -> Option<&'static AbbrevTable> {
+        self.selected_local_table().map(|table| &table.abbrevs)
+    }
+
+    fn selected_local_table(&self) -> Option<&'static LocalTable> {
+        let OriginatingCenter::Ncep {
+            subcenter,
+            local_table_version,
+        } = *self
+        else {
+            return None;
+        };
+        LocalTableVersion::select(self.local_tables(), subcenter, local_table_version)
+    }
+
+    fn ncep_local_tables() -> &'static [LocalTable] {
+        static NCEP_LOCAL_TABLES: [LocalTable; 2] = [LocalTable {
+            version: LocalTableVersion {
+                first_version: 1,
+                subcenters: &[],
+            },
+            abbrevs: abbrev_table! {
+                "SNOHF" => Product::Meteorological(MeteorologicalCategory::Temperature(TemperatureParameter::NcepSnowPhaseChangeHeatFlux)),
+                "TTRAD" => Product::Meteorological(MeteorologicalCategory::Temperature(TemperatureParameter::NcepTemperatureTendencyByAllRadiation)),
+                "CRAIN" => Product::Meteorological(MeteorologicalCategory::Moisture(MoistureParameter::NcepCategoricalRain)),
+                "CFRZR" => Product::Meteorological(MeteorologicalCategory::Moisture(MoistureParameter::NcepCategoricalFreezingRain)),
+                "CICEP" => Product::Meteorological(MeteorologicalCategory::Moisture(MoistureParameter::NcepCategoricalIcePellets)),
+                "CSNOW" => Product::Meteorological(MeteorologicalCategory::Moisture(MoistureParameter::NcepCategoricalSnow)),
+                "CPRAT" => Product::Meteorological(MeteorologicalCategory::Moisture(MoistureParameter::NcepConvectivePrecipitationRate)),
+                "FRAIN" => Product::Meteorological(MeteorologicalCategory::Moisture(MoistureParameter::NcepRainFraction)),
+                "RIME" => Product::Meteorological(MeteorologicalCategory::Moisture(MoistureParameter::NcepRimeFactor)),
+                "TCOLR" => Product::Meteorological(MeteorologicalCategory::Moisture(MoistureParameter::NcepTotalColumnIntegratedRain)),
+                "TCOLS" => Product::Meteorological(MeteorologicalCategory::Moisture(MoistureParameter::NcepTotalColumnIntegratedSnow)),
+                "VWSH" => Product::Meteorological(MeteorologicalCategory::Momentum(MomentumParameter::NcepVerticalSpeedShear)),
+                "MFLX" => Product::Meteorological(MeteorologicalCategory::Momentum(MomentumParameter::NcepHorizontalMomentumFlux)),
+                "USTM" => Product::Meteorological(MeteorologicalCategory::Momentum(MomentumParameter::NcepUComponentStormMotion)),
+                "VSTM" => Product::Meteorological(MeteorologicalCategory::Momentum(MomentumParameter::NcepVComponentStormMotion)),
+                "MSLET" => Product::Meteorological(MeteorologicalCategory::Mass(MassParameter::NcepMslpEtaModelReduction)),
+                "5WAVH" => Product::Meteorological(MeteorologicalCategory::Mass(MassParameter::NcepFiveWaveGeopotentialHeight)),
+                "U-GWD" => Product::Meteorological(MeteorologicalCategory::Mass(MassParameter::NcepZonalFluxOfGravityWaveStress)),
+                "V-GWD" => Product::Meteorological(MeteorologicalCategory::Mass(MassParameter::NcepMeridionalFluxOfGravityWaveStress)),
+                "HPBL" => Product::Meteorological(MeteorologicalCategory::Mass(MassParameter::NcepPlanetaryBoundaryLayerHeight)),
+                "5WAVA" => Product::Meteorological(MeteorologicalCategory::Mass(MassParameter::NcepFiveWaveGeopotentialHeightAnomaly)),
+                "MSLMA" => Product::Meteorological(MeteorologicalCategory::Mass(MassParameter::NcepMslpMapsSystemReduction)),
+                "PLPL" => Product::Meteorological(MeteorologicalCategory::Mass(MassParameter::NcepPressureOfLevelFromWhichParcelWasLifted)),
+                "OZCON" => Product::Meteorological(MeteorologicalCategory::TraceGases(TraceGasesParameter::NcepOzoneConcentration)),
+                "OZCAT" => Product::Meteorological(MeteorologicalCategory::TraceGases(TraceGasesParameter::NcepCategoricalOzoneConcentration)),
+                "CPOZP" => Product::Hydrological(HydrologicalCategory::HydrologyProbabilities(HydrologyProbabilitiesParameter::NcepProbabilityOfFreezingPrecipitation)),
+                "PPFFG" => Product::Hydrological(HydrologicalCategory::HydrologyProbabilities(HydrologyProbabilitiesParameter::NcepProbabilityOfPrecipitationExceedingFlashFloodGuidance)),
+                "CWR" => Product::Hydrological(HydrologicalCategory::HydrologyProbabilities(HydrologyProbabilitiesParameter::NcepProbabilityOfWettingRain)),
+            },
+        }, LocalTable {
+            // The Hydrometeorological Prediction Center (now the Weather Prediction Center) has
+            // its own table, which reuses 0.1.192 (CRAIN in NCEP's main table).
+            version: LocalTableVersion {
+                first_version: 1,
+                subcenters: &[5],
+            },
+            abbrevs: abbrev_table! {
+                "HPC-Wx" => Product::Meteorological(MeteorologicalCategory::Moisture(MoistureParameter::HpcWeatherCode)),
+            },
+        }];
+        &NCEP_LOCAL_TABLES
+    }
+}
+
+/// One version of a center's local tables.
+pub(crate) struct LocalTable {
+    pub(crate) version: LocalTableVersion,
+    abbrevs: AbbrevTable,
+}
+
+impl Borrow<LocalTableVersion> for LocalTable {
+    fn borrow(&self) -> &LocalTableVersion {
+        &self.version
     }
 }
@@ -0,0 +1,265 @@
+//! Find the values of a field at points which aren't on the grid, e.g. to make a time series at
+//! a weather station.
+
+use std::f64::consts::FRAC_PI_4;
+
+use crate::grid::{Grid, LambertConformalGrid, LatLonGrid, PolarStereographicGrid};
+
+/// How to combine the grid points around a location.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Interpolation {
+    /// The value of the closest grid point (in grid coordinates).
+    Nearest,
+
+    /// Weight the four surrounding grid points by their distance (in grid coordinates).
+    Bilinear,
+}
+
+/// The grid points which make up the value at a location.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Weights {
+    /// One point for [`Interpolation::Nearest`], and four for [`Interpolation::Bilinear`]. The
+    /// weights add up to 1.
+    pub points: Vec<WeightedPoint>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WeightedPoint {
+    /// The position along the i (or x) axis, counted in the grid's scanning direction.
+    pub i: u32,
+
+    /// The position along the j (or y) axis, counted in the grid's scanning direction.
+    pub j: u32,
+
+    /// The position of this point in the field's values, given the grid's scanning mode.
+    pub index: usize,
+    pub weight: f64,
+}
+
+impl Grid {
+    /// The grid points around (`lat`, `lon`), in degrees. Longitudes may be in -180 to 180 or in 0
+    /// to 360. Points between the last and the first column of a global lat/lon grid wrap around.
+    ///
+    /// Returns `None` if the location is outside the grid. Ellipsoidal Earths are treated as
+    /// spheres, which moves points by much less than a grid length on the grids NWP models use.
+    pub fn weights(&self, lat: f64, lon: f64, interpolation: Interpolation) -> Option<Weights> {
+        let (ni, nj) = self.dimensions();
+        let (x, y) = match self {
+            Grid::LatLon(grid) => grid.position(lat, lon)?,
+            Grid::PolarStereographic(grid) => grid.position(lat, lon),
+            Grid::LambertConformal(grid) => grid.position(lat, lon),
+        };
+        let wraps = matches!(self, Grid::LatLon(grid) if grid.is_global());
+        let x = axis_position(x, ni, wraps)?;
+        let y = axis_position(y, nj, false)?;
+        let points = match interpolation {
+            Interpolation::Nearest => vec![(x.nearest(), y.nearest(), 1.0)],
+            Interpolation::Bilinear => {
+                let (i0, i1, fx) = x.neighbours();
+                let (j0, j1, fy) = y.neighbours();
+                vec![
+                    (i0, j0, (1.0 - fx) * (1.0 - fy)),
+                    (i1, j0, fx * (1.0 - fy)),
+                    (i0, j1, (1.0 - fx) * fy),
+                    (i1, j1, fx * fy),
+                ]
+            }
+        };
+        let scanning_mode = self.scanning_mode();
+        let points = points
+            .into_iter()
+            .map(|(i, j, weight)| WeightedPoint {
+                i,
+                j,
+                index: index(i, j, ni, nj, scanning_mode),
+                weight,
+            })
+            .collect();
+        Some(Weights { points })
+    }
+
+    fn scanning_mode(&self) -> u8 {
+        match self {
+            Grid::LatLon(grid) => grid.scanning_mode,
+            Grid::PolarStereographic(grid) => grid.scanning_mode,
+            Grid::LambertConformal(grid) => grid.scanning_mode,
+        }
+    }
+}
+
+impl Weights {
+    /// Combine the values of a field (as returned by
+    /// [`Field::values`](crate::message::Field::values)). NaN if any of the points is missing.
+    pub fn interpolate(&self, values: &[f32]) -> f32 {
+        self.points
+            .iter()
+            .map(|point| {
+                let value = values.get(point.index).map_or(f64::NAN, |&v| f64::from(v));
+                value * point.weight
+            })
+            .sum::<f64>() as f32
+    }
+}
+
+impl LatLonGrid {
+    /// The fractional position of (`lat`, `lon`) along each axis, in grid lengths from the first
+    /// point. `None` if the increments are zero.
+    fn position(&self, lat: f64, lon: f64) -> Option<(f64, f64)> {
+        if self.di == 0.0 || self.dj == 0.0 {
+            return None;
+        }
+        let east = (lon - self.first_lon).rem_euclid(360.0);
+        let x = match self.scanning_mode & 0x80 {
+            0 => east,
+            _ => (360.0 - east).rem_euclid(360.0),
+        } / self.di;
+        let y = match self.scanning_mode & 0x40 {
+            0 => self.first_lat - lat,
+            _ => lat - self.first_lat,
+        } / self.dj;
+        Some((x, y))
+    }
+
+    /// Whether the columns go all the way around the Earth, so the first column follows the last.
+    fn is_global(&self) -> bool {
+        (f64::from(self.ni) * self.di - 360.0).abs() < self.di / 2.0
+    }
+}
+
+impl PolarStereographicGrid {
+    fn position(&self, lat: f64, lon: f64) -> (f64, f64) {
+        let south = self.projection_centre & 0x80 != 0;
+        let project = |lat: f64, lon: f64| {
+            let (lat, lad) = (lat.to_radians(), self.lad.to_radians());
+            let theta = (lon - self.lov).to_radians();
+            let radius = earth_radius(self.shape_of_earth);
+            if south {
+                let rho = radius * (1.0 - lad.sin()) * (FRAC_PI_4 + lat / 2.0).tan();
+                (rho * theta.sin(), rho * theta.cos())
+            } else {
+                let rho = radius * (1.0 + lad.sin()) * (FRAC_PI_4 - lat / 2.0).tan();
+                (rho * theta.sin(), -rho * theta.cos())
+            }
+        };
+        relative_position(
+            project(lat, lon),
+            project(self.first_lat, self.first_lon),
+            (self.dx, self.dy),
+            self.scanning_mode,
+        )
+    }
+}
+
+impl LambertConformalGrid {
+    fn position(&self, lat: f64, lon: f64) -> (f64, f64) {
+        // Snyder, "Map Projections: A Working Manual", equations 15-1 to 15-4, for a sphere.
+        let (latin1, latin2) = (self.latin1.to_radians(), self.latin2.to_radians());
+        let t = |lat: f64| (FRAC_PI_4 + lat / 2.0).tan();
+        let n = if (latin1 - latin2).abs() < 1e-9 {
+            latin1.sin()
+        } else {
+            (latin1.cos() / latin2.cos()).ln() / (t(latin2) / t(latin1)).ln()
+        };
+        let f = latin1.cos() * t(latin1).powf(n) / n;
+        let radius = earth_radius(self.shape_of_earth);
+        let project = |lat: f64, lon: f64| {
+            let rho = radius * f / t(lat.to_radians()).powf(n);
+            let theta = n * ((lon - self.lov + 180.0).rem_euclid(360.0) - 180.0).to_radians();
+            (rho * theta.sin(), -rho * theta.cos())
+        };
+        relative_position(
+            project(lat, lon),
+            project(self.first_lat, self.first_lon),
+            (self.dx, self.dy),
+            self.scanning_mode,
+        )
+    }
+}
+
+/// Convert projected coordinates (in metres) to grid lengths from the first point, in the
+/// grid's scanning directions.
+fn relative_position(
+    (x, y): (f64, f64),
+    (first_x, first_y): (f64, f64),
+    (dx, dy): (f64, f64),
+    scanning_mode: u8,
+) -> (f64, f64) {
+    let x = match scanning_mode & 0x80 {
+        0 => x - first_x,
+        _ => first_x - x,
+    } / dx;
+    let y = match scanning_mode & 0x40 {
+        0 => first_y - y,
+        _ => y - first_y,
+    } / dy;
+    (x, y)
+}
+
+/// In metres, from code table 3.2.
+fn earth_radius(shape_of_earth: u8) -> f64 {
+    match shape_of_earth {
+        0 => 6_367_470.0,
+        8 => 6_371_200.0,
+        // Including ellipsoids, which we treat as spheres.
+        _ => 6_371_229.0,
+    }
+}
+
+/// A fractional position along one axis of the grid.
+struct AxisPosition {
+    position: f64,
+    len: u32,
+    wraps: bool,
+}
+
+/// `None` if `position` is outside the axis. A position can be up to a thousandth of a grid
+/// length outside the axis, to allow for rounding errors at the edges.
+fn axis_position(position: f64, len: u32, wraps: bool) -> Option<AxisPosition> {
+    const TOLERANCE: f64 = 1e-3;
+    let last = f64::from(len) - if wraps { 0.0 } else { 1.0 };
+    if len == 0 || !(-TOLERANCE..=last + TOLERANCE).contains(&position) {
+        return None;
+    }
+    Some(AxisPosition {
+        position: position.clamp(0.0, last),
+        len,
+        wraps,
+    })
+}
+
+impl AxisPosition {
+    fn nearest(&self) -> u32 {
+        (self.position.round() as u32) % self.len
+    }
+
+    /// The points on either side, and how far it is from the first to the second.
+    fn neighbours(&self) -> (u32, u32, f64) {
+        let first = (self.position.floor() as u32).min(self.len - 1);
+        let second = match first + 1 {
+            second if second < self.len => second,
+            _ if self.wraps => 0,
+            _ => first,
+        };
+        let fraction = if second == first {
+            0.0
+        } else {
+            self.position - f64::from(first)
+        };
+        (first, second, fraction)
+    }
+}
+
+/// The position of point (`i`, `j`) in the values, given the scanning mode (flag table 3.4).
+fn index(i: u32, j: u32, ni: u32, nj: u32, scanning_mode: u8) -> usize {
+    let (row, column, row_len) = match scanning_mode & 0x20 {
+        0 => (j, i, ni),
+        _ => (i, j, nj),
+    };
+    // Boustrophedonic: every other row runs in the opposite direction.
+    let column = if scanning_mode & 0x10 != 0 && row % 2 == 1 {
+        row_len - 1 - column
+    } else {
+        column
+    };
+    row as usize * row_len as usize + column as usize
+}
@@ -1,17 +1,33 @@
+use core::ops::RangeInclusive;
+
 use crate::{
-    category::{Category, HydrologicalCategory, MeteorologicalCategory},
+    category::{
+        hydrological::HydrologicalCategory, meteorological::MeteorologicalCategory, Category,
+    },
     center_and_table_versions::CenterAndTableVersions,
+    code_table::CodeValue,
+    grib1::Grib1ParameterTable,
     master_table::MasterTable,
     originating_center::OriginatingCenter,
+    product_id::ProductId,
+    semantics::Semantics,
+    Parameter,
 };
 
-pub(crate) enum Product {
+/// A parameter such as temperature, from the tables of one discipline and category.
+///
+/// Non-exhaustive because the `runtime-tables` feature adds a variant, and so that more
+/// disciplines can be added.
+#[non_exhaustive]
+pub enum Product {
     Meteorological(MeteorologicalCategory),
     Hydrological(HydrologicalCategory),
 
-    // Local to NCEP:
-    NcepFoo(NcepFooProduct),
-    // etc.
+    /// A parameter from a local table loaded at runtime.
+    #[cfg(feature = "runtime-tables")]
+    Dynamic(&'static crate::local_tables::LocalParameter),
+    // Local disciplines would go here. None of the centers we support define any yet: NCEP's
+    // local parameters live in local categories and parameter numbers of the master disciplines.
 }
 
 impl Product {
@@ -20,10 +36,16 @@ impl Product {
         category_num: u8,
         parameter_num: u8,
         center_and_table_versions: CenterAndTableVersions,
-    ) -> Option<Product> {
+    ) -> CodeValue<Product> {
+        #[cfg(feature = "runtime-tables")]
+        let (center_num, local_table_version) = (
+            center_and_table_versions.originating_center().center_num(),
+            center_and_table_versions.local_table_version(),
+        );
+
         // This function just routes the query to the functions which handle Disciplines specified
         // in either local or master tables.
-        match discipline_num {
+        let product = match discipline_num {
             ..192 => Product::from_master_discipline_and_category_and_parameter_numbers(
                 discipline_num,
                 category_num,
@@ -39,7 +61,183 @@ impl Product {
                 center_and_table_versions,
             ),
 
-            255 => None, // 255 means "missing"
+            255 => CodeValue::Missing,
+        };
+
+        // Tables loaded at runtime are only consulted if the compiled-in tables don't know this
+        // parameter.
+        #[cfg(feature = "runtime-tables")]
+        let product = match product {
+            CodeValue::Known(product) => CodeValue::Known(product),
+            unknown => crate::local_tables::parameter_from_numbers(
+                center_num,
+                local_table_version,
+                discipline_num,
+                category_num,
+                parameter_num,
+            )
+            .map_or(unknown, |parameter| {
+                CodeValue::Known(Product::Dynamic(parameter))
+            }),
+        };
+
+        product
+    }
+
+    /// Decode a GRIB edition 1 parameter.
+    ///
+    /// GRIB1 identifies parameters by originating center, "table 2 version" and parameter number.
+    /// We look up the equivalent GRIB2 discipline, category and parameter numbers in
+    /// [`Grib1ParameterTable`] and then decode those, so both editions produce the same `Product`.
+    pub fn from_grib1_parameter_numbers(
+        center_num: u8,
+        table_version: u8,
+        parameter_num: u8,
+    ) -> Option<Product> {
+        let grib1_parameter =
+            Grib1ParameterTable::from_center_and_table_version(center_num, table_version)?
+                .parameter(parameter_num)?;
+
+        // GRIB1 has no concept of a local table version. NCEP's GRIB2 local tables start at 1.
+        // GRIB1 sub-centres choose their parameters by table version, so the GRIB2 equivalents
+        // are in the center's main local tables: use sub-centre 0.
+        let center_and_table_versions = CenterAndTableVersions::from_numbers(
+            center_num.into(),
+            0,
+            1,
+            MasterTable::LATEST.version_num(),
+        );
+        Product::from_discipline_and_category_and_parameter_numbers(
+            grib1_parameter.discipline,
+            grib1_parameter.category,
+            grib1_parameter.parameter,
+            center_and_table_versions,
+        )
+        .known()
+    }
+
+    /// Code table 0.0. The inverse of `from_discipline_and_category_and_parameter_numbers`, as
+    /// are `category_num` and `parameter_num`.
+    pub fn discipline_num(&self) -> u8 {
+        match self {
+            Product::Meteorological(_) => 0,
+            Product::Hydrological(_) => 1,
+            #[cfg(feature = "runtime-tables")]
+            Product::Dynamic(parameter) => parameter.discipline,
+        }
+    }
+
+    pub fn category_num(&self) -> u8 {
+        match self {
+            Product::Meteorological(category) => category.category_num(),
+            Product::Hydrological(category) => category.category_num(),
+            #[cfg(feature = "runtime-tables")]
+            Product::Dynamic(parameter) => parameter.category,
+        }
+    }
+
+    pub fn parameter_num(&self) -> u8 {
+        match self {
+            #[cfg(feature = "runtime-tables")]
+            Product::Dynamic(parameter) => parameter.parameter,
+            _ => self.parameter().parameter_num(),
+        }
+    }
+
+    /// A compact, stable identifier for this product. See [`ProductId::to_product`] for the
+    /// inverse. Products from a center's local tables are identified by the center and local
+    /// table version in `center_and_table_versions`, which should be the ones the product was
+    /// decoded with.
+    pub fn id(&self, center_and_table_versions: &CenterAndTableVersions) -> ProductId {
+        let numbers = (
+            self.discipline_num(),
+            self.category_num(),
+            self.parameter_num(),
+        );
+        match self {
+            #[cfg(feature = "runtime-tables")]
+            Product::Dynamic(parameter) => ProductId::new_local(
+                parameter.center,
+                0,
+                parameter.local_table_version.unwrap_or(0),
+                numbers.0,
+                numbers.1,
+                numbers.2,
+            ),
+            _ if [numbers.0, numbers.1, numbers.2]
+                .iter()
+                .any(|num| (192..=254).contains(num)) =>
+            {
+                ProductId::new_local(
+                    center_and_table_versions.center_num(),
+                    // Sub-centres with their own local tables are all below 255 (e.g. 5, HPC), so
+                    // 255 never finds a product again.
+                    center_and_table_versions
+                        .local_table_subcenter()
+                        .try_into()
+                        .unwrap_or(u8::MAX),
+                    center_and_table_versions.local_table_version(),
+                    numbers.0,
+                    numbers.1,
+                    numbers.2,
+                )
+            }
+            _ => ProductId::new(numbers.0, numbers.1, numbers.2),
+        }
+    }
+
+    pub fn abbrev(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "runtime-tables")]
+            Product::Dynamic(parameter) => &parameter.abbrev,
+            _ => self.parameter().abbrev(),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "runtime-tables")]
+            Product::Dynamic(parameter) => &parameter.name,
+            _ => self.parameter().name(),
+        }
+    }
+
+    pub fn unit(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "runtime-tables")]
+            Product::Dynamic(parameter) => &parameter.unit,
+            _ => self.parameter().unit(),
+        }
+    }
+
+    /// How this product usually combines along the time dimension. For decoded fields, use
+    /// [`Semantics::from_product_and_step`], which also takes the step into account.
+    ///
+    /// Runtime tables don't say, so their parameters are assumed to be instantaneous.
+    pub fn semantics(&self) -> Semantics {
+        match self {
+            #[cfg(feature = "runtime-tables")]
+            Product::Dynamic(_) => Semantics::Instantaneous,
+            _ => self.parameter().semantics(),
+        }
+    }
+
+    /// The range of physically plausible values, in `unit`, for quality control. `None` if
+    /// there's no useful range.
+    pub fn plausible_range(&self) -> Option<RangeInclusive<f64>> {
+        match self {
+            #[cfg(feature = "runtime-tables")]
+            Product::Dynamic(_) => None,
+            _ => self.parameter().plausible_range(),
+        }
+    }
+
+    fn parameter(&self) -> &dyn Parameter {
+        match self {
+            Product::Meteorological(category) => category.parameter(),
+            Product::Hydrological(category) => category.parameter(),
+            #[cfg(feature = "runtime-tables")]
+            Product::Dynamic(_) => unreachable!("dynamic products don't have a `Parameter`"),
         }
     }
 
@@ -48,45 +246,44 @@ impl Product {
         category_num: u8,
         parameter_num: u8,
         center_and_table_versions: CenterAndTableVersions,
-    ) -> Option<Self> {
+    ) -> CodeValue<Self> {
         match discipline_num {
-            0 => Some(Product::Meteorological(
-                MeteorologicalCategory::from_category_and_parameter_numbers(
-                    category_num,
-                    parameter_num,
-                    center_and_table_versions,
-                )?,
-            )),
+            0 => MeteorologicalCategory::from_category_and_parameter_numbers(
+                category_num,
+                parameter_num,
+                &center_and_table_versions,
+            )
+            .map(Product::Meteorological),
+            1 => HydrologicalCategory::from_category_and_parameter_numbers(
+                category_num,
+                parameter_num,
+                &center_and_table_versions,
+            )
+            .map(Product::Hydrological),
 
             // Demo of how to handle a discipline number which changes meaning across different
             // master table versions. This discipline number is made up! Just for demo purposes!
-            191 => match center_and_table_versions.master_table {
-                MasterTable::V32 => todo!(),
-                MasterTable::V33 => todo!(),
+            191 => match center_and_table_versions.master_table() {
+                // Neither version defines any parameters in it yet.
+                MasterTable::V32 => CodeValue::unknown(discipline_num),
+                MasterTable::V33 => CodeValue::unknown(discipline_num),
             },
 
             // Reserved for local use:
             192..=254 => panic!("Local disciplines should never be passed to this function!"),
-            _ => None,
+            _ => CodeValue::unknown(discipline_num),
         }
     }
 
     fn from_local_discipline_and_category_and_parameter_numbers(
         discipline_num: u8,
-        category_num: u8,
-        parameter_num: u8,
+        _category_num: u8,
+        _parameter_num: u8,
         center_and_table_versions: CenterAndTableVersions,
-    ) -> Option<Product> {
-        match center_and_table_versions.originating_center {
-            OriginatingCenter::NCEP => match discipline_num {
-                192 => Some(Product::NcepFoo(
-                    NcepFooProduct::from_category_and_parameter_numbers(
-                        category_num,
-                        parameter_num,
-                        center_and_table_versions,
-                    )?,
-                )),
-            },
+    ) -> CodeValue<Product> {
+        match center_and_table_versions.originating_center() {
+            OriginatingCenter::Ncep { .. } => CodeValue::unknown(discipline_num),
+            OriginatingCenter::Other { .. } => CodeValue::unknown(discipline_num),
         }
     }
 }
@@ -0,0 +1,89 @@
+use crate::{
+    constituent::Constituent,
+    ensemble::Ensemble,
+    error::Error,
+    level::Level,
+    message::{ReferenceTime, Section},
+    step::Step,
+};
+
+/// The parts of section 4 (the product definition section) which we decode.
+pub(crate) struct ProductDefinition {
+    /// Code table 4.0.
+    pub(crate) template_num: u16,
+
+    /// Code table 4.1.
+    pub(crate) category: u8,
+
+    /// Code table 4.2.
+    pub(crate) parameter: u8,
+
+    /// Code table 4.3.
+    pub(crate) type_of_generating_process: u8,
+
+    /// Defined by the originating center, e.g. ON388 table A for NCEP.
+    pub(crate) generating_process_identifier: u8,
+
+    pub(crate) level: Level,
+    pub(crate) step: Step,
+
+    /// Only set for atmospheric chemistry and aerosol templates (4.40 to 4.48).
+    pub(crate) constituent: Option<Constituent>,
+
+    /// `None` for deterministic forecasts.
+    pub(crate) ensemble: Option<Ensemble>,
+}
+
+impl ProductDefinition {
+    /// `reference_time` is from section 1.
+    pub(crate) fn parse(section: &Section, reference_time: ReferenceTime) -> Result<Self, Error> {
+        let template_num = section.u16(8)?;
+
+        // Where the time unit and the first fixed surface are, and, for statistically processed
+        // templates, the octet which holds "number of time range specifications". These move
+        // around because each template inserts its own fields first.
+        let (time_unit_octet, level_octet, statistics_n_octet) = match template_num {
+            0 | 1 | 2 | 5 | 6 | 15 => (18, 23, None),
+            8 => (18, 23, Some(42)),
+            9 => (18, 23, Some(55)),
+            10 => (18, 23, Some(43)),
+            11 => (18, 23, Some(45)),
+            12 => (18, 23, Some(44)),
+            40 | 41 => (20, 25, None),
+            42 => (20, 25, Some(44)),
+            43 => (20, 25, Some(47)),
+            44 => (31, 34, None),
+            45 => (31, 36, None),
+            46 => (31, 36, Some(55)),
+            47 => (31, 36, Some(58)),
+            48 => (42, 47, None),
+            // etc.
+            _ => {
+                return Err(Error::UnsupportedTemplate {
+                    section_num: 4,
+                    template_num,
+                })
+            }
+        };
+
+        let mut step = match template_num {
+            44 => Step::parse_short(section, time_unit_octet)?,
+            _ => Step::parse(section, time_unit_octet)?,
+        };
+        if let Some(n_octet) = statistics_n_octet {
+            step = step.parse_statistics(section, n_octet, reference_time)?;
+        }
+        Ok(Self {
+            template_num,
+            category: section.u8(10)?,
+            parameter: section.u8(11)?,
+            // In every template, the generating process is 6 octets before the time unit.
+            type_of_generating_process: section.u8(time_unit_octet - 6)?,
+            generating_process_identifier: section.u8(time_unit_octet - 4)?,
+            level: Level::parse(section, level_octet)?,
+            step,
+            constituent: Constituent::parse(section, template_num)?,
+            ensemble: Ensemble::parse(section, template_num)?,
+        })
+    }
+}
@@ -0,0 +1,172 @@
+use crate::{
+    center_and_table_versions::CenterAndTableVersions, master_table::MasterTable, product::Product,
+};
+
+/// A compact, stable identifier for a [`Product`], e.g. for use as a key in a large index.
+///
+/// The numbers are packed into a `u64`, most significant first, so that IDs sort by center, local
+/// table version, discipline, category, parameter and then sub-centre number:
+///
+/// | bits   | meaning                                                               |
+/// |--------|-----------------------------------------------------------------------|
+/// | 48..64 | center (common code table C-11). 65535 ("missing") for WMO products   |
+/// | 40..48 | local table version (code table 1.1). 0 for WMO products              |
+/// | 32..40 | discipline (code table 0.0)                                           |
+/// | 24..32 | category (code table 4.1)                                             |
+/// | 16..24 | parameter (code table 4.2)                                            |
+/// | 8..16  | sub-centre, for products from a sub-centre's own local tables. Else 0 |
+/// | 0..8   | flags. Bit 0 is set for products from a local table                   |
+///
+/// The packing will never change, so IDs can be stored.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "u64", into = "u64")
+)]
+pub struct ProductId(u64);
+
+/// Common code table C-11 uses 65535 for "missing".
+const NO_CENTER: u16 = u16::MAX;
+const LOCAL_FLAG: u64 = 1;
+
+impl ProductId {
+    /// A product from the WMO master tables.
+    pub fn new(discipline_num: u8, category_num: u8, parameter_num: u8) -> Self {
+        Self::pack(
+            NO_CENTER,
+            0,
+            discipline_num,
+            category_num,
+            parameter_num,
+            0,
+            0,
+        )
+    }
+
+    /// A product from the local tables of `center_num`. `subcenter` is 0 unless the product is
+    /// from a sub-centre's own local tables (see
+    /// [`CenterAndTableVersions::local_table_subcenter`]), and `local_table_version` is 0 if the
+    /// product means the same thing in every version of the center's local tables.
+    pub fn new_local(
+        center_num: u16,
+        subcenter: u8,
+        local_table_version: u8,
+        discipline_num: u8,
+        category_num: u8,
+        parameter_num: u8,
+    ) -> Self {
+        Self::pack(
+            center_num,
+            local_table_version,
+            discipline_num,
+            category_num,
+            parameter_num,
+            subcenter,
+            LOCAL_FLAG,
+        )
+    }
+
+    /// Returns `None` if `packed` wasn't made by [`ProductId::to_u64`].
+    pub fn from_u64(packed: u64) -> Option<Self> {
+        let id = Self(packed);
+        let reserved_bits_are_zero = packed & !LOCAL_FLAG & 0xff == 0;
+        let center_matches_flag = id.is_local() != (id.center_num() == NO_CENTER);
+        let wmo_numbers_are_zero =
+            id.is_local() || (id.local_table_version() == 0 && id.subcenter() == 0);
+        (reserved_bits_are_zero && center_matches_flag && wmo_numbers_are_zero).then_some(id)
+    }
+
+    pub fn to_u64(self) -> u64 {
+        self.0
+    }
+
+    /// 65535 for products from the WMO master tables.
+    pub fn center_num(self) -> u16 {
+        (self.0 >> 48) as u16
+    }
+
+    pub fn local_table_version(self) -> u8 {
+        (self.0 >> 40) as u8
+    }
+
+    pub fn discipline_num(self) -> u8 {
+        (self.0 >> 32) as u8
+    }
+
+    pub fn category_num(self) -> u8 {
+        (self.0 >> 24) as u8
+    }
+
+    pub fn parameter_num(self) -> u8 {
+        (self.0 >> 16) as u8
+    }
+
+    /// 0 unless the product is from a sub-centre's own local tables.
+    pub fn subcenter(self) -> u8 {
+        (self.0 >> 8) as u8
+    }
+
+    /// True for products from a center's local tables, rather than from the WMO master tables.
+    pub fn is_local(self) -> bool {
+        self.0 & LOCAL_FLAG != 0
+    }
+
+    /// Look up the product again. This is the inverse of [`Product::id`], and returns `None` if
+    /// the tables don't (or no longer) hold this product, e.g. because it came from a runtime
+    /// table which hasn't been registered.
+    pub fn to_product(self) -> Option<Product> {
+        let center_and_table_versions = || {
+            CenterAndTableVersions::from_numbers(
+                self.center_num(),
+                self.subcenter().into(),
+                self.local_table_version(),
+                MasterTable::LATEST.version_num(),
+            )
+        };
+        let product = Product::from_discipline_and_category_and_parameter_numbers(
+            self.discipline_num(),
+            self.category_num(),
+            self.parameter_num(),
+            center_and_table_versions(),
+        )
+        .known()?;
+
+        // e.g. a WMO product which was given a local ID.
+        (product.id(&center_and_table_versions()) == self).then_some(product)
+    }
+
+    fn pack(
+        center_num: u16,
+        local_table_version: u8,
+        discipline_num: u8,
+        category_num: u8,
+        parameter_num: u8,
+        subcenter: u8,
+        flags: u64,
+    ) -> Self {
+        Self(
+            u64::from(center_num) << 48
+                | u64::from(local_table_version) << 40
+                | u64::from(discipline_num) << 32
+                | u64::from(category_num) << 24
+                | u64::from(parameter_num) << 16
+                | u64::from(subcenter) << 8
+                | flags,
+        )
+    }
+}
+
+impl From<ProductId> for u64 {
+    fn from(id: ProductId) -> Self {
+        id.to_u64()
+    }
+}
+
+impl TryFrom<u64> for ProductId {
+    type Error = &'static str;
+
+    fn try_from(packed: u64) -> Result<Self, Self::Error> {
+        ProductId::from_u64(packed).ok_or("invalid product ID")
+    }
+}
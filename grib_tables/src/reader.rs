@@ -0,0 +1,239 @@
+use std::io::{self, Read, Seek, SeekFrom};
+
+use crate::{
+    error::Error,
+    message::{Indicator, Message, INDICATOR_LEN},
+};
+
+/// Every GRIB message starts with these four bytes.
+const MAGIC: &[u8; 4] = b"GRIB";
+
+/// How many bytes to read at a time when skipping over junk between messages.
+const SCAN_CHUNK_LEN: usize = 64 * 1024;
+
+/// Iterate over the GRIB2 messages in `bytes`, e.g. a whole GRIB2 file which has been read into
+/// memory (or memory-mapped).
+///
+/// Bytes between messages which aren't part of a GRIB message are skipped. Messages which aren't
+/// GRIB edition 2, or whose sections are malformed, are reported as errors and then skipped. A
+/// message which runs past the end of `bytes` is reported as `Error::Truncated`, after which the
+/// iterator ends.
+pub fn messages(bytes: &[u8]) -> SliceMessages<'_> {
+    SliceMessages {
+        bytes,
+        pos: 0,
+        done: false,
+    }
+}
+
+/// The iterator returned by [`messages`].
+pub struct SliceMessages<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    done: bool,
+}
+
+impl<'a> Iterator for SliceMessages<'a> {
+    type Item = Result<Message<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let Some(start) = find_magic(&self.bytes[self.pos..]).map(|i| self.pos + i) else {
+            self.done = true;
+            return None;
+        };
+        let remaining = &self.bytes[start..];
+        let indicator = match Indicator::parse(remaining, start as u64) {
+            Ok(indicator) => indicator,
+            Err(err) => {
+                // Fewer than 16 bytes left, or not edition 2. Either way, keep looking.
+                self.pos = start + MAGIC.len();
+                return Some(Err(err));
+            }
+        };
+        if (remaining.len() as u64) < indicator.total_length {
+            self.done = true;
+            return Some(Err(Error::Truncated {
+                offset: start as u64,
+                expected_len: indicator.total_length,
+                available_len: remaining.len() as u64,
+            }));
+        }
+        // Even if this message is malformed, we know where the next one should start.
+        self.pos = start + indicator.total_length as usize;
+        Some(Message::parse(remaining, start as u64))
+    }
+}
+
+/// Iterate over the GRIB2 messages in a `Read + Seek` stream, e.g. a `File`, starting from the
+/// stream's current position.
+///
+/// This reads one message at a time, so memory use is bounded by the largest message. Junk and
+/// errors are handled in the same way as [`messages`].
+pub struct MessageReader<R> {
+    reader: R,
+
+    /// Where to start looking for the next message. `None` until the first call to `next`.
+    pos: Option<u64>,
+    done: bool,
+}
+
+/// A GRIB2 message which has been read into memory by a [`MessageReader`].
+pub struct MessageBuf {
+    offset: u64,
+    bytes: Vec<u8>,
+}
+
+impl MessageBuf {
+    /// The position of the start of this message, in bytes from the start of the stream.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// The bytes of the whole message, from "GRIB" to "7777".
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// The length of the whole message, in bytes.
+    pub fn total_length(&self) -> u64 {
+        self.bytes.len() as u64
+    }
+
+    /// Decode the sections of this message.
+    pub fn message(&self) -> Message<'_> {
+        Message::parse(&self.bytes, self.offset)
+            .expect("MessageReader only yields messages which have already been parsed once")
+    }
+}
+
+impl<R: Read + Seek> MessageReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            pos: None,
+            done: false,
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    fn read_next(&mut self) -> Result<Option<MessageBuf>, Error> {
+        let pos = match self.pos {
+            Some(pos) => pos,
+            None => self.reader.stream_position()?,
+        };
+        let Some(start) = self.seek_to_magic(pos)? else {
+            self.done = true;
+            return Ok(None);
+        };
+
+        let mut bytes = Vec::with_capacity(INDICATOR_LEN);
+        (&mut self.reader)
+            .take(INDICATOR_LEN as u64)
+            .read_to_end(&mut bytes)?;
+        let indicator = match Indicator::parse(&bytes, start) {
+            Ok(indicator) => indicator,
+            Err(err) => {
+                // Fewer than 16 bytes left, or not edition 2. Either way, keep looking.
+                self.pos = Some(start + MAGIC.len() as u64);
+                return Err(err);
+            }
+        };
+
+        // Use `take` rather than allocating `total_length` bytes up front, in case the length is
+        // corrupt.
+        (&mut self.reader)
+            .take(indicator.total_length.saturating_sub(INDICATOR_LEN as u64))
+            .read_to_end(&mut bytes)?;
+        if (bytes.len() as u64) < indicator.total_length {
+            self.done = true;
+            return Err(Error::Truncated {
+                offset: start,
+                expected_len: indicator.total_length,
+                available_len: bytes.len() as u64,
+            });
+        }
+        self.pos = Some(start + indicator.total_length);
+
+        // Check the sections now, so errors are reported by the iterator.
+        Message::parse(&bytes, start)?;
+        Ok(Some(MessageBuf {
+            offset: start,
+            bytes,
+        }))
+    }
+
+    /// Seek to the next occurrence of "GRIB" at or after `pos`, and return its position.
+    /// Returns `None` if we reach the end of the stream first.
+    fn seek_to_magic(&mut self, pos: u64) -> Result<Option<u64>, Error> {
+        self.reader.seek(SeekFrom::Start(pos))?;
+        let mut buf = vec![0; SCAN_CHUNK_LEN];
+        let mut buf_start = pos;
+        let mut carried_len = 0;
+
+        // In well-formed files, the next message starts immediately, so check that first.
+        let mut read_len = MAGIC.len();
+        loop {
+            let n = read_up_to(
+                &mut self.reader,
+                &mut buf[carried_len..carried_len + read_len],
+            )?;
+            let filled_len = carried_len + n;
+            if let Some(i) = find_magic(&buf[..filled_len]) {
+                let start = buf_start + i as u64;
+                self.reader.seek(SeekFrom::Start(start))?;
+                return Ok(Some(start));
+            }
+            if n < read_len {
+                return Ok(None);
+            }
+
+            // Keep the last few bytes, in case "GRIB" straddles two chunks.
+            carried_len = filled_len.min(MAGIC.len() - 1);
+            buf.copy_within(filled_len - carried_len..filled_len, 0);
+            buf_start += (filled_len - carried_len) as u64;
+            read_len = SCAN_CHUNK_LEN - carried_len;
+        }
+    }
+}
+
+impl<R: Read + Seek> Iterator for MessageReader<R> {
+    type Item = Result<MessageBuf, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let result = self.read_next();
+        if let Err(Error::Io(_)) = result {
+            self.done = true;
+        }
+        result.transpose()
+    }
+}
+
+fn find_magic(bytes: &[u8]) -> Option<usize> {
+    bytes
+        .windows(MAGIC.len())
+        .position(|window| window == MAGIC)
+}
+
+/// Like `Read::read_exact`, except that reaching the end of the stream isn't an error.
+/// Returns the number of bytes read.
+fn read_up_to(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled_len = 0;
+    while filled_len < buf.len() {
+        match reader.read(&mut buf[filled_len..]) {
+            Ok(0) => break,
+            Ok(n) => filled_len += n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(filled_len)
+}
@@ -0,0 +1,58 @@
+use crate::{
+    product::Product,
+    step::{StatisticalProcess, Step},
+};
+
+/// How a field combines along the time dimension.
+///
+/// e.g. a 0-6 hour and a 0-12 hour precipitation accumulation can be subtracted to get the 6-12
+/// hour accumulation, but two precipitation rates can't.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Semantics {
+    /// Valid at a single time, e.g. temperature or precipitation rate.
+    Instantaneous,
+
+    /// Accumulated from the reference time to the valid time, e.g. "0-12 hour acc fcst".
+    AccumulatedSinceStart,
+
+    /// Accumulated over a time range which doesn't have to start at the reference time, e.g.
+    /// "6-12 hour acc fcst". For parameters (rather than decoded fields), this is used for every
+    /// accumulation: only the step says when the time range starts.
+    AccumulatedOverWindow,
+
+    /// Averaged over a time range.
+    Averaged,
+
+    /// The maximum or minimum over a time range.
+    Extremum,
+
+    /// Some other statistic over a time range, e.g. a standard deviation.
+    OtherStatistic,
+}
+
+impl Semantics {
+    /// The semantics of a decoded field. The step's statistical processing (if any) takes
+    /// precedence over the product's usual semantics: e.g. "TMP" is instantaneous, but a 6 hour
+    /// average of "TMP" is averaged.
+    pub fn from_product_and_step(product: &Product, step: &Step) -> Self {
+        let Some(statistics) = step.statistics else {
+            return product.semantics();
+        };
+        let Some(process) = statistics.process.known() else {
+            return Semantics::OtherStatistic;
+        };
+        match process {
+            StatisticalProcess::Accumulation | StatisticalProcess::Summation
+                if step.forecast_time == 0 =>
+            {
+                Semantics::AccumulatedSinceStart
+            }
+            StatisticalProcess::Accumulation | StatisticalProcess::Summation => {
+                Semantics::AccumulatedOverWindow
+            }
+            StatisticalProcess::Average => Semantics::Averaged,
+            StatisticalProcess::Maximum | StatisticalProcess::Minimum => Semantics::Extremum,
+            _ => Semantics::OtherStatistic,
+        }
+    }
+}
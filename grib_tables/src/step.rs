@@ -0,0 +1,400 @@
+use core::fmt;
+
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+
+use crate::code_table::CodeValue;
+#[cfg(feature = "std")]
+use crate::{
+    error::Error,
+    message::{ReferenceTime, Section},
+};
+
+/// Indicator of unit of time range (code table 4.4).
+#[derive(FromPrimitive, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TimeUnit {
+    Minute = 0,
+    Hour,
+    Day,
+    Month,
+    Year,
+    Decade,
+    Normal, // 30 years
+    Century,
+    ThreeHours = 10,
+    SixHours,
+    TwelveHours,
+    Second,
+}
+
+impl TimeUnit {
+    /// The length of one unit, in minutes. `None` for calendar units (months and longer), whose
+    /// length depends on the date, and for seconds.
+    pub(crate) fn minutes(&self) -> Option<u32> {
+        match self {
+            TimeUnit::Minute => Some(1),
+            TimeUnit::Hour => Some(60),
+            TimeUnit::Day => Some(60 * 24),
+            TimeUnit::ThreeHours => Some(60 * 3),
+            TimeUnit::SixHours => Some(60 * 6),
+            TimeUnit::TwelveHours => Some(60 * 12),
+            _ => None,
+        }
+    }
+
+    /// The abbreviation `wgrib2` uses, e.g. "hour" in "6 hour fcst".
+    pub fn abbrev(&self) -> &'static str {
+        match self {
+            TimeUnit::Minute => "min",
+            TimeUnit::Hour => "hour",
+            TimeUnit::Day => "day",
+            TimeUnit::Month => "month",
+            TimeUnit::Year => "year",
+            TimeUnit::Decade => "decade",
+            TimeUnit::Normal => "normal",
+            TimeUnit::Century => "century",
+            TimeUnit::ThreeHours => "3 hours",
+            TimeUnit::SixHours => "6 hours",
+            TimeUnit::TwelveHours => "12 hours",
+            TimeUnit::Second => "sec",
+        }
+    }
+
+    fn from_abbrev(abbrev: &str) -> Option<Self> {
+        (0..=u8::MAX)
+            .filter_map(TimeUnit::from_u8)
+            .find(|time_unit| time_unit.abbrev() == abbrev)
+    }
+
+    /// Convert 3, 6 and 12 hour units into hours, so that the same step always has the same
+    /// representation. Returns `None` if the number of hours doesn't fit in a `u32`.
+    fn normalise(time_unit: CodeValue<Self>, count: u32) -> Option<(CodeValue<Self>, u32)> {
+        let hours = match time_unit {
+            CodeValue::Known(TimeUnit::ThreeHours) => 3,
+            CodeValue::Known(TimeUnit::SixHours) => 6,
+            CodeValue::Known(TimeUnit::TwelveHours) => 12,
+            _ => return Some((time_unit, count)),
+        };
+        Some((CodeValue::Known(TimeUnit::Hour), count.checked_mul(hours)?))
+    }
+}
+
+/// Type of statistical processing (code table 4.10).
+#[derive(FromPrimitive, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StatisticalProcess {
+    Average = 0,
+    Accumulation,
+    Maximum,
+    Minimum,
+    DifferenceEndMinusStart,
+    RootMeanSquare,
+    StandardDeviation,
+    Covariance,
+    DifferenceStartMinusEnd,
+    Ratio,
+    StandardizedAnomaly,
+    Summation,
+    // etc.
+}
+
+impl StatisticalProcess {
+    /// The abbreviation `wgrib2` uses, e.g. "acc" in "0-6 hour acc fcst".
+    pub fn abbrev(&self) -> &'static str {
+        match self {
+            StatisticalProcess::Average => "ave",
+            StatisticalProcess::Accumulation => "acc",
+            StatisticalProcess::Maximum => "max",
+            StatisticalProcess::Minimum => "min",
+            StatisticalProcess::DifferenceEndMinusStart => "last-first",
+            StatisticalProcess::RootMeanSquare => "RMS",
+            StatisticalProcess::StandardDeviation => "StdDev",
+            StatisticalProcess::Covariance => "covar",
+            StatisticalProcess::DifferenceStartMinusEnd => "first-last",
+            StatisticalProcess::Ratio => "ratio",
+            StatisticalProcess::StandardizedAnomaly => "standardized anomaly",
+            StatisticalProcess::Summation => "summation",
+        }
+    }
+
+    fn from_abbrev(abbrev: &str) -> Option<Self> {
+        (0..=u8::MAX)
+            .filter_map(StatisticalProcess::from_u8)
+            .find(|process| process.abbrev() == abbrev)
+    }
+}
+
+/// A statistically processed time range, from product definition templates 4.8, 4.11, etc.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Statistics {
+    pub process: CodeValue<StatisticalProcess>,
+
+    /// The length of the time range, in the `Step`'s `time_unit`.
+    pub length: u32,
+}
+
+/// The forecast step of a field, e.g. "anl", "6 hour fcst" or "0-6 hour acc fcst".
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Step {
+    pub time_unit: CodeValue<TimeUnit>,
+
+    /// The forecast time. For statistically processed fields, this is the start of the time range.
+    pub forecast_time: u32,
+
+    /// `None` for instantaneous fields.
+    pub statistics: Option<Statistics>,
+}
+
+impl Step {
+    /// Returns `None` if the forecast time doesn't fit in a `u32` once converted into hours.
+    pub fn new(time_unit: TimeUnit, forecast_time: u32) -> Option<Self> {
+        Self::with_time_unit(CodeValue::Known(time_unit), forecast_time)
+    }
+
+    /// Like `new`, for units which may not be in code table 4.4.
+    fn with_time_unit(time_unit: CodeValue<TimeUnit>, forecast_time: u32) -> Option<Self> {
+        let (time_unit, forecast_time) = match TimeUnit::normalise(time_unit, forecast_time)? {
+            // An analysis is the same step whatever its unit.
+            (_, 0) => (CodeValue::Known(TimeUnit::Hour), 0),
+            normalised => normalised,
+        };
+        Some(Self {
+            time_unit,
+            forecast_time,
+            statistics: None,
+        })
+    }
+
+    /// The time range may be given in a different unit to the forecast time. If so, we convert
+    /// both to the coarsest unit which represents them both exactly.
+    ///
+    /// Returns `None` if the end of the time range doesn't fit in a `u32`, or if the units can't
+    /// be converted exactly (months and longer, whose length depends on the date, and seconds).
+    pub fn with_statistics(
+        self,
+        process: StatisticalProcess,
+        length_unit: TimeUnit,
+        length: u32,
+    ) -> Option<Self> {
+        self.with_coded_statistics(
+            CodeValue::Known(process),
+            CodeValue::Known(length_unit),
+            length,
+        )
+    }
+
+    /// Like `with_statistics`, for processes and units which may not be in code tables 4.10 and
+    /// 4.4. Units which aren't in code table 4.4 can't be converted at all.
+    fn with_coded_statistics(
+        self,
+        process: CodeValue<StatisticalProcess>,
+        length_unit: CodeValue<TimeUnit>,
+        length: u32,
+    ) -> Option<Self> {
+        let minutes = |time_unit: CodeValue<TimeUnit>| time_unit.known()?.minutes();
+        let (length_unit, length) = TimeUnit::normalise(length_unit, length)?;
+        let (time_unit, forecast_time, length) = if length_unit == self.time_unit {
+            (self.time_unit, self.forecast_time, length)
+        } else if self.forecast_time == 0 {
+            (length_unit, 0, length)
+        } else if length == 0 {
+            (self.time_unit, self.forecast_time, 0)
+        } else if let (Some(forecast_minutes), Some(length_minutes)) = (
+            minutes(self.time_unit).map(|m| u64::from(m) * u64::from(self.forecast_time)),
+            minutes(length_unit).map(|m| u64::from(m) * u64::from(length)),
+        ) {
+            in_coarsest_unit(forecast_minutes, length_minutes)?
+        } else {
+            // `Statistics::length` is in the forecast time's unit, and keeping it in its own unit
+            // instead would give the wrong end time.
+            return None;
+        };
+        forecast_time.checked_add(length)?;
+        Some(Self {
+            time_unit,
+            forecast_time,
+            statistics: Some(Statistics { process, length }),
+        })
+    }
+
+    /// Like `with_coded_statistics`, for a time range given by its end rather than its length.
+    /// This converts time ranges in months and longer, whose length depends on the date.
+    ///
+    /// Returns `None` if either time isn't valid, if the time range ends before it starts, or if
+    /// the times aren't a whole number of minutes apart.
+    #[cfg(feature = "std")]
+    fn with_end_time(
+        self,
+        process: CodeValue<StatisticalProcess>,
+        reference_time: ReferenceTime,
+        end_time: ReferenceTime,
+    ) -> Option<Self> {
+        let start_time = reference_time.checked_add(self.time_unit.known()?, self.forecast_time)?;
+        if !end_time.is_valid() {
+            return None;
+        }
+        let minutes = |from: ReferenceTime, to: ReferenceTime| {
+            let seconds = to.seconds_since_epoch() - from.seconds_since_epoch();
+            (seconds % 60 == 0).then(|| u64::try_from(seconds / 60).ok())?
+        };
+        let (time_unit, forecast_time, length) = in_coarsest_unit(
+            minutes(reference_time, start_time)?,
+            minutes(start_time, end_time)?,
+        )?;
+        forecast_time.checked_add(length)?;
+        Some(Self {
+            time_unit,
+            forecast_time,
+            statistics: Some(Statistics { process, length }),
+        })
+    }
+
+    /// Read the unit of time range (at `time_unit_octet`) and the forecast time which follows it.
+    /// In product definition template 4.0 and friends, `time_unit_octet` is 18.
+    #[cfg(feature = "std")]
+    pub(crate) fn parse(section: &Section, time_unit_octet: usize) -> Result<Self, Error> {
+        let time_unit = Self::parse_time_unit(section, time_unit_octet)?;
+        Self::with_time_unit(time_unit, section.u32(time_unit_octet + 1)?)
+            .ok_or_else(|| Self::too_long(section))
+    }
+
+    /// Like `parse`, for template 4.44, whose forecast time is only 2 octets long.
+    #[cfg(feature = "std")]
+    pub(crate) fn parse_short(section: &Section, time_unit_octet: usize) -> Result<Self, Error> {
+        let time_unit = Self::parse_time_unit(section, time_unit_octet)?;
+        Self::with_time_unit(time_unit, section.u16(time_unit_octet + 1)?.into())
+            .ok_or_else(|| Self::too_long(section))
+    }
+
+    /// Read the first time range specification of a statistically processed field. `n_octet` is
+    /// the octet holding "number of time range specifications", e.g. 42 in template 4.8.
+    ///
+    /// If the time range's unit can't be converted into the forecast time's unit (e.g. a one
+    /// month average which starts 6 hours after `reference_time`), we use the end of the overall
+    /// time interval, which the templates give just before `n_octet`.
+    #[cfg(feature = "std")]
+    pub(crate) fn parse_statistics(
+        self,
+        section: &Section,
+        n_octet: usize,
+        reference_time: ReferenceTime,
+    ) -> Result<Self, Error> {
+        // We only describe the outermost time range, like `wgrib2 -s` does.
+        let process = CodeValue::new(
+            section.u8(n_octet + 5)?,
+            StatisticalProcess::from_u8,
+            |_| None,
+        );
+        let length_unit = Self::parse_time_unit(section, n_octet + 7)?;
+        if let Some(step) =
+            self.with_coded_statistics(process, length_unit, section.u32(n_octet + 8)?)
+        {
+            return Ok(step);
+        }
+        let end_time = ReferenceTime {
+            year: section.u16(n_octet - 7)?,
+            month: section.u8(n_octet - 5)?,
+            day: section.u8(n_octet - 4)?,
+            hour: section.u8(n_octet - 3)?,
+            minute: section.u8(n_octet - 2)?,
+            second: section.u8(n_octet - 1)?,
+        };
+        self.with_end_time(process, reference_time, end_time)
+            .ok_or_else(|| Self::too_long(section))
+    }
+
+    #[cfg(feature = "std")]
+    fn too_long(section: &Section) -> Error {
+        Error::InvalidSection {
+            offset: section.offset(),
+            section_num: 4,
+            reason: "forecast time or time range is too long, or in incompatible units",
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn parse_time_unit(section: &Section, octet: usize) -> Result<CodeValue<TimeUnit>, Error> {
+        Ok(CodeValue::new(
+            section.u8(octet)?,
+            TimeUnit::from_u8,
+            |_| None,
+        ))
+    }
+
+    /// Parse the text which `wgrib2` prints for a step (the inverse of `Display`).
+    /// Returns `None` if we don't recognise the text.
+    pub fn from_idx_str(s: &str) -> Option<Self> {
+        if s == "anl" {
+            return Self::new(TimeUnit::Hour, 0);
+        }
+        let time_unit =
+            |unit| CodeValue::from_idx_str(unit, "unit", TimeUnit::from_abbrev, TimeUnit::from_u8);
+        let (times, rest) = s.strip_suffix(" fcst")?.split_once(' ')?;
+        match rest.split_once(' ') {
+            None => Self::with_time_unit(time_unit(rest)?, times.parse().ok()?),
+            Some((unit, process)) => {
+                let time_unit = time_unit(unit)?;
+                let process = CodeValue::from_idx_str(
+                    process,
+                    "process",
+                    StatisticalProcess::from_abbrev,
+                    StatisticalProcess::from_u8,
+                )?;
+                let (start, end) = times.split_once('-')?;
+                let (start, end): (u32, u32) = (start.parse().ok()?, end.parse().ok()?);
+                Self::with_time_unit(time_unit, start)?.with_coded_statistics(
+                    process,
+                    time_unit,
+                    end.checked_sub(start)?,
+                )
+            }
+        }
+    }
+
+    /// The end of the time range (or the forecast time, for instantaneous fields). `None` if it
+    /// doesn't fit in a `u32`, which can only happen if the fields were set by hand.
+    pub fn end(&self) -> Option<u32> {
+        self.forecast_time
+            .checked_add(self.statistics.map_or(0, |statistics| statistics.length))
+    }
+}
+
+/// Formats steps the same way as `wgrib2`. Units and processes which aren't in code tables 4.4
+/// and 4.10 are written as e.g. "unit=14" and "process=200".
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let unit = self
+            .time_unit
+            .idx_str("unit", |time_unit| time_unit.abbrev());
+        match self.statistics {
+            None if self.forecast_time == 0 => f.write_str("anl"),
+            None => write!(f, "{} {unit} fcst", self.forecast_time),
+            Some(statistics) => write!(
+                f,
+                "{}-{} {unit} {} fcst",
+                self.forecast_time,
+                u64::from(self.forecast_time) + u64::from(statistics.length),
+                statistics
+                    .process
+                    .idx_str("process", |process| process.abbrev())
+            ),
+        }
+    }
+}
+
+/// Convert a forecast time and a time range, both in minutes, into the coarsest unit which
+/// represents them both exactly. Returns `None` if either doesn't fit in a `u32` in that unit.
+fn in_coarsest_unit(
+    forecast_minutes: u64,
+    length_minutes: u64,
+) -> Option<(CodeValue<TimeUnit>, u32, u32)> {
+    let (unit, m) = [TimeUnit::Day, TimeUnit::Hour, TimeUnit::Minute]
+        .into_iter()
+        .map(|unit| (CodeValue::Known(unit), u64::from(unit.minutes().unwrap())))
+        .find(|&(_, m)| forecast_minutes.is_multiple_of(m) && length_minutes.is_multiple_of(m))
+        .unwrap();
+    Some((
+        unit,
+        u32::try_from(forecast_minutes / m).ok()?,
+        u32::try_from(length_minutes / m).ok()?,
+    ))
+}
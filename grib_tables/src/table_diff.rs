@@ -0,0 +1,227 @@
+//! Compare two versions of the parameter tables (code table 4.2), e.g. before upgrading to a new
+//! WMO master table version.
+
+use std::{collections::BTreeMap, fmt, io::Read};
+
+use crate::{csv, error::Error, master_table::MasterTable};
+
+/// One parameter from code table 4.2.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TableEntry {
+    pub discipline: u8,
+    pub category: u8,
+    pub parameter: u8,
+
+    /// `None` for tables which don't define abbreviations, such as WMO's CSV files.
+    pub abbrev: Option<String>,
+    pub name: String,
+    pub unit: String,
+}
+
+/// The parameters of one version of the tables, keyed by discipline, category and parameter
+/// number.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ParameterTable {
+    entries: BTreeMap<(u8, u8, u8), TableEntry>,
+}
+
+/// The differences between an older and a newer [`ParameterTable`]. Each list is sorted by
+/// discipline, category and parameter number.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TableDiff {
+    pub added: Vec<TableEntry>,
+    pub removed: Vec<TableEntry>,
+
+    /// Parameters whose name or abbreviation changed, as `(older, newer)`. Names are compared
+    /// case-insensitively, and abbreviations are only compared if both tables define them.
+    pub renamed: Vec<(TableEntry, TableEntry)>,
+
+    /// Parameters whose unit changed, as `(older, newer)`.
+    pub unit_changed: Vec<(TableEntry, TableEntry)>,
+}
+
+impl ParameterTable {
+    /// The compiled-in master table parameters for `master_table_version` (code table 1.0).
+    /// Versions which this crate doesn't know are decoded with the latest table, just like
+    /// messages are.
+    pub fn from_master_table_version(master_table_version: u8) -> Self {
+        let entries = MasterTable::from_version_num(master_table_version)
+            .products()
+            .map(|product| {
+                let entry = TableEntry {
+                    discipline: product.discipline_num(),
+                    category: product.category_num(),
+                    parameter: product.parameter_num(),
+                    abbrev: Some(product.abbrev().to_string()),
+                    name: product.name().to_string(),
+                    unit: product.unit().to_string(),
+                };
+                ((entry.discipline, entry.category, entry.parameter), entry)
+            })
+            .collect();
+        Self { entries }
+    }
+
+    /// Read the CSV release of code table 4.2 which WMO publishes alongside each master table
+    /// version (`GRIB2_CodeFlag_4_2_*_en.csv`). Rows which aren't a single parameter, such as
+    /// "Reserved for local use", are skipped.
+    pub fn from_wmo_csv<R: Read>(mut reader: R) -> Result<Self, Error> {
+        let mut csv = String::new();
+        reader.read_to_string(&mut csv)?;
+        let mut rows = rows(&csv);
+        let Some((_, header)) = rows.next() else {
+            return Ok(Self::default());
+        };
+        let header = csv::split_row(header).map_err(|reason| invalid(1, reason))?;
+        let column = |name: &str| {
+            header
+                .iter()
+                .position(|column| column.trim() == name)
+                .ok_or_else(|| invalid(1, format!("no \"{name}\" column")))
+        };
+        let subtitle = column("SubTitle_en")?;
+        let code = column("CodeFlag")?;
+        let name = column("MeaningParameterDescription_en")?;
+        let unit = column("UnitComments_en")?;
+
+        let mut entries = BTreeMap::new();
+        for (line_num, row) in rows {
+            let fields = csv::split_row(row).map_err(|reason| invalid(line_num, reason))?;
+            let field = |i: usize| fields.get(i).map_or("", |field| field.trim());
+            let Ok(parameter) = field(code).parse() else {
+                continue; // e.g. "192-254".
+            };
+            let (discipline, category) = discipline_and_category(field(subtitle))
+                .ok_or_else(|| invalid(line_num, format!("unrecognised subtitle in {row}")))?;
+            let entry = TableEntry {
+                discipline,
+                category,
+                parameter,
+                abbrev: None,
+                name: field(name).to_string(),
+                unit: field(unit).to_string(),
+            };
+            entries.insert((discipline, category, parameter), entry);
+        }
+        Ok(Self { entries })
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &TableEntry> {
+        self.entries.values()
+    }
+
+    /// What changed from `self` to `newer`.
+    pub fn diff(&self, newer: &ParameterTable) -> TableDiff {
+        let mut diff = TableDiff::default();
+        for (key, older) in &self.entries {
+            let Some(newer) = newer.entries.get(key) else {
+                diff.removed.push(older.clone());
+                continue;
+            };
+            let abbrev_changed = matches!(
+                (&older.abbrev, &newer.abbrev),
+                (Some(older), Some(newer)) if older != newer
+            );
+            if abbrev_changed || !older.name.eq_ignore_ascii_case(&newer.name) {
+                diff.renamed.push((older.clone(), newer.clone()));
+            }
+            if older.unit != newer.unit {
+                diff.unit_changed.push((older.clone(), newer.clone()));
+            }
+        }
+        diff.added = newer
+            .entries
+            .iter()
+            .filter(|(key, _)| !self.entries.contains_key(key))
+            .map(|(_, entry)| entry.clone())
+            .collect();
+        diff
+    }
+}
+
+impl TableDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.renamed.is_empty()
+            && self.unit_changed.is_empty()
+    }
+}
+
+/// e.g. `0.1.8 APCP "Total precipitation" (kg m-2)`.
+impl fmt::Display for TableEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}.{}.{} ",
+            self.discipline, self.category, self.parameter
+        )?;
+        if let Some(abbrev) = &self.abbrev {
+            write!(f, "{abbrev} ")?;
+        }
+        write!(f, "\"{}\" ({})", self.name, self.unit)
+    }
+}
+
+/// One line per difference.
+impl fmt::Display for TableDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in &self.added {
+            writeln!(f, "added: {entry}")?;
+        }
+        for entry in &self.removed {
+            writeln!(f, "removed: {entry}")?;
+        }
+        for (older, newer) in &self.renamed {
+            writeln!(f, "renamed: {older} -> {newer}")?;
+        }
+        for (older, newer) in &self.unit_changed {
+            writeln!(f, "unit changed: {older} -> {newer}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Split a CSV file into rows, with their line numbers (starting at 1). Quoted fields may contain
+/// newlines, so a row may span several lines.
+fn rows(csv: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut start = 0;
+    let mut line_num = 0;
+    let mut row_line_num = 1;
+    let mut in_quotes = false;
+    let mut rows = Vec::new();
+    for (i, c) in csv.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '\n' => {
+                line_num += 1;
+                if !in_quotes {
+                    rows.push((row_line_num, csv[start..i].trim_end_matches('\r')));
+                    start = i + 1;
+                    row_line_num = line_num + 1;
+                }
+            }
+            _ => (),
+        }
+    }
+    rows.push((row_line_num, &csv[start..]));
+    rows.into_iter().filter(|(_, row)| !row.trim().is_empty())
+}
+
+/// Parse a subtitle such as "Product discipline 0 - Meteorological products, parameter category
+/// 1: moisture".
+fn discipline_and_category(subtitle: &str) -> Option<(u8, u8)> {
+    let number_after = |prefix: &str| {
+        let (_, rest) = subtitle.split_once(prefix)?;
+        let digits = rest.trim_start();
+        let end = digits
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(digits.len());
+        digits[..end].parse().ok()
+    };
+    Some((number_after("discipline")?, number_after("category")?))
+}
+
+fn invalid(line: usize, reason: String) -> Error {
+    Error::InvalidWmoTable { line, reason }
+}
@@ -0,0 +1,161 @@
+//! Valid times: when a field is valid, rather than its reference time plus a step.
+//!
+//! All times are UTC, and are represented by [`ReferenceTime`] whether or not they're reference
+//! times. GRIB2 has no concept of leap seconds, so neither do we.
+
+use crate::{
+    message::ReferenceTime,
+    step::{Step, TimeUnit},
+};
+
+/// When a field is valid. For instantaneous fields, `start` and `end` are the same. For
+/// statistically processed fields (e.g. accumulations), the field describes the time range from
+/// `start` to `end`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ValidTime {
+    pub start: ReferenceTime,
+    pub end: ReferenceTime,
+}
+
+impl ValidTime {
+    /// Returns `None` if `reference_time` isn't a valid date and time, or if the valid time is
+    /// after the year 65535.
+    pub fn new(reference_time: ReferenceTime, step: &Step) -> Option<Self> {
+        Some(Self {
+            start: reference_time.checked_add(step.time_unit.known()?, step.forecast_time)?,
+            end: reference_time.checked_add(step.time_unit.known()?, step.end()?)?,
+        })
+    }
+
+    pub fn is_instantaneous(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+impl ReferenceTime {
+    /// Parse the `d=YYYYMMDDHH` field of a `.idx` file.
+    pub fn from_idx_str(s: &str) -> Option<Self> {
+        let date = s.strip_prefix("d=")?;
+        if date.len() != 10 || !date.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let reference_time = ReferenceTime {
+            year: date[0..4].parse().ok()?,
+            month: date[4..6].parse().ok()?,
+            day: date[6..8].parse().ok()?,
+            hour: date[8..10].parse().ok()?,
+            minute: 0,
+            second: 0,
+        };
+        reference_time.is_valid().then_some(reference_time)
+    }
+
+    /// Add `count` units of time.
+    ///
+    /// Months, years, decades, normals (30 years) and centuries are calendar units: adding one
+    /// month keeps the day of the month and the time of day, unless that day doesn't exist in the
+    /// new month, in which case we use the last day of the month. e.g. 31 January 2024 plus one
+    /// month is 29 February 2024. All other units have a fixed length.
+    ///
+    /// Returns `None` if `self` isn't a valid date and time, or if the result is after the year
+    /// 65535.
+    pub fn checked_add(self, time_unit: TimeUnit, count: u32) -> Option<Self> {
+        if !self.is_valid() {
+            return None;
+        }
+        let count = i64::from(count);
+        let seconds = match time_unit {
+            TimeUnit::Second => count,
+            TimeUnit::Month => return self.add_months(count),
+            TimeUnit::Year => return self.add_months(count * 12),
+            TimeUnit::Decade => return self.add_months(count * 12 * 10),
+            TimeUnit::Normal => return self.add_months(count * 12 * 30),
+            TimeUnit::Century => return self.add_months(count * 12 * 100),
+            _ => count * 60 * i64::from(time_unit.minutes()?),
+        };
+        let seconds = self.seconds_since_epoch() + seconds;
+        let days = seconds.div_euclid(SECONDS_PER_DAY);
+        let seconds_of_day = seconds.rem_euclid(SECONDS_PER_DAY);
+        let (year, month, day) = civil_from_days(days);
+        Some(Self {
+            year: year.try_into().ok()?,
+            month,
+            day,
+            hour: (seconds_of_day / 3600) as u8,
+            minute: (seconds_of_day / 60 % 60) as u8,
+            second: (seconds_of_day % 60) as u8,
+        })
+    }
+
+    fn add_months(self, months: i64) -> Option<Self> {
+        let months = i64::from(self.year) * 12 + i64::from(self.month) - 1 + months;
+        let year: u16 = (months / 12).try_into().ok()?;
+        let month = (months % 12 + 1) as u8;
+        Some(Self {
+            year,
+            month,
+            day: self.day.min(days_in_month(year, month)),
+            ..self
+        })
+    }
+
+    /// Seconds since 1970-01-01T00:00:00, ignoring leap seconds.
+    pub(crate) fn seconds_since_epoch(&self) -> i64 {
+        let days = days_from_civil(self.year.into(), self.month, self.day);
+        days * SECONDS_PER_DAY
+            + i64::from(self.hour) * 3600
+            + i64::from(self.minute) * 60
+            + i64::from(self.second)
+    }
+
+    pub(crate) fn is_valid(&self) -> bool {
+        (1..=12).contains(&self.month)
+            && (1..=days_in_month(self.year, self.month)).contains(&self.day)
+            && self.hour < 24
+            && self.minute < 60
+            && self.second < 60
+    }
+}
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+fn is_leap_year(year: u16) -> bool {
+    year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400))
+}
+
+fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// The number of days since 1970-01-01 in the proleptic Gregorian calendar. From Howard
+/// Hinnant's `days_from_civil`: <https://howardhinnant.github.io/date_algorithms.html>
+fn days_from_civil(year: i64, month: u8, day: u8) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month = i64::from(month);
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// The inverse of `days_from_civil`.
+fn civil_from_days(days: i64) -> (i64, u8, u8) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u8;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
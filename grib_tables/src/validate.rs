@@ -0,0 +1,422 @@
+//! Check GRIB2 files and `.idx` inventories against the tables, e.g. before indexing a new
+//! dataset.
+
+use std::{collections::BTreeMap, fmt};
+
+use crate::{
+    center_and_table_versions::CenterAndTableVersions,
+    code_table::CodeValue,
+    constituent::Constituent,
+    data_representation::DataRepresentation,
+    ensemble::Ensemble,
+    error::Error,
+    idx::IdxRecord,
+    level::Level,
+    message::{Field, Message},
+    product::Product,
+    product_definition::ProductDefinition,
+    reader,
+    step::Step,
+    AbbrevToProduct,
+};
+
+/// The problems found by [`validate_grib`], [`validate_idx`] or [`validate_grib_and_idx`].
+#[derive(Debug, Default)]
+pub struct Report {
+    /// How many fields (or `.idx` records) were checked.
+    pub num_fields: usize,
+    pub issues: Vec<Issue>,
+}
+
+/// One problem with one field (or one whole message, if `field_num` is `None`).
+#[derive(Debug)]
+pub struct Issue {
+    /// Starts at 1, like the first column of a `.idx` file.
+    pub message_num: u32,
+
+    /// Starts at 1. Only set when the message holds more than one field.
+    pub field_num: Option<u32>,
+    pub kind: IssueKind,
+}
+
+#[derive(Debug)]
+pub enum IssueKind {
+    /// The message couldn't be decoded at all.
+    InvalidMessage(Error),
+
+    /// This discipline, category and parameter aren't in the tables for the message's center
+    /// and table versions. `local` is true if the unknown number is reserved for local use.
+    UnknownParameter {
+        discipline: u8,
+        category: u8,
+        parameter: u8,
+        local: bool,
+    },
+
+    /// The parameter is 255 ("missing").
+    MissingParameter,
+
+    /// A `.idx` abbreviation which isn't in the tables, e.g. "var discipline=0 ...".
+    UnknownAbbrev(String),
+
+    /// A type of fixed surface (code table 4.5) which we don't know.
+    UnknownLevel { type_num: u8 },
+
+    /// `.idx` text for a level or step which we can't parse.
+    UnrecognisedIdxText { column: &'static str, text: String },
+
+    /// A template which we can't decode (yet).
+    UnsupportedTemplate { section_num: u8, template_num: u16 },
+
+    /// A template which WMO has deprecated, with the template which replaces it.
+    DeprecatedTemplate {
+        section_num: u8,
+        template_num: u16,
+        replacement: u16,
+    },
+
+    /// A value which WMO reserves for future use, e.g. a unit of time which isn't in code table 4.4.
+    ReservedValue { code_table: &'static str, value: u8 },
+
+    /// The `.idx` record and the GRIB2 message disagree.
+    IdxMismatch {
+        column: &'static str,
+        idx: String,
+        grib: String,
+    },
+
+    /// The GRIB2 file holds a field which isn't in the `.idx` file.
+    NotInIdx,
+
+    /// The `.idx` file lists a field which isn't in the GRIB2 file.
+    NotInGrib,
+}
+
+/// Check every field of every message in `grib` (the bytes of a whole GRIB2 file) against the
+/// tables for the center and table versions which each message declares.
+pub fn validate_grib(grib: &[u8]) -> Report {
+    let mut report = Report::default();
+    for (i, message) in reader::messages(grib).enumerate() {
+        let message_num = i as u32 + 1;
+        match message {
+            Ok(message) => check_message(&message, message_num, &mut report),
+            Err(err) => report.push(message_num, None, IssueKind::InvalidMessage(err)),
+        }
+    }
+    report
+}
+
+/// Check every record of `idx` (the contents of a `.idx` file) against the tables.
+///
+/// `.idx` files don't say which center, sub-centre or table versions they use, so these are
+/// passed in, just like for [`DatasetIndexer::new`](crate::dataset::DatasetIndexer::new). Returns
+/// an error if any line isn't a valid `.idx` record.
+pub fn validate_idx(
+    idx: &str,
+    center_num: u16,
+    subcenter: u16,
+    local_table_version: u8,
+    master_table_version: u8,
+) -> Result<Report, Error> {
+    let center_and_table_versions = CenterAndTableVersions::from_numbers(
+        center_num,
+        subcenter,
+        local_table_version,
+        master_table_version,
+    );
+    let mut report = Report::default();
+    for line in idx.lines().filter(|line| !line.trim().is_empty()) {
+        let record = IdxRecord::parse(line)?;
+        report.num_fields += 1;
+        let mut push = |kind| report.push(record.message_num, record.field_num, kind);
+        if center_and_table_versions
+            .abbrev_to_product(&record.abbrev)
+            .is_none()
+        {
+            push(IssueKind::UnknownAbbrev(record.abbrev.clone()));
+        }
+        match Level::from_idx_str(&record.level) {
+            Some(level) => check_level(&level, &mut push),
+            None => push(IssueKind::UnrecognisedIdxText {
+                column: "level",
+                text: record.level.clone(),
+            }),
+        }
+        if Step::from_idx_str(&record.step).is_none() {
+            push(IssueKind::UnrecognisedIdxText {
+                column: "step",
+                text: record.step.clone(),
+            });
+        }
+    }
+    Ok(report)
+}
+
+/// Like [`validate_grib`], and also check that `idx` describes `grib` correctly: that every field
+/// has exactly one record, and that the offsets, reference times, abbreviations, levels, steps
+/// and extra columns match what we decode from the messages.
+pub fn validate_grib_and_idx(grib: &[u8], idx: &str) -> Result<Report, Error> {
+    let mut idx_records = BTreeMap::new();
+    for line in idx.lines().filter(|line| !line.trim().is_empty()) {
+        let record = IdxRecord::parse(line)?;
+        idx_records.insert((record.message_num, record.field_num), record);
+    }
+
+    let mut report = Report::default();
+    for (i, message) in reader::messages(grib).enumerate() {
+        let message_num = i as u32 + 1;
+        let message = match message {
+            Ok(message) => message,
+            Err(err) => {
+                report.push(message_num, None, IssueKind::InvalidMessage(err));
+                continue;
+            }
+        };
+        check_message(&message, message_num, &mut report);
+        let grib_records = match IdxRecord::from_message(&message, message_num) {
+            Ok(grib_records) => grib_records,
+            // `check_message` has already reported why. The message's records in the `.idx` can't
+            // be compared with anything, but they aren't missing from the GRIB either.
+            Err(_) => {
+                idx_records.retain(|&(num, _), _| num != message_num);
+                continue;
+            }
+        };
+        for grib_record in grib_records {
+            let key = (grib_record.message_num, grib_record.field_num);
+            let mut push = |kind| report.push(key.0, key.1, kind);
+            match idx_records.remove(&key) {
+                Some(idx_record) => compare_records(&idx_record, &grib_record, &mut push),
+                None => push(IssueKind::NotInIdx),
+            }
+        }
+    }
+    for (message_num, field_num) in idx_records.into_keys() {
+        report.push(message_num, field_num, IssueKind::NotInGrib);
+    }
+    Ok(report)
+}
+
+impl Report {
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    fn push(&mut self, message_num: u32, field_num: Option<u32>, kind: IssueKind) {
+        self.issues.push(Issue {
+            message_num,
+            field_num,
+            kind,
+        });
+    }
+}
+
+fn check_message(message: &Message, message_num: u32, report: &mut Report) {
+    let identification = message.identification();
+    let fields = message.fields();
+
+    // Code tables 1.2, 1.3 and 1.4 aren't decoded anywhere else.
+    let reserved = [
+        ("1.2", identification.significance_of_reference_time, 6),
+        ("1.3", identification.production_status, 14),
+        ("1.4", identification.type_of_data, 9),
+    ];
+    for (code_table, value, first_reserved) in reserved {
+        if (first_reserved..192).contains(&value) {
+            report.push(
+                message_num,
+                None,
+                IssueKind::ReservedValue { code_table, value },
+            );
+        }
+    }
+
+    for (i, field) in fields.iter().enumerate() {
+        report.num_fields += 1;
+        let field_num = (fields.len() > 1).then_some(i as u32 + 1);
+        let mut push = |kind| report.push(message_num, field_num, kind);
+        check_field(message, field, &mut push);
+    }
+}
+
+fn check_field(message: &Message, field: &Field, push: &mut impl FnMut(IssueKind)) {
+    let mut check_template = |result: Result<(), Error>| match result {
+        Ok(()) => (),
+        Err(Error::UnsupportedTemplate {
+            section_num,
+            template_num,
+        }) => push(IssueKind::UnsupportedTemplate {
+            section_num,
+            template_num,
+        }),
+        Err(err) => push(IssueKind::InvalidMessage(err)),
+    };
+    check_template(field.grid().map(|_| ()));
+    check_template(DataRepresentation::parse(&field.data_representation).map(|_| ()));
+    let product_definition =
+        match ProductDefinition::parse(&field.product_definition, field.reference_time) {
+            Ok(product_definition) => product_definition,
+            Err(err) => return check_template(Err(err)),
+        };
+
+    if let Some(replacement) = replacement_for_deprecated_template(product_definition.template_num)
+    {
+        push(IssueKind::DeprecatedTemplate {
+            section_num: 4,
+            template_num: product_definition.template_num,
+            replacement,
+        });
+    }
+
+    let discipline = message.indicator().discipline;
+    let (category, parameter) = (product_definition.category, product_definition.parameter);
+    match Product::from_discipline_and_category_and_parameter_numbers(
+        discipline,
+        category,
+        parameter,
+        message.identification().center_and_table_versions(),
+    ) {
+        CodeValue::Known(_) => (),
+        CodeValue::Missing => push(IssueKind::MissingParameter),
+        CodeValue::Reserved(_) | CodeValue::LocalUse(_) => push(IssueKind::UnknownParameter {
+            discipline,
+            category,
+            parameter,
+            local: [discipline, category, parameter]
+                .iter()
+                .any(|num| (192..=254).contains(num)),
+        }),
+    }
+    check_level(&product_definition.level, push);
+    check_code_values(&product_definition, push);
+}
+
+fn check_level(level: &Level, push: &mut impl FnMut(IssueKind)) {
+    let surfaces = std::iter::once(level.first).chain(level.second);
+    for surface in surfaces {
+        match surface.surface_type() {
+            CodeValue::Known(_) | CodeValue::Missing => (),
+            CodeValue::Reserved(type_num) | CodeValue::LocalUse(type_num) => {
+                push(IssueKind::UnknownLevel { type_num })
+            }
+        }
+    }
+}
+
+/// Report values which WMO reserves in the code tables which describe the step, the ensemble and
+/// the constituent.
+fn check_code_values(product_definition: &ProductDefinition, push: &mut impl FnMut(IssueKind)) {
+    let step = product_definition.step;
+    let mut values = vec![("4.4", step.time_unit.map(|_| ()))];
+    if let Some(statistics) = step.statistics {
+        values.push(("4.10", statistics.process.map(|_| ())));
+    }
+    match product_definition.ensemble {
+        Some(Ensemble::Member(member)) => values.push(("4.6", member.forecast_type.map(|_| ()))),
+        Some(Ensemble::Derived(derived)) => values.push(("4.7", derived.map(|_| ()))),
+        Some(Ensemble::Probability(probability)) => {
+            values.push(("4.9", probability.probability_type.map(|_| ())))
+        }
+        None => (),
+    }
+    if let Some(Constituent::Aerosol(aerosol)) = product_definition.constituent {
+        for interval in aerosol.size.into_iter().chain(aerosol.wavelength) {
+            values.push(("4.91", interval.interval_type.map(|_| ())));
+        }
+    }
+    for (code_table, value) in values {
+        if let CodeValue::Reserved(value) = value {
+            push(IssueKind::ReservedValue { code_table, value });
+        }
+    }
+}
+
+fn compare_records(idx: &IdxRecord, grib: &IdxRecord, push: &mut impl FnMut(IssueKind)) {
+    let mut compare = |column, idx: String, grib: String| {
+        if idx != grib {
+            push(IssueKind::IdxMismatch { column, idx, grib });
+        }
+    };
+    compare("offset", idx.offset.to_string(), grib.offset.to_string());
+    compare(
+        "reference time",
+        format!("{:?}", idx.reference_time),
+        format!("{:?}", grib.reference_time),
+    );
+    compare("abbrev", idx.abbrev.clone(), grib.abbrev.clone());
+    compare("level", idx.level.clone(), grib.level.clone());
+    compare("step", idx.step.clone(), grib.step.clone());
+    compare("extra", idx.extra.join(":"), grib.extra.join(":"));
+}
+
+/// Product definition templates which WMO has deprecated (code table 4.0).
+fn replacement_for_deprecated_template(template_num: u16) -> Option<u16> {
+    match template_num {
+        // Aerosols. 4.48 adds the optical properties.
+        44 => Some(48),
+        // etc.
+        _ => None,
+    }
+}
+
+/// e.g. `4.2: unknown parameter 0.0.150`.
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message_num)?;
+        if let Some(field_num) = self.field_num {
+            write!(f, ".{field_num}")?;
+        }
+        write!(f, ": ")?;
+        match &self.kind {
+            IssueKind::InvalidMessage(err) => write!(f, "{err}"),
+            IssueKind::UnknownParameter {
+                discipline,
+                category,
+                parameter,
+                local,
+            } => {
+                let local = if *local { "local " } else { "" };
+                write!(
+                    f,
+                    "unknown {local}parameter {discipline}.{category}.{parameter}"
+                )
+            }
+            IssueKind::MissingParameter => write!(f, "parameter is missing"),
+            IssueKind::UnknownAbbrev(abbrev) => write!(f, "unknown abbreviation {abbrev}"),
+            IssueKind::UnknownLevel { type_num } => write!(f, "unknown level type {type_num}"),
+            IssueKind::UnrecognisedIdxText { column, text } => {
+                write!(f, "unrecognised {column} \"{text}\"")
+            }
+            IssueKind::UnsupportedTemplate {
+                section_num,
+                template_num,
+            } => write!(f, "unsupported template {section_num}.{template_num}"),
+            IssueKind::DeprecatedTemplate {
+                section_num,
+                template_num,
+                replacement,
+            } => write!(
+                f,
+                "template {section_num}.{template_num} is deprecated: use {section_num}.{replacement}"
+            ),
+            IssueKind::ReservedValue { code_table, value } => {
+                write!(f, "reserved value {value} in code table {code_table}")
+            }
+            IssueKind::IdxMismatch { column, idx, grib } => {
+                write!(f, "{column} is \"{idx}\" in the .idx but \"{grib}\" in the GRIB2")
+            }
+            IssueKind::NotInIdx => write!(f, "missing from the .idx"),
+            IssueKind::NotInGrib => write!(f, "missing from the GRIB2 file"),
+        }
+    }
+}
+
+/// One line per issue.
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for issue in &self.issues {
+            writeln!(f, "{issue}")?;
+        }
+        Ok(())
+    }
+}
@@ -1,43 +1,222 @@
-extern crate hypergrib_grib_tables;
+use hypergrib_grib_tables::{
+    center_and_table_versions::CenterAndTableVersions, product_id::ProductId, AbbrevToProduct,
+    Product,
+};
+use proptest::prelude::*;
+
+/// Every combination of tables compiled into this crate: both master table versions, with each
+/// version and sub-centre of NCEP's local tables, and without local tables (e.g. for ECMWF).
+fn every_table_version() -> Vec<TableVersion> {
+    let mut tables = Vec::new();
+    for master_table_version in [32, 33] {
+        for center_num in [7, 98] {
+            let local_tables = CenterAndTableVersions::from_numbers(center_num, 0, 0, 33);
+            tables.extend(local_tables.local_table_versions().flat_map(|version| {
+                let subcenters = match version.subcenters {
+                    [] => &[0],
+                    subcenters => subcenters,
+                };
+                subcenters.iter().map(move |&subcenter| TableVersion {
+                    center_num,
+                    subcenter,
+                    local_table_version: version.first_version,
+                    master_table_version,
+                })
+            }));
+        }
+        tables.push(TableVersion {
+            center_num: 98,
+            subcenter: 0,
+            local_table_version: 0,
+            master_table_version,
+        });
+    }
+    tables
+}
+
+#[derive(Clone, Copy, Debug)]
+struct TableVersion {
+    center_num: u16,
+    subcenter: u16,
+    local_table_version: u8,
+    master_table_version: u8,
+}
+
+impl TableVersion {
+    fn tables(&self) -> CenterAndTableVersions {
+        CenterAndTableVersions::from_numbers(
+            self.center_num,
+            self.subcenter,
+            self.local_table_version,
+            self.master_table_version,
+        )
+    }
+}
+
+#[test]
+fn abbrev_to_product() {
+    for version in every_table_version() {
+        let tables = version.tables();
+        for product in tables.products() {
+            let found = tables
+                .abbrev_to_product(product.abbrev())
+                .unwrap_or_else(|| {
+                    panic!(
+                        "{} isn't found by its abbreviation in {version:?}",
+                        product.abbrev()
+                    )
+                });
+            assert_eq!(found.abbrev(), product.abbrev());
+            assert_eq!(
+                found.id(&tables),
+                product.id(&tables),
+                "{}",
+                product.abbrev()
+            );
+        }
+    }
+}
 
 #[test]
-fn abbrev_to_product() {}
+fn grib_numbers_to_product() {
+    for version in every_table_version() {
+        for product in version.tables().products() {
+            let numbers = (
+                product.discipline_num(),
+                product.category_num(),
+                product.parameter_num(),
+            );
+            let found = Product::from_discipline_and_category_and_parameter_numbers(
+                numbers.0,
+                numbers.1,
+                numbers.2,
+                version.tables(),
+            )
+            .known()
+            .unwrap_or_else(|| {
+                panic!(
+                    "{} isn't found by its numbers in {version:?}",
+                    product.abbrev()
+                )
+            });
+            assert_eq!(found.abbrev(), product.abbrev());
+            assert_eq!(
+                (
+                    found.discipline_num(),
+                    found.category_num(),
+                    found.parameter_num()
+                ),
+                numbers
+            );
+            let id = product.id(&version.tables());
+            assert_eq!(ProductId::from_u64(id.to_u64()), Some(id));
+            assert_eq!(
+                id.to_product().map(|product| product.abbrev()),
+                Some(product.abbrev())
+            );
+        }
+    }
+}
 
 #[test]
-fn grib_numbers_to_product() {}
-
-
-fn ADAPT_THIS_CODE_FOR_THE_TESTS_ABOVE() {
-    // First, build a ParamDecoder:
-    let param_decoder: ParamDecoder = ParamDecoderBuilder::new()
-        .master_tables_version_number(24) // Optional. Defaults to latest table version.
-        .with_local_tables_version_number(4) // Optional. Defaults to ignoring local tables.
-        .center(OriginatingCenter::NCEP) // Required iff local tables are used.
-        .build();
-
-    // Now, there are two ways to "decode" a parameter:
-    // 1) Decode from the binary data in the GRIB sections:
-    let param = param_decoder
-        .from_grib() // -> ParamDecoderFromGrib
-        .discipline(Product::from_u8(0)) // or Discipline::from_u8(0)
-        .category(MeteorologicalCategory::from_u8(1)) //
-        .parameter_number(0)
-        .build();
-
-    // 2) Or decode from the abbreviation string from an .idx file:
-    let param = param_decoder
-        .from_idx() // -> ParamDecoderFromIdx
-        .abbrev("TMP")
-        .build();
-
-    // Now print information:
-    println!("{}, {}, {}", param.name(), param.unit(), param.abbrev());
-
-    // The above code is probably sufficient for the MVP. Later, we could add things like:
-    let param = param_decoder
-        .from_idx() // -> ParamDecoderFromIdx
-        .abbrev("TMP")
-        .vertical_level("5000-2000 m above ground")
-        .step("775-780 min ave fcst")
-        .build();
+fn hydrological_products() {
+    let tables = |center_num| CenterAndTableVersions::from_numbers(center_num, 0, 1, 33);
+    let abbrev = |numbers: (u8, u8, u8), center_num| {
+        Product::from_discipline_and_category_and_parameter_numbers(
+            numbers.0,
+            numbers.1,
+            numbers.2,
+            tables(center_num),
+        )
+        .known()
+        .map(|product| product.abbrev())
+    };
+    assert_eq!(abbrev((1, 0, 0), 98), Some("FFLDG"));
+    assert_eq!(abbrev((1, 0, 6), 98), Some("SSRUN"));
+    assert_eq!(abbrev((1, 1, 2), 98), Some("POP"));
+    assert_eq!(abbrev((1, 1, 195), 7), Some("CWR"));
+    assert_eq!(abbrev((1, 1, 195), 98), None);
+
+    let pop = tables(98).abbrev_to_product("POP").unwrap();
+    assert_eq!(
+        (
+            pop.discipline_num(),
+            pop.category_num(),
+            pop.parameter_num()
+        ),
+        (1, 1, 2)
+    );
+    assert_eq!(pop.unit(), "%");
+    assert!(tables(98).abbrev_to_product("CPOZP").is_none());
+    assert!(tables(7).abbrev_to_product("CPOZP").is_some());
+}
+
+/// A code table number, biased towards the ends of WMO's part of the table and the local part.
+fn code_table_num() -> impl Strategy<Value = u8> {
+    prop_oneof![0u8..=24, 185u8..=255, any::<u8>()]
+}
+
+proptest! {
+    #[test]
+    fn no_lookup_by_numbers_panics(
+        category_num in code_table_num(),
+        parameter_num in code_table_num(),
+        center_num in prop_oneof![Just(7u16), Just(98u16), any::<u16>()],
+        subcenter in prop_oneof![Just(0u16), Just(5u16), any::<u16>()],
+        local_table_version: u8,
+        master_table_version in prop_oneof![32u8..=33, any::<u8>()],
+    ) {
+        // There are few enough disciplines to try them all.
+        for discipline_num in 0..=u8::MAX {
+            let tables = CenterAndTableVersions::from_numbers(
+                center_num,
+                subcenter,
+                local_table_version,
+                master_table_version,
+            );
+            let product = Product::from_discipline_and_category_and_parameter_numbers(
+                discipline_num,
+                category_num,
+                parameter_num,
+                tables,
+            );
+
+            // Whatever we find must describe itself with the numbers we looked it up by.
+            if let Some(product) = product.known() {
+                prop_assert_eq!(
+                    (product.discipline_num(), product.category_num(), product.parameter_num()),
+                    (discipline_num, category_num, parameter_num)
+                );
+                let _ = (product.name(), product.unit(), product.semantics(), product.plausible_range());
+            }
+
+            // GRIB1 uses the same three octets for its center, table version and parameter.
+            if let Some(product) =
+                Product::from_grib1_parameter_numbers(discipline_num, category_num, parameter_num)
+            {
+                let tables =
+                    CenterAndTableVersions::from_numbers(center_num, subcenter, local_table_version, 33);
+                let _ = (product.abbrev(), product.id(&tables));
+            }
+        }
+    }
+
+    #[test]
+    fn no_lookup_by_id_or_abbrev_panics(packed: u64, abbrev in "\\PC{0,8}") {
+        if let Some(id) = ProductId::from_u64(packed) {
+            if let Some(product) = id.to_product() {
+                let tables = CenterAndTableVersions::from_numbers(
+                    id.center_num(),
+                    id.subcenter().into(),
+                    id.local_table_version(),
+                    33,
+                );
+                prop_assert_eq!(product.id(&tables).to_u64(), packed);
+            }
+        }
+        let tables = CenterAndTableVersions::from_numbers(7, 0, 1, 33);
+        if let Some(product) = tables.abbrev_to_product(&abbrev) {
+            prop_assert_eq!(product.abbrev(), abbrev.as_str());
+        }
+    }
 }
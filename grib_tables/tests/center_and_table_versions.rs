@@ -0,0 +1,168 @@
+use hypergrib_grib_tables::{
+    center_and_table_versions::{CenterAndTableVersions, LocalTableVersion},
+    encoder::Encoder,
+    grid::{Grid, LatLonGrid},
+    idx::write_inventory,
+    level::Level,
+    message::ReferenceTime,
+    step::{Step, TimeUnit},
+    AbbrevToProduct,
+};
+
+/// The abbreviation which `write_inventory` gives NCEP's CRAIN (0.1.192), in a message from
+/// `center_num` and `subcenter` which declares `local_table_version`.
+fn decode_crain(center_num: u16, subcenter: u16, local_table_version: u8) -> String {
+    let ncep = CenterAndTableVersions::from_numbers(7, 0, 1, 33);
+    let center_and_table_versions =
+        CenterAndTableVersions::from_numbers(center_num, 0, local_table_version, 33);
+    let encoder = Encoder {
+        product: ncep.abbrev_to_product("CRAIN").unwrap(),
+        center_and_table_versions: &center_and_table_versions,
+        reference_time: ReferenceTime {
+            year: 2024,
+            month: 1,
+            day: 2,
+            hour: 6,
+            minute: 0,
+            second: 0,
+        },
+        type_of_generating_process: 2,
+        grid: Grid::LatLon(LatLonGrid {
+            shape_of_earth: 6,
+            ni: 2,
+            nj: 1,
+            first_lat: 0.0,
+            first_lon: 0.0,
+            resolution_and_component_flags: 0x30,
+            last_lat: 0.0,
+            last_lon: 1.0,
+            di: 1.0,
+            dj: 1.0,
+            scanning_mode: 0,
+        }),
+        level: Level::from_idx_str("surface").unwrap(),
+        step: Step::new(TimeUnit::Hour, 6).unwrap(),
+        bits_per_value: 1,
+        decimal_scale_factor: 0,
+    };
+    let mut grib2 = encoder.encode(&[0.0, 1.0]).unwrap();
+
+    // The encoder always writes sub-centre 0. Patch octets 8 to 9 of section 1.
+    grib2[16 + 7..16 + 9].copy_from_slice(&subcenter.to_be_bytes());
+    let mut inventory = Vec::new();
+    write_inventory(&grib2, &mut inventory).unwrap();
+    let inventory = String::from_utf8(inventory).unwrap();
+    inventory.split(':').nth(3).unwrap().to_string()
+}
+
+#[test]
+fn ncep_local_tables_fall_back_to_the_nearest_earlier_version() {
+    // NCEP's files declare local table version 1. We have no version 9, so use version 1.
+    assert_eq!(decode_crain(7, 0, 1), "CRAIN");
+    assert_eq!(decode_crain(7, 0, 9), "CRAIN");
+
+    // 0 and 255 don't name a version, so use the latest.
+    assert_eq!(decode_crain(7, 0, 0), "CRAIN");
+    assert_eq!(decode_crain(7, 0, 255), "CRAIN");
+
+    // Sub-centres without tables of their own (e.g. 14, MDL) use NCEP's main tables.
+    assert_eq!(decode_crain(7, 14, 1), "CRAIN");
+    let mdl = CenterAndTableVersions::from_numbers(7, 14, 1, 33);
+    assert!(mdl.abbrev_to_product("CRAIN").is_some());
+    assert_eq!(mdl.local_table_subcenter(), 0);
+
+    // Other centers don't use NCEP's local tables.
+    assert_eq!(
+        decode_crain(98, 0, 1),
+        "var discipline=0 center=98 local_table=1 parmcat=1 parm=192"
+    );
+    let ecmwf = CenterAndTableVersions::from_numbers(98, 0, 1, 33);
+    assert!(ecmwf.abbrev_to_product("CRAIN").is_none());
+}
+
+#[test]
+fn subcenters_with_their_own_local_tables_only_use_those() {
+    // HPC (sub-centre 5) has a local table of its own, in which 0.1.192 isn't CRAIN.
+    assert_eq!(decode_crain(7, 5, 1), "HPC-Wx");
+    let hpc = CenterAndTableVersions::from_numbers(7, 5, 1, 33);
+    let hpc_wx = hpc.abbrev_to_product("HPC-Wx").unwrap();
+    assert_eq!(
+        (
+            hpc_wx.discipline_num(),
+            hpc_wx.category_num(),
+            hpc_wx.parameter_num()
+        ),
+        (0, 1, 192)
+    );
+    assert_eq!((hpc_wx.name(), hpc_wx.unit()), ("HPC Code", "-"));
+    assert!(hpc.abbrev_to_product("CRAIN").is_none());
+    assert_eq!(hpc.local_table_subcenter(), 5);
+
+    let ncep = CenterAndTableVersions::from_numbers(7, 0, 1, 33);
+    assert!(ncep.abbrev_to_product("HPC-Wx").is_none());
+
+    // The sub-centre is part of the ID, so the two products don't share one.
+    let hpc_wx_id = hpc_wx.id(&hpc);
+    let crain_id = ncep.abbrev_to_product("CRAIN").unwrap().id(&ncep);
+    assert_ne!(hpc_wx_id, crain_id);
+    assert_eq!((hpc_wx_id.subcenter(), crain_id.subcenter()), (5, 0));
+    assert_eq!(hpc_wx_id.to_product().unwrap().abbrev(), "HPC-Wx");
+    assert_eq!(crain_id.to_product().unwrap().abbrev(), "CRAIN");
+}
+
+#[test]
+fn local_tables_are_selected_by_version_and_subcenter() {
+    // We only have one version of each of NCEP's tables, so select between made-up ones.
+    let tables = [
+        LocalTableVersion {
+            first_version: 1,
+            subcenters: &[],
+        },
+        LocalTableVersion {
+            first_version: 3,
+            subcenters: &[],
+        },
+        LocalTableVersion {
+            first_version: 2,
+            subcenters: &[14],
+        },
+    ];
+    let select = |subcenter, version| {
+        LocalTableVersion::select(&tables, subcenter, version)
+            .map(|table| tables.iter().position(|t| t == table).unwrap())
+    };
+
+    // Versions N-1, N and later, and a version older than any we have.
+    assert_eq!(select(0, 2), Some(0));
+    assert_eq!(select(0, 3), Some(1));
+    assert_eq!(select(0, 7), Some(1));
+    assert_eq!(select(0, 0), Some(1));
+    assert_eq!(select(0, 255), Some(1));
+
+    // A sub-centre with tables of its own only uses those.
+    assert_eq!(select(14, 2), Some(2));
+    assert_eq!(select(14, 3), Some(2));
+    assert_eq!(select(14, 1), None);
+
+    // Other sub-centres use the center's main tables.
+    assert_eq!(select(4, 2), Some(0));
+    assert_eq!(select(4, 3), Some(1));
+
+    let ncep = CenterAndTableVersions::from_numbers(7, 0, 1, 33);
+    let versions: Vec<_> = ncep.local_table_versions().collect();
+    assert_eq!(
+        versions,
+        [
+            LocalTableVersion {
+                first_version: 1,
+                subcenters: &[],
+            },
+            LocalTableVersion {
+                first_version: 1,
+                subcenters: &[5],
+            }
+        ]
+    );
+    let ecmwf = CenterAndTableVersions::from_numbers(98, 0, 1, 33);
+    assert_eq!(ecmwf.local_table_versions().count(), 0);
+}
@@ -0,0 +1,176 @@
+use hypergrib_grib_tables::{
+    code_table::CodeValue,
+    dataset::DatasetIndexer,
+    ensemble::{Ensemble, Probability},
+    idx::write_inventory,
+    level::{FixedSurface, Level},
+    step::{Statistics, Step, TimeUnit},
+    validate::{validate_grib, IssueKind},
+};
+
+fn section(section_num: u8, body: &[u8]) -> Vec<u8> {
+    let mut section = ((body.len() + 5) as u32).to_be_bytes().to_vec();
+    section.push(section_num);
+    section.extend_from_slice(body);
+    section
+}
+
+const TWO_M: [u8; 12] = [103, 0, 0, 0, 0, 2, 255, 255, 255, 255, 255, 255];
+
+/// A GRIB2 message holding a 6 unit forecast of temperature (0.0.0), using product definition
+/// template `template_num`. `extra` is the template's octets after the fixed surfaces. Only the
+/// sections which the inventory looks at are filled in.
+fn message(template_num: u16, time_unit: u8, surfaces: [u8; 12], extra: &[u8]) -> Vec<u8> {
+    let mut identification = vec![0, 7, 0, 0, 33, 1, 1];
+    identification.extend_from_slice(&2024u16.to_be_bytes());
+    identification.extend_from_slice(&[1, 2, 12, 0, 0, 0, 1]);
+
+    let mut product_definition = vec![0, 0];
+    product_definition.extend_from_slice(&template_num.to_be_bytes());
+    product_definition.extend_from_slice(&[0, 0, 2, 0, 96, 0, 0, 0, time_unit]);
+    product_definition.extend_from_slice(&6u32.to_be_bytes());
+    product_definition.extend_from_slice(&surfaces);
+    product_definition.extend_from_slice(extra);
+
+    let mut body = section(1, &identification);
+    body.extend(section(3, &[0; 67]));
+    body.extend(section(4, &product_definition));
+    body.extend(section(5, &[0; 16]));
+    body.extend(section(6, &[255]));
+    body.extend(section(7, &[]));
+
+    let mut message = b"GRIB\0\0\0\x02".to_vec();
+    message.extend_from_slice(&((body.len() + 20) as u64).to_be_bytes());
+    message.extend(body);
+    message.extend_from_slice(b"7777");
+    message
+}
+
+/// Template 4.8: the `process` of 6 hours of forecasts, ending at 2024-01-03 00:00.
+fn statistics(process: u8) -> Vec<u8> {
+    let mut extra = 2024u16.to_be_bytes().to_vec();
+    extra.extend_from_slice(&[1, 3, 0, 0, 0, 1, 0, 0, 0, 0, process, 2, 1, 0, 0, 0, 6]);
+    extra.extend_from_slice(&[1, 0, 0, 0, 0]);
+    extra
+}
+
+#[test]
+fn decodes_values_which_arent_in_the_code_tables() {
+    // 4.4 and 4.5 (on one message), then 4.6, 4.7, 4.9 and 4.10: 100 is reserved in all of them.
+    let level_type_190 = [190, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255];
+    let messages = [
+        message(0, 14, level_type_190, &[]),
+        message(1, 1, TWO_M, &[100, 2, 20]),
+        message(2, 1, TWO_M, &[100, 20]),
+        message(
+            5,
+            1,
+            TWO_M,
+            &[1, 2, 100, 3, 0, 0, 0, 254, 255, 255, 255, 255, 255],
+        ),
+        message(8, 1, TWO_M, &statistics(100)),
+    ];
+    let mut offsets = vec![0];
+    offsets.extend(messages.iter().scan(0, |offset, message| {
+        *offset += message.len();
+        Some(*offset)
+    }));
+    let grib = messages.concat();
+
+    let mut inventory = Vec::new();
+    write_inventory(&grib, &mut inventory).unwrap();
+    let inventory = String::from_utf8(inventory).unwrap();
+    let lines = [
+        "TMP:level type 190:6 unit=14 fcst:",
+        "TMP:2 m above ground:6 hour fcst:ENS=type=100 pert=2:",
+        "TMP:2 m above ground:6 hour fcst:derived=100:",
+        "TMP:2 m above ground:6 hour fcst:prob type=100 lower=0.254 upper=missing:prob fcst 1/2:",
+        "TMP:2 m above ground:6-12 hour process=100 fcst:",
+    ];
+    let expected: String = lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| format!("{}:{}:d=2024010212:{line}\n", i + 1, offsets[i]))
+        .collect();
+    assert_eq!(inventory, expected);
+
+    // The numbers are read back from the `.idx` as the same values.
+    let mut indexer = DatasetIndexer::new(7, 0, 1, 33);
+    indexer.add_idx("gfs.grib2", &inventory, None).unwrap();
+    let index = indexer.finish();
+    assert!(index.undecoded().is_empty());
+    let surface = FixedSurface {
+        type_num: 190,
+        value: None,
+    };
+    assert_eq!(surface.surface_type(), CodeValue::Reserved(190));
+    assert!(index.levels().contains(&Level {
+        first: surface,
+        second: None,
+    }));
+    let step = |time_unit, statistics| Step {
+        time_unit,
+        forecast_time: 6,
+        statistics,
+    };
+    assert!(index.steps().contains(&step(CodeValue::Reserved(14), None)));
+    let statistics = Statistics {
+        process: CodeValue::Reserved(100),
+        length: 6,
+    };
+    let hour = CodeValue::Known(TimeUnit::Hour);
+    assert!(index.steps().contains(&step(hour, Some(statistics))));
+    let ensembles: Vec<_> = index.ensembles().iter().flatten().collect();
+    assert!(matches!(
+        ensembles.as_slice(),
+        [
+            Ensemble::Member(member),
+            Ensemble::Derived(CodeValue::Reserved(100)),
+            Ensemble::Probability(Probability {
+                probability_type: CodeValue::Reserved(100),
+                lower_limit: Some(_),
+                upper_limit: None,
+                forecast_probability_num: 1,
+                total_forecast_probabilities: 2,
+            }),
+        ] if member.forecast_type == CodeValue::Reserved(100) && member.perturbation_num == 2
+    ));
+
+    // Validation reports them, without failing to decode the messages.
+    let report = validate_grib(&grib);
+    let issues: Vec<_> = report
+        .issues
+        .iter()
+        .filter_map(|issue| match issue.kind {
+            IssueKind::ReservedValue { code_table, value } => {
+                Some((issue.message_num, code_table, value))
+            }
+            IssueKind::UnknownLevel { type_num } => Some((issue.message_num, "4.5", type_num)),
+            IssueKind::InvalidMessage(_) => panic!("{issue}"),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        issues,
+        [
+            (1, "4.5", 190),
+            (1, "4.4", 14),
+            (2, "4.6", 100),
+            (3, "4.7", 100),
+            (4, "4.9", 100),
+            (5, "4.10", 100),
+        ]
+    );
+}
+
+#[test]
+fn decodes_missing_and_local_level_types() {
+    let surface = |type_num| FixedSurface {
+        type_num,
+        value: None,
+    };
+    assert_eq!(surface(103).surface_type(), CodeValue::Known(103));
+    assert_eq!(surface(200).surface_type(), CodeValue::Known(200));
+    assert_eq!(surface(250).surface_type(), CodeValue::LocalUse(250));
+    assert_eq!(surface(255).surface_type(), CodeValue::Missing);
+}
@@ -0,0 +1,135 @@
+use hypergrib_grib_tables::{
+    code_table::CodeValue,
+    constituent::{AerosolType, ChemicalConstituent, Constituent},
+    dataset::DatasetIndexer,
+    idx::write_inventory,
+};
+
+fn section(section_num: u8, body: &[u8]) -> Vec<u8> {
+    let mut section = ((body.len() + 5) as u32).to_be_bytes().to_vec();
+    section.push(section_num);
+    section.extend_from_slice(body);
+    section
+}
+
+/// A GRIB2 message holding one 1 hour forecast of mass density (0.20.0) at 8 m above ground,
+/// using product definition template `template_num`. `constituent` is the template's octets 12
+/// onwards, up to the type of generating process. Only the sections which the inventory looks at
+/// are filled in.
+fn message(template_num: u16, constituent: &[u8]) -> Vec<u8> {
+    let mut identification = vec![0, 7, 0, 0, 33, 1, 1];
+    identification.extend_from_slice(&2024u16.to_be_bytes());
+    identification.extend_from_slice(&[1, 2, 12, 0, 0, 0, 1]);
+
+    let mut product_definition = vec![0, 0];
+    product_definition.extend_from_slice(&template_num.to_be_bytes());
+    product_definition.extend_from_slice(&[20, 0]);
+    product_definition.extend_from_slice(constituent);
+    product_definition.extend_from_slice(&[2, 0, 96, 0, 0, 0, 1]);
+    product_definition.extend_from_slice(&1u32.to_be_bytes());
+    product_definition.extend_from_slice(&[103, 0, 0, 0, 0, 8, 255, 255, 255, 255, 255, 255]);
+
+    let mut body = section(1, &identification);
+    body.extend(section(3, &[0; 67]));
+    body.extend(section(4, &product_definition));
+    body.extend(section(5, &[0; 16]));
+    body.extend(section(6, &[255]));
+    body.extend(section(7, &[]));
+
+    let mut message = b"GRIB\0\0\0\x02".to_vec();
+    message.extend_from_slice(&((body.len() + 20) as u64).to_be_bytes());
+    message.extend(body);
+    message.extend_from_slice(b"7777");
+    message
+}
+
+/// Template 4.48: an aerosol with particle sizes of type `size_type` in code table 4.91, whose
+/// first limit is 2.5e-6 m, and no wavelength. Type 0 is "smaller than first limit".
+fn aerosol(aerosol_type: u16, size_type: u8) -> Vec<u8> {
+    let mut constituent = aerosol_type.to_be_bytes().to_vec();
+    constituent.extend_from_slice(&[size_type, 7, 0, 0, 0, 25, 255, 255, 255, 255, 255]);
+    constituent.extend_from_slice(&[255; 11]);
+    message(48, &constituent)
+}
+
+fn inventory(grib: &[u8]) -> String {
+    let mut inventory = Vec::new();
+    write_inventory(grib, &mut inventory).unwrap();
+    String::from_utf8(inventory).unwrap()
+}
+
+#[test]
+fn decodes_constituents_which_arent_in_the_tables() {
+    let mut grib = message(40, &0u16.to_be_bytes());
+    let chemical_len = grib.len();
+    grib.extend(message(40, &10500u16.to_be_bytes()));
+    grib.extend(message(40, &u16::MAX.to_be_bytes()));
+    let aerosol_offset = grib.len();
+    grib.extend(aerosol(62001, 0));
+    grib.extend(aerosol(62999, 0));
+
+    let inventory = inventory(&grib);
+    let second_aerosol_offset = aerosol_offset + aerosol(0, 0).len();
+    let fcst = "MASSDEN:8 m above ground:1 hour fcst";
+    assert_eq!(
+        inventory,
+        format!(
+            "1:0:d=2024010212:{fcst}:chemical=Ozone:\n\
+             2:{}:d=2024010212:{fcst}:chemical=10500:\n\
+             3:{}:d=2024010212:{fcst}:chemical=65535:\n\
+             4:{aerosol_offset}:d=2024010212:{fcst}:aerosol=Dust Dry:aerosol_size <2.5e-06:\n\
+             5:{second_aerosol_offset}:d=2024010212:{fcst}:aerosol=62999:aerosol_size <2.5e-06:\n",
+            chemical_len,
+            2 * chemical_len,
+        )
+    );
+
+    // The numbers are read back from the `.idx` as the same constituents.
+    let mut indexer = DatasetIndexer::new(7, 0, 1, 33);
+    indexer.add_idx("aqm.grib2", &inventory, None).unwrap();
+    let index = indexer.finish();
+    assert!(index.undecoded().is_empty());
+    let constituents: Vec<_> = index
+        .products()
+        .iter()
+        .map(|(_, constituent)| *constituent)
+        .collect();
+    let chemical_at = |i: usize| match constituents[i] {
+        Some(Constituent::Chemical(chemical)) => chemical,
+        other => panic!("expected a chemical, got {other:?}"),
+    };
+    let aerosol_at = |i: usize| match constituents[i] {
+        Some(Constituent::Aerosol(aerosol)) => aerosol,
+        other => panic!("expected an aerosol, got {other:?}"),
+    };
+    assert_eq!(constituents.len(), 5);
+    assert_eq!(chemical_at(0), CodeValue::Known(ChemicalConstituent::Ozone));
+    assert_eq!(chemical_at(1), CodeValue::Reserved(10500));
+    assert_eq!(chemical_at(2), CodeValue::Missing);
+    let dust = aerosol_at(3);
+    assert_eq!(dust.aerosol_type, CodeValue::Known(AerosolType::DustDry));
+    assert_eq!(dust.size.unwrap().first_limit.unwrap().value(), 2.5e-6);
+    assert_eq!(dust.wavelength, None);
+    assert_eq!(aerosol_at(4).aerosol_type, CodeValue::Reserved(62999));
+}
+
+#[test]
+fn decodes_intervals_which_arent_in_the_table() {
+    let inventory = inventory(&aerosol(62001, 20));
+    assert_eq!(
+        inventory,
+        "1:0:d=2024010212:MASSDEN:8 m above ground:1 hour fcst:aerosol=Dust Dry:\
+         aerosol_size type=20 first=2.5e-06 second=missing:\n"
+    );
+
+    let mut indexer = DatasetIndexer::new(7, 0, 1, 33);
+    indexer.add_idx("aqm.grib2", &inventory, None).unwrap();
+    let index = indexer.finish();
+    let Some(Constituent::Aerosol(aerosol)) = index.products()[0].1 else {
+        panic!("expected an aerosol, got {:?}", index.products()[0]);
+    };
+    let size = aerosol.size.unwrap();
+    assert_eq!(size.interval_type, CodeValue::Reserved(20));
+    assert_eq!(size.first_limit.unwrap().value(), 2.5e-6);
+    assert_eq!(size.second_limit, None);
+}
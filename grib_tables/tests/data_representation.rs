@@ -0,0 +1,191 @@
+use hypergrib_grib_tables::reader::messages;
+
+/// The values of one field on a 13 x 11 grid.
+fn values(grib2: &[u8]) -> Vec<f32> {
+    let message = messages(grib2).next().unwrap().unwrap();
+    message.fields()[0].values().unwrap()
+}
+
+// See `fixtures/README.md` for where the real fixtures and their reference values come from.
+
+#[test]
+fn unpacks_real_jpeg2000() {
+    let grib2 = include_bytes!("fixtures/cmc_glb_tmp.grib2");
+    let message = messages(grib2).next().unwrap().unwrap();
+    let values = message.fields()[0].values().unwrap();
+    assert_eq!(values.len(), 1_126_500);
+
+    // Every 112650th value, as written by wgrib2 and by OpenJPEG. OpenJPEG's values were scaled
+    // in single precision, so differ in the last bits.
+    let samples = [
+        (236.27512, 236.27513),
+        (235.67513, 235.67513),
+        (243.25012, 243.25012),
+        (254.85013, 254.85013),
+        (268.70013, 268.70013),
+        (265.25012, 265.25012),
+        (266.40012, 266.40012),
+        (263.57513, 263.57513),
+        (269.9251, 269.92514),
+        (276.70013, 276.70013),
+    ];
+    for (i, (wgrib2, openjpeg)) in samples.into_iter().enumerate() {
+        let value = values[i * 112_650];
+        assert_eq!(value, wgrib2, "value {}", i * 112_650);
+        assert!((value - openjpeg).abs() < 1e-4, "value {}", i * 112_650);
+    }
+
+    // The sums of all the values (in double precision, in order) from wgrib2 and from OpenJPEG.
+    let sum: f64 = values.iter().map(|&value| f64::from(value)).sum();
+    assert_eq!(sum, 293_524_634.1157379);
+    assert!((sum - 293_524_638.10328674).abs() < 10.0);
+    let min = values.iter().copied().fold(f32::INFINITY, f32::min);
+    let max = values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    assert_eq!((min, max), (228.47513, 285.72513));
+}
+
+#[test]
+fn unpacks_real_run_length() {
+    let grib2 = include_bytes!("fixtures/jma_tornado_nowcast.grib2");
+    let message = messages(grib2).next().unwrap().unwrap();
+    assert_eq!(message.fields().len(), 7);
+    let values = message.fields()[3].values().unwrap();
+    assert_eq!(values.len(), 256 * 336);
+
+    // How often each level appears, and the first points of each, according to wgrib2.
+    let count = |level: f32| values.iter().filter(|&&value| value == level).count();
+    assert_eq!(values.iter().filter(|value| value.is_nan()).count(), 71_495);
+    assert_eq!((count(1.0), count(2.0), count(3.0)), (14_358, 92, 71));
+    let first = |level: f32| values.iter().position(|&value| value == level);
+    assert_eq!(
+        (first(1.0), first(2.0), first(3.0)),
+        (Some(6065), Some(35244), Some(36521))
+    );
+}
+
+#[test]
+fn unpacks_jpeg2000() {
+    // 12 bit integers, compressed losslessly with two levels of the 5/3 wavelet and 8 x 8
+    // code-blocks, so that every subband has partial code-blocks.
+    let values = values(include_bytes!("fixtures/jpeg2000.grib2"));
+    let expected: Vec<f32> = (0..11)
+        .flat_map(|j| (0..13).map(move |i| ((i * 37 + j * 91 + i * j) % 4096) as f32))
+        .collect();
+    assert_eq!(values, expected);
+}
+
+/// `fixtures/jpeg2000.grib2`, with its code stream replaced by `edit(code stream)`.
+fn edit_jpeg2000(edit: impl FnOnce(&mut Vec<u8>)) -> Vec<u8> {
+    let grib2 = include_bytes!("fixtures/jpeg2000.grib2");
+    // Section 7 starts at octet 173, and the code stream 5 octets later.
+    let (start, end) = (172, grib2.len() - 4);
+    let mut codestream = grib2[start + 5..end].to_vec();
+    edit(&mut codestream);
+    let mut edited = grib2[..start].to_vec();
+    edited.extend((codestream.len() as u32 + 5).to_be_bytes());
+    edited.push(7);
+    edited.extend(codestream);
+    edited.extend(b"7777");
+    let total_length = edited.len() as u64;
+    edited[8..16].copy_from_slice(&total_length.to_be_bytes());
+    edited
+}
+
+#[test]
+fn rejects_invalid_jpeg2000() {
+    let error = |grib2: Vec<u8>| {
+        let message = messages(&grib2).next().unwrap().unwrap();
+        message.fields()[0].values().unwrap_err().to_string()
+    };
+    assert_eq!(values(&edit_jpeg2000(|_| ())).len(), 143);
+
+    // Cut off in the middle of the tile's data.
+    let truncated = error(edit_jpeg2000(|codestream| codestream.truncate(80)));
+    assert!(truncated.contains("JPEG 2000"), "{truncated}");
+
+    // A single-tile 2^32 x 2^32 image, which we mustn't try to allocate.
+    let huge = error(edit_jpeg2000(|codestream| {
+        codestream[8..16].copy_from_slice(&[0xFF; 8]);
+        codestream[24..32].copy_from_slice(&[0xFF; 8]);
+    }));
+    assert!(huge.contains("number of values in section 5"), "{huge}");
+
+    // One tile per sample of that image, which is more tiles than there can be.
+    let too_many_tiles = error(edit_jpeg2000(|codestream| {
+        codestream[8..16].copy_from_slice(&[0xFF; 8]);
+        codestream[24..32].copy_from_slice(&[0, 0, 0, 1, 0, 0, 0, 1]);
+    }));
+    assert!(too_many_tiles.contains("tile grid"), "{too_many_tiles}");
+
+    // Garbage in the tile's data may decode to the wrong values, but mustn't panic.
+    let garbage = edit_jpeg2000(|codestream| {
+        for byte in &mut codestream[70..110] {
+            *byte = byte.wrapping_mul(97).wrapping_add(13);
+        }
+    });
+    let message = messages(&garbage).next().unwrap().unwrap();
+    if let Ok(values) = message.fields()[0].values() {
+        assert_eq!(values.len(), 143);
+    }
+}
+
+#[test]
+fn unpacks_run_length() {
+    // Runs of up to 60 values, which take two digits each with 4 bits per value.
+    let values = values(include_bytes!("fixtures/run_length.grib2"));
+    let runs = [
+        (f32::NAN, 20),
+        (0.5, 3),
+        (1.0, 1),
+        (2.5, 40),
+        (f32::NAN, 2),
+        (0.5, 60),
+        (1.0, 17),
+    ];
+    let expected: Vec<f32> = runs
+        .iter()
+        .flat_map(|&(value, n)| std::iter::repeat_n(value, n))
+        .collect();
+    assert_eq!(values.len(), expected.len());
+    for (value, expected) in values.iter().zip(&expected) {
+        assert!(value == expected || value.is_nan() && expected.is_nan());
+    }
+}
+
+#[test]
+fn rejects_run_lengths_which_overflow() {
+    // Level 1, followed by 40 digits of 11, with 4 bits per value. The run length is about
+    // 12^40, which doesn't fit in a u64.
+    let grib2 = include_bytes!("fixtures/run_length.grib2");
+    let start = 172;
+    let mut packed = vec![0x1F];
+    packed.extend([0xFF; 19]);
+    packed.push(0xF0);
+    let mut edited = grib2[..start].to_vec();
+    edited.extend((packed.len() as u32 + 5).to_be_bytes());
+    edited.push(7);
+    edited.extend(packed);
+    edited.extend(b"7777");
+    let total_length = edited.len() as u64;
+    edited[8..16].copy_from_slice(&total_length.to_be_bytes());
+
+    let message = messages(&edited).next().unwrap().unwrap();
+    let error = message.fields()[0].values().unwrap_err().to_string();
+    assert!(error.contains("more values than section 5"), "{error}");
+}
+
+#[test]
+fn rejects_more_values_than_grid_points() {
+    // The number of values in section 5 (octets 6-9) is about 4 billion. Run-length, simple and
+    // JPEG 2000 packing with no bits per value would all have allocated that many values.
+    let mut grib2 = include_bytes!("fixtures/run_length.grib2").to_vec();
+    assert_eq!(grib2[0x94..0x98], 143u32.to_be_bytes());
+    grib2[0x94..0x98].copy_from_slice(&u32::MAX.to_be_bytes());
+
+    let message = messages(&grib2).next().unwrap().unwrap();
+    let error = message.fields()[0].values().unwrap_err().to_string();
+    assert!(
+        error.contains("more values than the grid has points"),
+        "{error}"
+    );
+}
@@ -0,0 +1,185 @@
+use hypergrib_grib_tables::{
+    code_table::CodeValue,
+    constituent::{AerosolType, ChemicalConstituent, Constituent},
+    dataset::{DatasetIndexer, Location},
+    ensemble::{DerivedForecast, Ensemble, EnsembleForecastType, EnsembleMember, ProbabilityType},
+    level::Level,
+    step::Step,
+};
+
+const CONTROL: &str = "\
+1:0:d=2024010200:TMP:2 m above ground:6 hour fcst:ENS=low-res ctl:
+2:1000:d=2024010200:TMP:500 mb:6 hour fcst:ENS=low-res ctl:
+3.1:2500:d=2024010200:UGRD:10 m above ground:6 hour fcst:ENS=low-res ctl:
+3.2:2500:d=2024010200:VGRD:10 m above ground:6 hour fcst:ENS=low-res ctl:
+4:4000:d=2024010200:APCP:surface:0-6 hour acc fcst:ENS=low-res ctl:
+";
+
+// No 500 mb temperature, a duplicate 2 m temperature and a product we don't know.
+const MEMBER: &str = "\
+1:0:d=2024010200:TMP:2 m above ground:6 hour fcst:ENS=+1:
+2:1000:d=2024010200:TMP:2 m above ground:6 hour fcst:ENS=+1:
+3.1:2500:d=2024010200:UGRD:10 m above ground:6 hour fcst:ENS=+1:
+3.2:2500:d=2024010200:VGRD:10 m above ground:6 hour fcst:ENS=+1:
+4:4000:d=2024010200:APCP:surface:0-6 hour acc fcst:ENS=+1:
+5:5000:d=2024010200:NOTAVAR:surface:0-6 hour acc fcst:ENS=+1:
+";
+
+#[test]
+fn indexes_idx_files() {
+    let mut indexer = DatasetIndexer::new(7, 0, 1, 2);
+    indexer
+        .add_idx("control.grib2", CONTROL, Some(4500))
+        .unwrap();
+    indexer.add_idx("member.grib2", MEMBER, None).unwrap();
+    let index = indexer.finish();
+
+    assert_eq!(index.files().len(), 2);
+    assert_eq!(index.init_times().len(), 1);
+    assert_eq!(
+        index.ensembles(),
+        [
+            Some(Ensemble::Member(EnsembleMember {
+                forecast_type: CodeValue::Known(EnsembleForecastType::LowResolutionControl),
+                perturbation_num: 0,
+            })),
+            Some(Ensemble::Member(EnsembleMember {
+                forecast_type: CodeValue::Known(EnsembleForecastType::PositivelyPerturbed),
+                perturbation_num: 1,
+            })),
+        ]
+    );
+    let products: Vec<_> = index.products().iter().map(|(abbrev, _)| *abbrev).collect();
+    assert_eq!(products, ["TMP", "UGRD", "VGRD", "APCP"]);
+    assert_eq!(index.len(), 9);
+
+    let (coordinates, location) = index
+        .iter()
+        .find(|(c, l)| c.product == "VGRD" && l.file_index == 0)
+        .unwrap();
+    assert_eq!(
+        coordinates.level,
+        Level::from_idx_str("10 m above ground").unwrap()
+    );
+    assert_eq!(coordinates.step, Step::from_idx_str("6 hour fcst").unwrap());
+    assert_eq!(
+        *location,
+        Location {
+            file_index: 0,
+            start: 2500,
+            end: Some(4000),
+        }
+    );
+    let apcp = index
+        .iter()
+        .find(|(c, l)| c.product == "APCP" && l.file_index == 0)
+        .unwrap();
+    assert_eq!(apcp.1.end, Some(4500));
+
+    let missing = index.missing();
+    assert_eq!(missing.len(), 1);
+    assert_eq!(missing[0].ensemble, index.ensembles()[1]);
+    assert_eq!(missing[0].level, Level::from_idx_str("500 mb").unwrap());
+
+    assert_eq!(index.duplicates().len(), 1);
+    assert_eq!(index.duplicates()[0].ignored.start, 1000);
+    assert_eq!(index.undecoded().len(), 1);
+    assert_eq!(index.undecoded()[0].reason, "unknown product");
+}
+
+#[test]
+fn distinguishes_probabilities_from_deterministic_forecasts() {
+    let idx = "\
+1:0:d=2024010200:APCP:surface:0-6 hour acc fcst:
+2:100:d=2024010200:APCP:surface:0-6 hour acc fcst:ens mean:
+3:200:d=2024010200:APCP:surface:0-6 hour acc fcst:prob >0.254:prob fcst 255/255:
+4:300:d=2024010200:APCP:surface:0-6 hour acc fcst:prob >2.54:prob fcst 255/255:
+";
+    let mut indexer = DatasetIndexer::new(7, 0, 1, 2);
+    indexer.add_idx("probabilities.grib2", idx, None).unwrap();
+    let index = indexer.finish();
+
+    assert_eq!(index.len(), 4);
+    assert!(index.duplicates().is_empty());
+    let ensembles = index.ensembles();
+    assert_eq!(ensembles[0], None);
+    assert_eq!(
+        ensembles[1],
+        Some(Ensemble::Derived(CodeValue::Known(
+            DerivedForecast::UnweightedMean
+        )))
+    );
+    let Some(Ensemble::Probability(probability)) = ensembles[2] else {
+        panic!("expected a probability, got {:?}", ensembles[2]);
+    };
+    assert_eq!(
+        probability.probability_type,
+        CodeValue::Known(ProbabilityType::AboveUpperLimit)
+    );
+    assert_eq!(probability.upper_limit.unwrap().value(), 0.254);
+    assert_eq!(probability.total_forecast_probabilities, 255);
+}
+
+#[test]
+fn distinguishes_aerosols() {
+    let idx = "\
+1:0:d=2024010212:MASSDEN:8 m above ground:1 hour fcst:aerosol=Total Aerosol:aerosol_size <2.5e-06:
+2:100:d=2024010212:MASSDEN:8 m above ground:1 hour fcst:aerosol=Total Aerosol:aerosol_size <1e-05:
+3:200:d=2024010212:MASSDEN:8 m above ground:1 hour fcst:chemical=Ozone:
+4:300:d=2024010212:MASSDEN:8 m above ground:1 hour fcst:aerosol=Unobtainium:
+";
+    let mut indexer = DatasetIndexer::new(7, 0, 1, 2);
+    indexer.add_idx("aqm.grib2", idx, None).unwrap();
+    let index = indexer.finish();
+
+    assert_eq!(index.len(), 3);
+    assert!(index.duplicates().is_empty());
+    assert_eq!(
+        index.products()[2],
+        (
+            "MASSDEN",
+            Some(Constituent::Chemical(CodeValue::Known(
+                ChemicalConstituent::Ozone
+            )))
+        )
+    );
+    let Some(Constituent::Aerosol(aerosol)) = index.products()[0].1 else {
+        panic!("expected an aerosol, got {:?}", index.products()[0]);
+    };
+    assert_eq!(
+        aerosol.aerosol_type,
+        CodeValue::Known(AerosolType::TotalAerosol)
+    );
+    assert_eq!(aerosol.size.unwrap().first_limit.unwrap().value(), 2.5e-6);
+    assert_eq!(index.undecoded()[0].reason, "unknown aerosol type");
+}
+
+#[test]
+fn only_expects_products_at_the_kinds_of_ensemble_entry_they_appear_at() {
+    // The 2.54 mm probability of precipitation is missing from the second init time.
+    let init_00 = "\
+1:0:d=2024010200:TMP:2 m above ground:6 hour fcst:
+2:100:d=2024010200:TMP:2 m above ground:6 hour fcst:prob >300:prob fcst 255/255:
+3:200:d=2024010200:APCP:surface:0-6 hour acc fcst:prob >0.254:prob fcst 255/255:
+4:300:d=2024010200:APCP:surface:0-6 hour acc fcst:prob >2.54:prob fcst 255/255:
+";
+    let init_06 = "\
+1:0:d=2024010206:TMP:2 m above ground:6 hour fcst:
+2:100:d=2024010206:TMP:2 m above ground:6 hour fcst:prob >300:prob fcst 255/255:
+3:200:d=2024010206:APCP:surface:0-6 hour acc fcst:prob >0.254:prob fcst 255/255:
+";
+    let mut indexer = DatasetIndexer::new(7, 0, 1, 2);
+    indexer.add_idx("00.grib2", init_00, None).unwrap();
+    indexer.add_idx("06.grib2", init_06, None).unwrap();
+    let index = indexer.finish();
+    assert_eq!(index.ensembles().len(), 4);
+
+    let missing = index.missing();
+    assert_eq!(missing.len(), 1, "{missing:?}");
+    assert_eq!(missing[0].product, "APCP");
+    assert_eq!(missing[0].init_time, index.init_times()[1]);
+    assert_eq!(missing[0].ensemble, index.ensembles()[3]);
+
+    assert_eq!(index.expected_ensembles(&[0]), [0]);
+    assert_eq!(index.expected_ensembles(&[2]), [2]);
+}
@@ -0,0 +1,63 @@
+use hypergrib_grib_tables::{
+    center_and_table_versions::CenterAndTableVersions,
+    encoder::Encoder,
+    grid::{Grid, LatLonGrid},
+    level::Level,
+    message::ReferenceTime,
+    reader::messages,
+    step::{Step, TimeUnit},
+    AbbrevToProduct,
+};
+
+#[test]
+fn describes_every_section() {
+    let center_and_table_versions = CenterAndTableVersions::from_numbers(7, 0, 1, 33);
+    let encoder = Encoder {
+        product: center_and_table_versions.abbrev_to_product("TMP").unwrap(),
+        center_and_table_versions: &center_and_table_versions,
+        reference_time: ReferenceTime {
+            year: 2024,
+            month: 1,
+            day: 2,
+            hour: 6,
+            minute: 0,
+            second: 0,
+        },
+        type_of_generating_process: 2,
+        grid: Grid::LatLon(LatLonGrid {
+            shape_of_earth: 6,
+            ni: 3,
+            nj: 2,
+            first_lat: 50.0,
+            first_lon: 0.0,
+            resolution_and_component_flags: 0x30,
+            last_lat: 49.5,
+            last_lon: 1.0,
+            di: 0.5,
+            dj: 0.5,
+            scanning_mode: 0,
+        }),
+        level: Level::from_idx_str("2 m above ground").unwrap(),
+        step: Step::new(TimeUnit::Hour, 6).unwrap(),
+        bits_per_value: 12,
+        decimal_scale_factor: 1,
+    };
+    let values = [270.0, 271.0, f32::NAN, 273.0, 274.0, 275.5];
+    let grib2 = encoder.encode(&values).unwrap();
+    let message = messages(&grib2).next().unwrap().unwrap();
+    let expected = "\
+GRIB2 message at byte 0, 188 bytes, edition 2
+  discipline 0 (meteorological)
+  center 7 (NCEP), subcenter 0, master table version 33, local table version 1
+  reference time 2024-01-02 06:00:00 (start of forecast), operational products, forecast products
+  field 1 of 1
+    grid: lat/lon (template 3.0), 3 x 2 points, lat 50 to 49.5 by 0.5, lon 0 to 1 by 0.5, shape of earth 6, scanning mode 0x00
+    product: TMP, Temperature [K] (template 4.0, parameter 0.0.0)
+    level: 2 m above ground, step: 6 hour fcst
+    generating process: Forecast, model missing
+    data representation: template 5.0, simple packing, 5 values, 12 bits per value, R = 2700, E = -6, D = 1
+    bitmap: indicator 0, 1 of 6 points missing
+    values: min 270, max 275.5, mean 272.7
+";
+    assert_eq!(message.describe().to_string(), expected);
+}
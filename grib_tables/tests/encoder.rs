@@ -0,0 +1,135 @@
+use hypergrib_grib_tables::{
+    center_and_table_versions::CenterAndTableVersions,
+    encoder::Encoder,
+    grid::{Grid, LambertConformalGrid, LatLonGrid},
+    idx::write_inventory,
+    level::Level,
+    message::ReferenceTime,
+    reader::messages,
+    step::{Step, TimeUnit},
+    AbbrevToProduct,
+};
+
+const REFERENCE_TIME: ReferenceTime = ReferenceTime {
+    year: 2024,
+    month: 1,
+    day: 2,
+    hour: 6,
+    minute: 0,
+    second: 0,
+};
+
+fn latlon_grid() -> Grid {
+    Grid::LatLon(LatLonGrid {
+        shape_of_earth: 6,
+        ni: 4,
+        nj: 3,
+        first_lat: 50.0,
+        first_lon: 358.5,
+        resolution_and_component_flags: 0x30,
+        last_lat: 49.5,
+        last_lon: 1.25,
+        di: 0.25,
+        dj: 0.25,
+        scanning_mode: 0,
+    })
+}
+
+#[test]
+fn round_trips_through_decoder() {
+    let center_and_table_versions = CenterAndTableVersions::from_numbers(7, 0, 1, 33);
+    let encoder = Encoder {
+        product: center_and_table_versions.abbrev_to_product("TMP").unwrap(),
+        center_and_table_versions: &center_and_table_versions,
+        reference_time: REFERENCE_TIME,
+        type_of_generating_process: 3,
+        grid: latlon_grid(),
+        level: Level::from_idx_str("2 m above ground").unwrap(),
+        step: Step::new(TimeUnit::Hour, 6).unwrap(),
+        bits_per_value: 12,
+        decimal_scale_factor: 1,
+    };
+    let values = [
+        273.15,
+        274.0,
+        f32::NAN,
+        280.25,
+        265.5,
+        f32::NAN,
+        270.0,
+        271.1,
+        290.0,
+        288.8,
+        260.0,
+        259.9,
+    ];
+    let grib2 = encoder.encode(&values).unwrap();
+
+    let mut inventory = Vec::new();
+    write_inventory(&grib2, &mut inventory).unwrap();
+    assert_eq!(
+        String::from_utf8(inventory).unwrap(),
+        "1:0:d=2024010206:TMP:2 m above ground:6 hour fcst:\n"
+    );
+
+    let message = messages(&grib2).next().unwrap().unwrap();
+    let field = &message.fields()[0];
+    assert_eq!(field.grid().unwrap(), latlon_grid());
+    let decoded = field.values().unwrap();
+    assert_eq!(decoded.len(), values.len());
+    for (value, decoded) in values.iter().zip(decoded) {
+        if value.is_nan() {
+            assert!(decoded.is_nan());
+        } else {
+            // Rounded to 0.1 K, and then packed into 12 bits.
+            assert!(
+                (value - decoded).abs() <= 0.05 + 0.01,
+                "{value} != {decoded}"
+            );
+        }
+    }
+}
+
+#[test]
+fn round_trips_lambert_grids_and_constant_fields() {
+    let grid = Grid::LambertConformal(LambertConformalGrid {
+        shape_of_earth: 6,
+        nx: 3,
+        ny: 2,
+        first_lat: 21.138123,
+        first_lon: 237.280472,
+        resolution_and_component_flags: 0x08,
+        lad: 38.5,
+        lov: 262.5,
+        dx: 3000.0,
+        dy: 3000.0,
+        projection_centre: 0,
+        scanning_mode: 0x40,
+        latin1: 38.5,
+        latin2: 38.5,
+        south_pole_lat: -90.0,
+        south_pole_lon: 0.0,
+    });
+    let center_and_table_versions = CenterAndTableVersions::from_numbers(7, 0, 1, 33);
+    let encoder = Encoder {
+        product: center_and_table_versions
+            .abbrev_to_product("PRMSL")
+            .unwrap(),
+        center_and_table_versions: &center_and_table_versions,
+        reference_time: REFERENCE_TIME,
+        type_of_generating_process: 0,
+        grid,
+        level: Level::from_idx_str("mean sea level").unwrap(),
+        step: Step::new(TimeUnit::Hour, 0).unwrap(),
+        bits_per_value: 16,
+        decimal_scale_factor: 0,
+    };
+    let grib2 = encoder.encode(&[101325.0; 6]).unwrap();
+
+    let message = messages(&grib2).next().unwrap().unwrap();
+    let field = &message.fields()[0];
+    assert_eq!(field.grid().unwrap(), grid);
+    assert_eq!(field.values().unwrap(), [101325.0; 6]);
+
+    assert!(encoder.encode(&[101325.0; 5]).is_err());
+}
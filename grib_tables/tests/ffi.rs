@@ -0,0 +1,57 @@
+#![cfg(feature = "ffi")]
+
+use std::{
+    ffi::{CStr, CString},
+    ptr,
+};
+
+use hypergrib_grib_tables::ffi::*;
+
+#[test]
+fn test_lookups_round_trip() {
+    let abbrev = CString::new("APCP").unwrap();
+    let mut numbers = GribTablesProductNumbers {
+        discipline: 255,
+        category: 255,
+        parameter: 255,
+    };
+    assert!(unsafe { grib_tables_abbrev_to_numbers(abbrev.as_ptr(), 7, 0, 1, 33, &mut numbers) });
+    assert_eq!(
+        (numbers.discipline, numbers.category, numbers.parameter),
+        (0, 1, 8)
+    );
+
+    let product = grib_tables_product(0, 1, 8, 7, 0, 1, 33);
+    assert!(!product.is_null());
+    let text = |s| unsafe { CStr::from_ptr(s) }.to_str().unwrap();
+    unsafe {
+        assert_eq!(text((*product).abbrev), "APCP");
+        assert_eq!(text((*product).name), "Total precipitation");
+        assert_eq!(text((*product).unit), "kg m-2");
+        grib_tables_product_free(product);
+    }
+    assert!(grib_tables_product(0, 1, 191, 98, 0, 0, 33).is_null());
+}
+
+#[test]
+fn test_idx_parse() {
+    let idx = "1:0:d=2024010100:TMP:2 m above ground:anl:\n\
+               2.2:5000:d=2024010106:UGRD:10 m above ground:6 hour fcst:ENS=+1:\n";
+    let mut error = ptr::null_mut();
+    let records = unsafe { grib_tables_idx_parse(idx.as_ptr().cast(), idx.len(), &mut error) };
+    assert!(error.is_null());
+    unsafe {
+        assert_eq!((*records).len, 2);
+        let record = &*(*records).records.add(1);
+        assert_eq!((record.message_num, record.field_num), (2, 2));
+        assert_eq!((record.offset, record.day, record.hour), (5000, 1, 6));
+        assert_eq!(CStr::from_ptr(record.step).to_str(), Ok("6 hour fcst"));
+        assert_eq!(CStr::from_ptr(record.extra).to_str(), Ok("ENS=+1"));
+        grib_tables_idx_records_free(records);
+    }
+
+    let records = unsafe { grib_tables_idx_parse(c"bad".as_ptr(), 3, &mut error) };
+    assert!(records.is_null());
+    assert!(!error.is_null());
+    unsafe { grib_tables_string_free(error) };
+}
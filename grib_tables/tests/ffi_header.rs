@@ -0,0 +1,21 @@
+#![cfg(feature = "ffi")]
+
+use std::path::Path;
+
+#[test]
+fn header_is_up_to_date() {
+    let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml")).unwrap();
+    let mut generated = Vec::new();
+    cbindgen::Builder::new()
+        .with_crate(crate_dir)
+        .with_config(config)
+        .generate()
+        .unwrap()
+        .write(&mut generated);
+    let header = std::fs::read_to_string(crate_dir.join("include/grib_tables.h")).unwrap();
+    assert!(
+        String::from_utf8(generated).unwrap() == header,
+        "include/grib_tables.h is out of date: regenerate it with cbindgen"
+    );
+}
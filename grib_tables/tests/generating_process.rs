@@ -0,0 +1,102 @@
+use hypergrib_grib_tables::{
+    center_and_table_versions::CenterAndTableVersions,
+    code_table::CodeValue,
+    encoder::Encoder,
+    generating_process::{Model, NcepModel, TypeOfGeneratingProcess},
+    grid::{Grid, LatLonGrid},
+    level::Level,
+    message::ReferenceTime,
+    reader::messages,
+    step::{Step, TimeUnit},
+    AbbrevToProduct,
+};
+
+/// A 6 hour forecast from `center_num`, made by model `process_num`.
+fn message(center_num: u16, type_of_generating_process: u8, process_num: u8) -> Vec<u8> {
+    let center_and_table_versions = CenterAndTableVersions::from_numbers(7, 0, 1, 33);
+    let encoder = Encoder {
+        product: center_and_table_versions.abbrev_to_product("TMP").unwrap(),
+        center_and_table_versions: &center_and_table_versions,
+        reference_time: ReferenceTime {
+            year: 2024,
+            month: 1,
+            day: 2,
+            hour: 6,
+            minute: 0,
+            second: 0,
+        },
+        type_of_generating_process,
+        grid: Grid::LatLon(LatLonGrid {
+            shape_of_earth: 6,
+            ni: 2,
+            nj: 2,
+            first_lat: 1.0,
+            first_lon: 0.0,
+            resolution_and_component_flags: 0x30,
+            last_lat: 0.0,
+            last_lon: 1.0,
+            di: 1.0,
+            dj: 1.0,
+            scanning_mode: 0,
+        }),
+        level: Level::from_idx_str("2 m above ground").unwrap(),
+        step: Step::new(TimeUnit::Hour, 6).unwrap(),
+        bits_per_value: 12,
+        decimal_scale_factor: 0,
+    };
+    let mut grib2 = encoder.encode(&[270.0, 271.0, 272.0, 273.0]).unwrap();
+
+    // The encoder leaves the model missing, and always writes NCEP as the center. Patch octets
+    // 6 to 7 of section 1 and octet 14 of section 4.
+    grib2[16 + 5..16 + 7].copy_from_slice(&center_num.to_be_bytes());
+    let mut pos = 16;
+    while grib2[pos + 4] != 4 {
+        pos += u32::from_be_bytes(grib2[pos..pos + 4].try_into().unwrap()) as usize;
+    }
+    grib2[pos + 13] = process_num;
+    grib2
+}
+
+#[test]
+fn decodes_the_type_of_process_and_the_model() {
+    let decode = |center_num, type_of_generating_process, process_num| {
+        let grib2 = message(center_num, type_of_generating_process, process_num);
+        let message = messages(&grib2).next().unwrap().unwrap();
+        let center_and_table_versions = message.center_and_table_versions();
+        message.fields()[0]
+            .generating_process(&center_and_table_versions)
+            .unwrap()
+    };
+
+    let gfs = decode(7, 2, 96);
+    assert_eq!(
+        gfs.type_of_generating_process,
+        CodeValue::Known(TypeOfGeneratingProcess::Forecast)
+    );
+    assert_eq!(gfs.model, Model::Ncep(NcepModel::Gfs));
+    assert_eq!(NcepModel::Gfs.name(), "GFS");
+
+    let hrrr = decode(7, 0, 83);
+    assert_eq!(
+        hrrr.type_of_generating_process,
+        CodeValue::Known(TypeOfGeneratingProcess::Analysis)
+    );
+    assert_eq!(hrrr.model, Model::Ncep(NcepModel::Hrrr));
+
+    let ncep_local = decode(7, 193, 255);
+    assert_eq!(
+        ncep_local.type_of_generating_process,
+        CodeValue::Known(TypeOfGeneratingProcess::NcepProbabilityMatchedMean)
+    );
+    assert_eq!(ncep_local.model, Model::Missing);
+    // NCEP's local types and models mean nothing for ECMWF.
+    let ecmwf = decode(98, 193, 96);
+    assert_eq!(ecmwf.type_of_generating_process, CodeValue::LocalUse(193));
+    assert_eq!(
+        ecmwf.model,
+        Model::Unknown {
+            center_num: 98,
+            process_num: 96
+        }
+    );
+}
@@ -0,0 +1,76 @@
+use hypergrib_grib_tables::{grib1::Grib1ParameterTable, Product};
+
+#[test]
+fn grib1_parameters_decode_into_the_same_products_as_grib2() {
+    let abbrev = |center_num, table_version, parameter_num| {
+        Product::from_grib1_parameter_numbers(center_num, table_version, parameter_num)
+            .map(|product| product.abbrev())
+    };
+    assert_eq!(abbrev(7, 2, 11), Some("TMP"));
+    assert_eq!(
+        abbrev(98, 128, 11),
+        None,
+        "ECMWF's local tables aren't supported"
+    );
+    assert_eq!(abbrev(98, 3, 61), Some("APCP"));
+    assert_eq!(abbrev(98, 3, 140), None, "only NCEP has local parameters");
+    assert_eq!(abbrev(7, 2, 140), Some("CRAIN"));
+    assert_eq!(abbrev(7, 2, 154), Some("O3MR"));
+
+    // The GRIB1 and GRIB2 abbreviations can differ.
+    let frime = Grib1ParameterTable::from_center_and_table_version(7, 129)
+        .and_then(|table| table.parameter(133))
+        .unwrap();
+    assert_eq!(frime.abbrev(), "FRIME");
+    assert_eq!(abbrev(7, 129, 133), Some("RIME"));
+
+    // Table 131 shares parameters 1-127 with the WMO table, but mustn't be read as table 2.
+    assert_eq!(abbrev(7, 131, 11), Some("TMP"));
+    assert_eq!(abbrev(7, 131, 216), Some("TTRAD"));
+    assert_eq!(abbrev(7, 2, 147), Some("U-GWD"));
+    let baret = Grib1ParameterTable::from_center_and_table_version(7, 131)
+        .and_then(|table| table.parameter(147))
+        .unwrap();
+    assert_eq!(baret.abbrev(), "BARET");
+    assert_eq!(baret.grib2_numbers(), (2, 3, 200));
+}
+
+#[test]
+fn every_meteorological_grib1_parameter_decodes() {
+    // The GRIB2 categories of discipline 0 which this crate decodes.
+    const DECODED_CATEGORIES: [u8; 6] = [0, 1, 2, 3, 14, 20];
+    // Their GRIB2 equivalents aren't transcribed yet. Most are NCEP local parameters which only
+    // appear in table 131.
+    const NOT_IN_GRIB2_TABLES: [&str; 21] = [
+        "VPTMP", "CLWMR", "PEVPR", "PRESN", "MCONV", "ARAIN", "SNOWT", "LMH", "LMV", "ICMR",
+        "SNOAG", "CPOFP", "SBSNO", "APCPN", "ACPCPN", "HGTN", "PEVAP", "SNOWC", "SNOT", "CD",
+        "FRICV",
+    ];
+    for (center_num, table_version) in [(7, 2), (7, 129), (7, 130), (7, 131), (98, 3)] {
+        let table =
+            Grib1ParameterTable::from_center_and_table_version(center_num, table_version).unwrap();
+        for parameter_num in 0..=u8::MAX {
+            let Some(parameter) = table.parameter(parameter_num) else {
+                continue;
+            };
+            let product =
+                Product::from_grib1_parameter_numbers(center_num, table_version, parameter_num);
+            let (discipline, category, _) = parameter.grib2_numbers();
+            if discipline == 0
+                && DECODED_CATEGORIES.contains(&category)
+                && !NOT_IN_GRIB2_TABLES.contains(&parameter.abbrev())
+            {
+                let product =
+                    product.unwrap_or_else(|| panic!("{} doesn't decode", parameter.abbrev()));
+                assert_eq!(
+                    (
+                        product.discipline_num(),
+                        product.category_num(),
+                        product.parameter_num()
+                    ),
+                    parameter.grib2_numbers()
+                );
+            }
+        }
+    }
+}
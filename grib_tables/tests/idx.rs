@@ -0,0 +1,73 @@
+use hypergrib_grib_tables::{
+    center_and_table_versions::CenterAndTableVersions,
+    encoder::Encoder,
+    grid::{Grid, LatLonGrid},
+    idx::write_inventory,
+    level::Level,
+    message::ReferenceTime,
+    step::{Step, TimeUnit},
+    AbbrevToProduct,
+};
+
+fn encode(abbrev: &str) -> Vec<u8> {
+    let center_and_table_versions = CenterAndTableVersions::from_numbers(7, 0, 1, 33);
+    Encoder {
+        product: center_and_table_versions.abbrev_to_product(abbrev).unwrap(),
+        center_and_table_versions: &center_and_table_versions,
+        reference_time: ReferenceTime {
+            year: 2024,
+            month: 1,
+            day: 2,
+            hour: 6,
+            minute: 0,
+            second: 0,
+        },
+        type_of_generating_process: 2,
+        grid: Grid::LatLon(LatLonGrid {
+            shape_of_earth: 6,
+            ni: 2,
+            nj: 2,
+            first_lat: 50.0,
+            first_lon: 0.0,
+            resolution_and_component_flags: 0x30,
+            last_lat: 49.0,
+            last_lon: 1.0,
+            di: 1.0,
+            dj: 1.0,
+            scanning_mode: 0,
+        }),
+        level: Level::from_idx_str("2 m above ground").unwrap(),
+        step: Step::new(TimeUnit::Hour, 6).unwrap(),
+        bits_per_value: 12,
+        decimal_scale_factor: 1,
+    }
+    .encode(&[273.15, 274.0, 275.5, 276.25])
+    .unwrap()
+}
+
+#[test]
+fn writes_a_line_for_fields_which_cant_be_decoded() {
+    let mut grib = encode("TMP");
+    let second = grib.len();
+    grib.extend(encode("RH"));
+    grib.extend(encode("TMP"));
+
+    // Make the second message use product definition template 4.31 (satellite products), which
+    // we don't decode, and give it a parameter which isn't in the tables.
+    let section_4 = second + 16 + 21 + 72;
+    assert_eq!(grib[section_4 + 4], 4);
+    grib[section_4 + 7..section_4 + 9].copy_from_slice(&31u16.to_be_bytes());
+    grib[section_4 + 10] = 150;
+
+    let mut inventory = Vec::new();
+    write_inventory(&grib, &mut inventory).unwrap();
+    let third = 2 * second;
+    assert_eq!(
+        String::from_utf8(inventory).unwrap(),
+        format!(
+            "1:0:d=2024010206:TMP:2 m above ground:6 hour fcst:\n\
+             2:{second}:d=2024010206:var discipline=0 center=7 local_table=1 parmcat=1 parm=150:::\n\
+             3:{third}:d=2024010206:TMP:2 m above ground:6 hour fcst:\n"
+        )
+    );
+}
@@ -0,0 +1,107 @@
+use hypergrib_grib_tables::{
+    inventory::{ByteRange, Inventory, Query},
+    level::Level,
+};
+
+const IDX: &str = "\
+1:0:d=2024010100:PRMSL:mean sea level:anl:
+2:1000:d=2024010100:TMP:2 m above ground:anl:
+3:2000:d=2024010100:UGRD:10 m above ground:anl:
+4:3000:d=2024010100:VGRD:10 m above ground:anl:
+5:4000:d=2024010100:TMP:2 m above ground:6 hour fcst:
+6.1:5000:d=2024010100:UGRD:10 m above ground:6 hour fcst:
+6.2:5000:d=2024010100:VGRD:10 m above ground:6 hour fcst:
+7:6000:d=2024010100:APCP:surface:0-6 hour acc fcst:
+8:7000:d=2024010100:TMP:2 m above ground:54 hour fcst:
+9:8000:d=2024010100:UGRD:10 m above ground:54 hour fcst:
+";
+
+fn query(abbrevs: &[&str], level: &str) -> Query {
+    Query {
+        abbrevs: abbrevs.iter().map(|abbrev| abbrev.to_string()).collect(),
+        levels: vec![Level::from_idx_str(level).unwrap()],
+        forecast_hours: Some(0..=48),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn selects_records_and_merges_byte_ranges() {
+    let inventory = Inventory::parse(IDX, Some(9000)).unwrap();
+    assert_eq!(inventory.len(), 10);
+
+    let selection = inventory.select(&[
+        query(&["TMP"], "2 m above ground"),
+        query(&["UGRD", "VGRD"], "10 m above ground"),
+    ]);
+    assert_eq!(
+        selection.lines,
+        [
+            "2:1000:d=2024010100:TMP:2 m above ground:anl:",
+            "3:2000:d=2024010100:UGRD:10 m above ground:anl:",
+            "4:3000:d=2024010100:VGRD:10 m above ground:anl:",
+            "5:4000:d=2024010100:TMP:2 m above ground:6 hour fcst:",
+            "6.1:5000:d=2024010100:UGRD:10 m above ground:6 hour fcst:",
+            "6.2:5000:d=2024010100:VGRD:10 m above ground:6 hour fcst:",
+        ]
+    );
+    assert_eq!(
+        selection.byte_ranges,
+        [ByteRange {
+            start: 1000,
+            end: Some(6000)
+        }]
+    );
+    assert_eq!(selection.byte_ranges[0].http_range(), "bytes=1000-5999");
+
+    // Accumulations match on the end of their time range. Without the file's length, the last
+    // message runs to the end of the file.
+    let inventory = Inventory::parse(IDX, None).unwrap();
+    let selection = inventory.select(&[
+        Query {
+            forecast_hours: Some(6..=6),
+            abbrevs: vec!["APCP".to_string()],
+            ..Default::default()
+        },
+        Query {
+            abbrevs: vec!["PRMSL".to_string(), "UGRD".to_string()],
+            forecast_hours: Some(54..=54),
+            ..Default::default()
+        },
+    ]);
+    assert_eq!(
+        selection.byte_ranges,
+        [
+            ByteRange {
+                start: 6000,
+                end: Some(7000)
+            },
+            ByteRange {
+                start: 8000,
+                end: None
+            },
+        ]
+    );
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn selects_records_with_a_regex() {
+    let inventory = Inventory::parse(IDX, Some(9000)).unwrap();
+    let regex = regex::Regex::new(":(UGRD|VGRD):10 m above ground:(anl|6 hour fcst)").unwrap();
+    let selection = inventory.select_regex(&regex);
+    assert_eq!(selection.lines.len(), 4);
+    assert_eq!(
+        selection.byte_ranges,
+        [
+            ByteRange {
+                start: 2000,
+                end: Some(4000)
+            },
+            ByteRange {
+                start: 5000,
+                end: Some(6000)
+            },
+        ]
+    );
+}
@@ -0,0 +1,153 @@
+#![cfg(feature = "kerchunk")]
+
+use hypergrib_grib_tables::{
+    dataset::DatasetIndexer,
+    error::Error,
+    grid::{Grid, LatLonGrid},
+    kerchunk::write_references,
+};
+use serde_json::{json, Value};
+
+const INIT_00: &str = "\
+1:0:d=2024010100:TMP:2 m above ground:anl:
+2:1000:d=2024010100:TMP:500 mb:anl:
+3:2000:d=2024010100:TMP:850 mb:anl:
+4:3000:d=2024010100:TMP:2 m above ground:6 hour fcst:
+";
+
+const INIT_06: &str = "\
+1:0:d=2024010106:TMP:2 m above ground:anl:
+2:1500:d=2024010106:TMP:850 mb:anl:
+";
+
+fn grid() -> Grid {
+    Grid::LatLon(LatLonGrid {
+        shape_of_earth: 6,
+        ni: 4,
+        nj: 3,
+        first_lat: 50.0,
+        first_lon: 0.0,
+        resolution_and_component_flags: 0x30,
+        last_lat: 48.0,
+        last_lon: 3.0,
+        di: 1.0,
+        dj: 1.0,
+        scanning_mode: 0,
+    })
+}
+
+#[test]
+fn writes_one_array_per_product_and_level_type() {
+    let mut indexer = DatasetIndexer::new(7, 0, 1, 33);
+    indexer
+        .add_idx("s3://bucket/00.grib2", INIT_00, Some(4000))
+        .unwrap();
+    indexer
+        .add_idx("s3://bucket/06.grib2", INIT_06, Some(2500))
+        .unwrap();
+    let index = indexer.finish();
+
+    let mut json = Vec::new();
+    write_references(&index, &grid(), &mut json).unwrap();
+    let references: Value = serde_json::from_slice(&json).unwrap();
+    assert_eq!(references["version"], 1);
+    let refs = &references["refs"];
+    let metadata =
+        |key: &str| -> Value { serde_json::from_str(refs[key].as_str().unwrap()).unwrap() };
+
+    let zarray = metadata("TMP_isobaric/.zarray");
+    assert_eq!(zarray["shape"], json!([2, 2, 1, 2, 3, 4]));
+    assert_eq!(zarray["chunks"], json!([1, 1, 1, 1, 3, 4]));
+    let zattrs = metadata("TMP_isobaric/.zattrs");
+    assert_eq!(
+        zattrs["_ARRAY_DIMENSIONS"],
+        json!(["init_time", "step", "member", "isobaric", "y", "x"])
+    );
+    assert_eq!(zattrs["units"], "K");
+    assert_eq!(zattrs["GRIB_parameter"], 0);
+    assert_eq!(
+        metadata("TMP_heightAboveGround/.zarray")["shape"],
+        json!([2, 2, 1, 1, 3, 4])
+    );
+
+    // 850 mb is the second level, and has no 6 hour forecast.
+    assert_eq!(
+        refs["TMP_isobaric/0.0.0.1.0.0"],
+        json!(["s3://bucket/00.grib2", 2000, 1000])
+    );
+    assert_eq!(
+        refs["TMP_isobaric/1.0.0.1.0.0"],
+        json!(["s3://bucket/06.grib2", 1500, 1000])
+    );
+    assert!(refs.get("TMP_isobaric/1.0.0.0.0.0").is_none());
+    assert_eq!(
+        refs["TMP_heightAboveGround/0.1.0.0.0.0"],
+        json!(["s3://bucket/00.grib2", 3000, 1000])
+    );
+
+    // Seconds since 1970 as little-endian i64s, and pressures in Pa as f64s.
+    assert_eq!(metadata("init_time/.zarray")["dtype"], "<i8");
+    assert_eq!(refs["init_time/0"], "base64:gACSZQAAAADgVJJlAAAAAA==");
+    assert_eq!(refs["isobaric/0"], "base64:AAAAAABq6EAAAAAAgMD0QA==");
+}
+
+#[test]
+fn gives_each_array_only_the_ensemble_entries_it_appears_with() {
+    let idx = "\
+1:0:d=2024010200:TMP:2 m above ground:6 hour fcst:
+2:100:d=2024010200:APCP:surface:0-6 hour acc fcst:prob >0.254:prob fcst 255/255:
+3:200:d=2024010200:APCP:surface:0-6 hour acc fcst:prob >2.54:prob fcst 255/255:
+4:300:d=2024010200:UGRD:10 m above ground:6 hour fcst:
+";
+    let mut indexer = DatasetIndexer::new(7, 0, 1, 33);
+    indexer
+        .add_idx("s3://bucket/00.grib2", idx, Some(400))
+        .unwrap();
+    let index = indexer.finish();
+
+    let mut json = Vec::new();
+    write_references(&index, &grid(), &mut json).unwrap();
+    let references: Value = serde_json::from_slice(&json).unwrap();
+    let refs = &references["refs"];
+    let metadata =
+        |key: &str| -> Value { serde_json::from_str(refs[key].as_str().unwrap()).unwrap() };
+
+    assert_eq!(
+        metadata("TMP_heightAboveGround/.zarray")["shape"],
+        json!([1, 2, 1, 1, 3, 4])
+    );
+    assert_eq!(
+        metadata("UGRD_heightAboveGround/.zattrs")["_ARRAY_DIMENSIONS"][2],
+        "member"
+    );
+    assert_eq!(
+        metadata("APCP_surface/.zarray")["shape"],
+        json!([1, 2, 2, 1, 3, 4])
+    );
+    assert_eq!(
+        metadata("APCP_surface/.zattrs")["_ARRAY_DIMENSIONS"][2],
+        "member_2"
+    );
+    assert_eq!(metadata("member_2/.zarray")["shape"], json!([2]));
+    assert_eq!(
+        refs["APCP_surface/0.1.1.0.0.0"],
+        json!(["s3://bucket/00.grib2", 200, 100])
+    );
+}
+
+#[test]
+fn refuses_fields_which_share_a_message() {
+    // wgrib2 numbers the fields of a message `3.1`, `3.2` etc., all at the message's offset.
+    let idx = "\
+1:0:d=2024010200:TMP:2 m above ground:6 hour fcst:
+2.1:100:d=2024010200:UGRD:10 m above ground:6 hour fcst:
+2.2:100:d=2024010200:VGRD:10 m above ground:6 hour fcst:
+";
+    let mut indexer = DatasetIndexer::new(7, 0, 1, 33);
+    indexer
+        .add_idx("s3://bucket/00.grib2", idx, Some(300))
+        .unwrap();
+    let index = indexer.finish();
+    let err = write_references(&index, &grid(), &mut Vec::new()).unwrap_err();
+    assert!(matches!(err, Error::CannotExport { .. }), "{err}");
+}
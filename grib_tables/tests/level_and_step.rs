@@ -0,0 +1,129 @@
+use hypergrib_grib_tables::{
+    level::{FixedSurface, Level, ScaledValue},
+    step::{StatisticalProcess, Step, TimeUnit},
+};
+use proptest::prelude::*;
+
+/// Types of surface which `wgrib2` prints with a value, e.g. "2 m above ground".
+const VALUED_TYPES: [u8; 11] = [20, 100, 102, 103, 104, 105, 106, 107, 108, 111, 160];
+
+/// Types of surface which `wgrib2` prints by name, e.g. "surface".
+const NAMED_TYPES: [u8; 12] = [1, 2, 3, 7, 8, 10, 101, 200, 204, 214, 220, 244];
+
+/// Values have at most 5 significant figures, because `wgrib2` (like C's `%g`) prints 6.
+fn value() -> impl Strategy<Value = ScaledValue> {
+    (0i8..=4, -99_999i32..=99_999)
+        .prop_map(|(scale_factor, scaled_value)| ScaledValue::new(scale_factor, scaled_value))
+}
+
+/// A surface of a known or unknown type.
+fn fixed_surface() -> impl Strategy<Value = FixedSurface> {
+    let surface = |type_num, value| FixedSurface { type_num, value };
+    prop_oneof![
+        (
+            prop::sample::select(&VALUED_TYPES[..]),
+            value().prop_map(Some)
+        )
+            .prop_map(move |(type_num, value)| surface(type_num, value)),
+        prop::sample::select(&NAMED_TYPES[..]).prop_map(move |type_num| surface(type_num, None)),
+        // Types which aren't in code table 4.5 (yet).
+        (
+            prop::sample::select(&[50u8, 150, 190][..]),
+            prop::option::of(value())
+        )
+            .prop_map(move |(type_num, value)| surface(type_num, value)),
+    ]
+}
+
+fn level() -> impl Strategy<Value = Level> {
+    prop_oneof![
+        fixed_surface().prop_map(|first| Level {
+            first,
+            second: None
+        }),
+        (fixed_surface(), fixed_surface()).prop_map(|(first, second)| Level {
+            first,
+            second: Some(second),
+        }),
+        // Layers between two surfaces of the same type are printed differently.
+        fixed_surface()
+            .prop_flat_map(|first| {
+                let second_value = match first.value {
+                    Some(_) => value().prop_map(Some).boxed(),
+                    None => Just(None).boxed(),
+                };
+                (Just(first), second_value)
+            })
+            .prop_map(|(first, value)| Level {
+                first,
+                second: Some(FixedSurface {
+                    type_num: first.type_num,
+                    value,
+                }),
+            }),
+    ]
+}
+
+fn step() -> impl Strategy<Value = Step> {
+    let time_unit = prop::sample::select(
+        &[
+            TimeUnit::Minute,
+            TimeUnit::Hour,
+            TimeUnit::Day,
+            TimeUnit::Month,
+            TimeUnit::Year,
+            TimeUnit::ThreeHours,
+            TimeUnit::SixHours,
+            TimeUnit::TwelveHours,
+            TimeUnit::Second,
+        ][..],
+    );
+    let process = prop::sample::select(
+        &[
+            StatisticalProcess::Average,
+            StatisticalProcess::Accumulation,
+            StatisticalProcess::Maximum,
+            StatisticalProcess::Minimum,
+            StatisticalProcess::RootMeanSquare,
+            StatisticalProcess::Summation,
+        ][..],
+    );
+    (
+        time_unit.clone(),
+        0u32..10_000,
+        prop::option::of((process, time_unit, 0u32..10_000)),
+    )
+        // Calendar units can't be converted to or from other units.
+        .prop_filter_map(
+            "time range can't be expressed in the forecast time's unit",
+            |(time_unit, forecast_time, statistics)| {
+                let step = Step::new(time_unit, forecast_time)?;
+                match statistics {
+                    Some((process, length_unit, length)) => {
+                        step.with_statistics(process, length_unit, length)
+                    }
+                    None => Some(step),
+                }
+            },
+        )
+}
+
+proptest! {
+    #[test]
+    fn levels_round_trip_through_idx_text(level in level()) {
+        let text = level.to_string();
+        prop_assert_eq!(Level::from_idx_str(&text), Some(level), "{}", text);
+    }
+
+    #[test]
+    fn steps_round_trip_through_idx_text(step in step()) {
+        let text = step.to_string();
+        prop_assert_eq!(Step::from_idx_str(&text), Some(step), "{}", text);
+    }
+
+    #[test]
+    fn parsing_any_text_doesnt_panic(text in "[-0-9a-z .=e]{0,24}|\\PC{0,24}") {
+        let _ = Level::from_idx_str(&text);
+        let _ = Step::from_idx_str(&text);
+    }
+}
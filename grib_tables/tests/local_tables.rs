@@ -0,0 +1,105 @@
+#![cfg(feature = "runtime-tables")]
+
+use hypergrib_grib_tables::{
+    center_and_table_versions::CenterAndTableVersions, dataset::DatasetIndexer,
+    local_tables::LocalTable, AbbrevToProduct, Product,
+};
+
+const CSV: &str = "\
+center,local_table_version,discipline,category,parameter,abbrev,name,unit
+# Made-up parameters for a made-up regional model.
+85,,0,1,192,SNOWRAT,\"Snow, rain ratio\",Numeric
+85,3,0,1,193,GRPL3,Graupel (version 3),kg m-2
+";
+
+#[test]
+fn registered_tables_decode_idx_abbrevs() {
+    let table = LocalTable::from_csv(CSV.as_bytes()).unwrap();
+    assert_eq!(table.parameters().len(), 2);
+    assert_eq!(table.parameters()[0].name, "Snow, rain ratio");
+    assert_eq!(table.parameters()[0].local_table_version, None);
+    table.register();
+
+    let json = r#"[{"center": 85, "discipline": 0, "category": 1, "parameter": 194,
+        "abbrev": "HAIL", "name": "Hail", "unit": "kg m-2"}]"#;
+    LocalTable::from_json(json.as_bytes()).unwrap().register();
+
+    let idx = "\
+1:0:d=2024010200:SNOWRAT:surface:anl:
+2:100:d=2024010200:GRPL3:surface:anl:
+3:200:d=2024010200:HAIL:surface:anl:
+4:300:d=2024010200:TMP:surface:anl:
+";
+    let mut indexer = DatasetIndexer::new(85, 0, 2, 33);
+    indexer.add_idx("regional.grib2", idx, None).unwrap();
+    let index = indexer.finish();
+
+    // GRPL3 is only defined in version 3 of the local tables.
+    let products: Vec<_> = index.products().iter().map(|(abbrev, _)| *abbrev).collect();
+    assert_eq!(products, ["SNOWRAT", "HAIL", "TMP"]);
+    assert_eq!(index.undecoded().len(), 1);
+}
+
+#[test]
+fn reports_malformed_rows() {
+    let csv = "center,local_table_version,discipline,category,parameter,abbrev,name,unit\n\
+        85,,0,1,not a number,X,X,X\n";
+    let err = LocalTable::from_csv(csv.as_bytes()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid local table at line 2: invalid parameter \"not a number\""
+    );
+}
+
+#[test]
+fn registering_a_table_again_reuses_its_parameters() {
+    let csv = "center,local_table_version,discipline,category,parameter,abbrev,name,unit\n\
+        86,,0,1,192,SLEET,Sleet,kg m-2\n";
+    let tables = CenterAndTableVersions::from_numbers(86, 0, 1, 33);
+    LocalTable::from_csv(csv.as_bytes()).unwrap().register();
+    let first = tables.abbrev_to_product("SLEET").unwrap();
+    LocalTable::from_csv(csv.as_bytes()).unwrap().register();
+    let second = tables.abbrev_to_product("SLEET").unwrap();
+    assert!(std::ptr::eq(first, second));
+
+    // A changed parameter replaces the old one.
+    let changed = csv.replace("Sleet", "Ice pellets");
+    LocalTable::from_csv(changed.as_bytes()).unwrap().register();
+    assert_eq!(
+        tables.abbrev_to_product("SLEET").unwrap().name(),
+        "Ice pellets"
+    );
+}
+
+#[test]
+fn unregistered_versions_fall_back_to_the_nearest_earlier_version() {
+    let csv = "center,local_table_version,discipline,category,parameter,abbrev,name,unit\n\
+        87,2,0,1,192,GRPL2,Graupel (version 2),kg m-2\n\
+        87,5,0,1,192,GRPL5,Graupel (version 5),kg m-2\n\
+        87,,0,1,193,SNOWRAT,Snow-rain ratio,Numeric\n";
+    LocalTable::from_csv(csv.as_bytes()).unwrap().register();
+    let decode = |local_table_version, parameter_num| {
+        let tables = CenterAndTableVersions::from_numbers(87, 0, local_table_version, 33);
+        Product::from_discipline_and_category_and_parameter_numbers(0, 1, parameter_num, tables)
+            .known()
+            .map(|product| product.abbrev())
+    };
+    assert_eq!(decode(1, 192), None);
+    assert_eq!(decode(2, 192), Some("GRPL2"));
+    assert_eq!(decode(4, 192), Some("GRPL2"));
+    assert_eq!(decode(5, 192), Some("GRPL5"));
+    assert_eq!(decode(9, 192), Some("GRPL5"));
+
+    // 0 and 255 don't name a version, so use the latest.
+    assert_eq!(decode(0, 192), Some("GRPL5"));
+    assert_eq!(decode(255, 192), Some("GRPL5"));
+
+    // Parameters without a version apply to every version.
+    assert_eq!(decode(1, 193), Some("SNOWRAT"));
+    assert_eq!(decode(9, 193), Some("SNOWRAT"));
+
+    // Abbreviations are looked up in the same version.
+    let tables = CenterAndTableVersions::from_numbers(87, 0, 4, 33);
+    assert!(tables.abbrev_to_product("GRPL2").is_some());
+    assert!(tables.abbrev_to_product("GRPL5").is_none());
+}
@@ -0,0 +1,119 @@
+use hypergrib_grib_tables::{
+    center_and_table_versions::CenterAndTableVersions,
+    encoder::Encoder,
+    grid::{Grid, LambertConformalGrid, LatLonGrid, PolarStereographicGrid},
+    level::Level,
+    message::ReferenceTime,
+    point::Interpolation,
+    reader::messages,
+    step::{Step, TimeUnit},
+    AbbrevToProduct,
+};
+
+#[test]
+fn interpolates_across_the_date_line() {
+    // Latitudes 90, 0 and -90, and longitudes 0, 90, 180 and 270.
+    let grid = Grid::LatLon(LatLonGrid {
+        shape_of_earth: 6,
+        ni: 4,
+        nj: 3,
+        first_lat: 90.0,
+        first_lon: 0.0,
+        resolution_and_component_flags: 0x30,
+        last_lat: -90.0,
+        last_lon: 270.0,
+        di: 90.0,
+        dj: 90.0,
+        scanning_mode: 0,
+    });
+    let center_and_table_versions = CenterAndTableVersions::from_numbers(7, 0, 1, 33);
+    let encoder = Encoder {
+        product: center_and_table_versions.abbrev_to_product("TMP").unwrap(),
+        center_and_table_versions: &center_and_table_versions,
+        reference_time: ReferenceTime {
+            year: 2024,
+            month: 1,
+            day: 2,
+            hour: 6,
+            minute: 0,
+            second: 0,
+        },
+        type_of_generating_process: 2,
+        grid,
+        level: Level::from_idx_str("2 m above ground").unwrap(),
+        step: Step::new(TimeUnit::Hour, 6).unwrap(),
+        bits_per_value: 12,
+        decimal_scale_factor: 0,
+    };
+    let values: Vec<f32> = (0..12).map(|i| 260.0 + i as f32).collect();
+    let grib2 = encoder.encode(&values).unwrap();
+    let message = messages(&grib2).next().unwrap().unwrap();
+    let field = &message.fields()[0];
+
+    // Halfway between 270 E and 0 E, and between 90 N and the equator.
+    let weights = grid.weights(45.0, -45.0, Interpolation::Bilinear).unwrap();
+    let points: Vec<_> = weights.points.iter().map(|p| (p.i, p.j, p.index)).collect();
+    assert_eq!(points, [(3, 0, 3), (0, 0, 0), (3, 1, 7), (0, 1, 4)]);
+    assert!(weights.points.iter().all(|p| p.weight == 0.25));
+
+    let extracted = field
+        .values_at(&[(45.0, 315.0), (-90.0, 91.0)], Interpolation::Bilinear)
+        .unwrap();
+    assert_eq!(extracted[0], (263.0 + 260.0 + 267.0 + 264.0) / 4.0);
+    assert!((extracted[1] - (269.0 + 1.0 / 90.0)).abs() < 1e-3);
+    let nearest = field
+        .values_at(&[(44.0, 359.0), (-91.0, 0.0)], Interpolation::Nearest)
+        .unwrap();
+    assert_eq!(nearest[0], 264.0);
+    assert!(nearest[1].is_nan());
+}
+
+#[test]
+fn finds_points_on_projected_grids() {
+    // The first point is at 60 N on the central meridian, and the pole is 10 points north of it.
+    let rho_at_60 = 6_371_229.0 * (1.0 + 60f64.to_radians().sin()) * 15f64.to_radians().tan();
+    let polar = Grid::PolarStereographic(PolarStereographicGrid {
+        shape_of_earth: 6,
+        nx: 21,
+        ny: 21,
+        first_lat: 60.0,
+        first_lon: 255.0,
+        resolution_and_component_flags: 0x08,
+        lad: 60.0,
+        lov: 255.0,
+        dx: rho_at_60 / 10.0,
+        dy: rho_at_60 / 10.0,
+        projection_centre: 0,
+        scanning_mode: 0x40,
+    });
+    let nearest = |grid: &Grid, lat, lon| {
+        let point = grid.weights(lat, lon, Interpolation::Nearest)?.points[0];
+        Some((point.i, point.j))
+    };
+    assert_eq!(nearest(&polar, 90.0, 0.0), Some((0, 10)));
+    assert_eq!(nearest(&polar, 60.0, -15.0), Some((10, 10)));
+    assert_eq!(nearest(&polar, 59.0, 255.0), None);
+
+    // The HRRR's grid, whose last point is at 47.842195 N, 60.917193 W.
+    let hrrr = Grid::LambertConformal(LambertConformalGrid {
+        shape_of_earth: 6,
+        nx: 1799,
+        ny: 1059,
+        first_lat: 21.138123,
+        first_lon: 237.280472,
+        resolution_and_component_flags: 0x08,
+        lad: 38.5,
+        lov: 262.5,
+        dx: 3000.0,
+        dy: 3000.0,
+        projection_centre: 0,
+        scanning_mode: 0x40,
+        latin1: 38.5,
+        latin2: 38.5,
+        south_pole_lat: -90.0,
+        south_pole_lon: 0.0,
+    });
+    assert_eq!(nearest(&hrrr, 21.138123, -122.719528), Some((0, 0)));
+    assert_eq!(nearest(&hrrr, 47.842195, -60.917193), Some((1798, 1058)));
+    assert_eq!(nearest(&hrrr, 51.5, 0.0), None);
+}
@@ -0,0 +1,60 @@
+use std::collections::HashSet;
+
+use hypergrib_grib_tables::{
+    center_and_table_versions::CenterAndTableVersions, product_id::ProductId,
+    table_diff::ParameterTable, AbbrevToProduct, Product,
+};
+
+#[test]
+fn round_trips_between_products_and_ids() {
+    let tables = CenterAndTableVersions::from_numbers(7, 0, 1, 33);
+    let mut ids = HashSet::new();
+    for abbrev in ["TMP", "APCP", "MASSDEN", "CRAIN", "HPBL"] {
+        let id = tables.abbrev_to_product(abbrev).unwrap().id(&tables);
+        assert_eq!(id.to_product().unwrap().abbrev(), abbrev);
+        assert_eq!(ProductId::from_u64(id.to_u64()), Some(id));
+        assert!(ids.insert(id));
+    }
+
+    let tmp = tables.abbrev_to_product("TMP").unwrap().id(&tables);
+    assert_eq!(tmp, ProductId::new(0, 0, 0));
+    assert!(!tmp.is_local());
+    let crain = tables.abbrev_to_product("CRAIN").unwrap().id(&tables);
+    assert!(crain.is_local());
+    assert_eq!(
+        (
+            crain.center_num(),
+            crain.local_table_version(),
+            crain.category_num()
+        ),
+        (7, 1, 1)
+    );
+    assert!(crain < tmp, "WMO products sort after local ones");
+
+    // Every product in the master tables has its own ID.
+    let master_table = ParameterTable::from_master_table_version(33);
+    let ids: HashSet<_> = master_table
+        .entries()
+        .map(|entry| ProductId::new(entry.discipline, entry.category, entry.parameter))
+        .collect();
+    assert_eq!(ids.len(), master_table.entries().count());
+    assert!(ids.iter().all(|id| id.to_product().is_some()));
+
+    // IDs which don't match a product, or which weren't made by `ProductId`.
+    assert!(ProductId::new(0, 0, 150).to_product().is_none());
+    assert!(ProductId::new_local(7, 0, 0, 0, 0, 0)
+        .to_product()
+        .is_none());
+    assert_eq!(ProductId::from_u64(tmp.to_u64() | 0x100), None);
+    assert_eq!(ProductId::from_u64(tmp.to_u64() | 1), None);
+}
+
+#[test]
+fn doesnt_know_any_products_in_discipline_191() {
+    for master_table_version in [32, 33] {
+        let tables = CenterAndTableVersions::from_numbers(7, 0, 1, master_table_version);
+        let product =
+            Product::from_discipline_and_category_and_parameter_numbers(191, 0, 0, tables);
+        assert!(product.known().is_none());
+    }
+}
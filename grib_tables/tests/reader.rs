@@ -0,0 +1,120 @@
+use std::io::Cursor;
+
+use hypergrib_grib_tables::{
+    error::Error,
+    reader::{messages, MessageReader},
+};
+
+fn section(section_num: u8, body: &[u8]) -> Vec<u8> {
+    let mut section = ((body.len() + 5) as u32).to_be_bytes().to_vec();
+    section.push(section_num);
+    section.extend_from_slice(body);
+    section
+}
+
+/// A GRIB2 message holding `n_fields` fields of 2 m temperature. Only the sections which the
+/// reader looks at are filled in.
+fn message(n_fields: usize) -> Vec<u8> {
+    let mut identification = vec![0, 7, 0, 0, 2, 1, 1];
+    identification.extend_from_slice(&2024u16.to_be_bytes());
+    identification.extend_from_slice(&[1, 2, 6, 0, 0, 0, 1]);
+
+    let mut product_definition = vec![0, 0, 0, 0, 0, 0, 2, 0, 96, 0, 0, 0, 1];
+    product_definition.extend_from_slice(&6u32.to_be_bytes());
+    product_definition.extend_from_slice(&[103, 0, 0, 0, 0, 2, 255, 255, 255, 255, 255, 255]);
+
+    let mut body = section(1, &identification);
+    body.extend(section(3, &[0; 67]));
+    for _ in 0..n_fields {
+        body.extend(section(4, &product_definition));
+        body.extend(section(5, &[0; 16]));
+        body.extend(section(6, &[255]));
+        body.extend(section(7, &[]));
+    }
+
+    let mut message = b"GRIB\0\0\0\x02".to_vec();
+    message.extend_from_slice(&((body.len() + 20) as u64).to_be_bytes());
+    message.extend(body);
+    message.extend_from_slice(b"7777");
+    message
+}
+
+#[test]
+fn skips_junk_between_messages() {
+    let first = message(1);
+    let second = message(2);
+    let mut file = b"junk".to_vec();
+    file.extend(&first);
+    file.extend(b"more junk, including GRI");
+    file.extend(&second);
+    file.extend(b"trailing junk");
+
+    let expected = [
+        (4, first.len() as u64, 1),
+        (4 + first.len() as u64 + 24, second.len() as u64, 2),
+    ];
+    let from_slice: Vec<_> = messages(&file)
+        .map(|message| {
+            let message = message.unwrap();
+            (
+                message.offset(),
+                message.total_length(),
+                message.fields().len(),
+            )
+        })
+        .collect();
+    assert_eq!(from_slice, expected);
+
+    let from_reader: Vec<_> = MessageReader::new(Cursor::new(&file))
+        .map(|message_buf| {
+            let message_buf = message_buf.unwrap();
+            (
+                message_buf.offset(),
+                message_buf.total_length(),
+                message_buf.message().fields().len(),
+            )
+        })
+        .collect();
+    assert_eq!(from_reader, expected);
+}
+
+#[test]
+fn reports_truncated_messages() {
+    let mut file = message(1);
+    let second = message(1);
+    file.extend(&second[..second.len() - 10]);
+
+    let results: Vec<_> = messages(&file).collect();
+    assert_eq!(results.len(), 2);
+    assert!(results[0].is_ok());
+    assert!(matches!(
+        results[1],
+        Err(Error::Truncated { offset, .. }) if offset == second.len() as u64
+    ));
+
+    let results: Vec<_> = MessageReader::new(Cursor::new(&file)).collect();
+    assert_eq!(results.len(), 2);
+    assert!(matches!(results[1], Err(Error::Truncated { .. })));
+}
+
+#[test]
+fn skips_messages_whose_total_length_is_too_short() {
+    for total_length in [0u64, 19] {
+        let mut file = message(1);
+        file[8..16].copy_from_slice(&total_length.to_be_bytes());
+        let second = message(1);
+        file.extend(&second);
+
+        let results: Vec<_> = messages(&file).take(5).collect();
+        assert_eq!(results.len(), 2, "total length {total_length}");
+        assert!(matches!(
+            results[0],
+            Err(Error::InvalidSection { section_num: 0, .. })
+        ));
+        assert!(results[1].is_ok());
+
+        let results: Vec<_> = MessageReader::new(Cursor::new(&file)).take(5).collect();
+        assert_eq!(results.len(), 2, "total length {total_length}");
+        assert!(results[1].is_ok());
+    }
+}
@@ -0,0 +1,35 @@
+use hypergrib_grib_tables::{
+    center_and_table_versions::CenterAndTableVersions, semantics::Semantics, step::Step,
+    AbbrevToProduct,
+};
+
+#[test]
+fn distinguishes_accumulations_from_rates() {
+    let tables = CenterAndTableVersions::from_numbers(7, 0, 1, 33);
+    let semantics = |abbrev: &str, step: &str| {
+        Semantics::from_product_and_step(
+            tables.abbrev_to_product(abbrev).unwrap(),
+            &Step::from_idx_str(step).unwrap(),
+        )
+    };
+    assert_eq!(
+        semantics("APCP", "0-12 hour acc fcst"),
+        Semantics::AccumulatedSinceStart
+    );
+    assert_eq!(
+        semantics("APCP", "6-12 hour acc fcst"),
+        Semantics::AccumulatedOverWindow
+    );
+    assert_eq!(semantics("PRATE", "12 hour fcst"), Semantics::Instantaneous);
+    assert_eq!(
+        semantics("PRATE", "6-12 hour ave fcst"),
+        Semantics::Averaged
+    );
+    assert_eq!(semantics("TMAX", "6-12 hour max fcst"), Semantics::Extremum);
+
+    let apcp = tables.abbrev_to_product("APCP").unwrap();
+    assert_eq!(apcp.semantics(), Semantics::AccumulatedOverWindow);
+    assert!(!apcp.plausible_range().unwrap().contains(&-1.0));
+    let tmp = tables.abbrev_to_product("TMP").unwrap();
+    assert!(tmp.plausible_range().unwrap().contains(&273.15));
+}
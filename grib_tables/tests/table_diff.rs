@@ -0,0 +1,46 @@
+use hypergrib_grib_tables::{error::Error, table_diff::ParameterTable};
+
+const WMO_CSV: &str = r#"Title_en,SubTitle_en,CodeFlag,Value,MeaningParameterDescription_en,Note_en,UnitComments_en,Status
+"Code table 4.2 - Parameter number by product discipline and parameter category","Product discipline 0 - Meteorological products, parameter category 0: temperature",0,,Temperature,,K,Operational
+"Code table 4.2 - Parameter number by product discipline and parameter category","Product discipline 0 - Meteorological products, parameter category 0: temperature",1,,Virtual temperature,,degC,Operational
+"Code table 4.2 - Parameter number by product discipline and parameter category","Product discipline 0 - Meteorological products, parameter category 0: temperature",2,,"Potential
+temperature (renamed)",,K,Operational
+"Code table 4.2 - Parameter number by product discipline and parameter category","Product discipline 0 - Meteorological products, parameter category 0: temperature",33,,Brand new temperature,,K,Operational
+"Code table 4.2 - Parameter number by product discipline and parameter category","Product discipline 0 - Meteorological products, parameter category 0: temperature",192-254,,Reserved for local use,,,
+"#;
+
+#[test]
+fn diffs_compiled_tables_against_a_wmo_csv() {
+    let older = ParameterTable::from_master_table_version(33);
+    let newer = ParameterTable::from_wmo_csv(WMO_CSV.as_bytes()).unwrap();
+    assert_eq!(newer.entries().count(), 4);
+
+    let diff = older.diff(&newer);
+    assert_eq!(diff.added.len(), 1);
+    assert_eq!(diff.added[0].name, "Brand new temperature");
+
+    // "Temperature" and "Virtual temperature" are unchanged apart from the latter's unit.
+    assert_eq!(diff.renamed.len(), 1);
+    assert_eq!(diff.renamed[0].0.abbrev.as_deref(), Some("POT"));
+    assert_eq!(diff.unit_changed.len(), 1);
+    assert_eq!(diff.unit_changed[0].1.unit, "degC");
+    assert_eq!(
+        diff.removed.len(),
+        older.entries().count() - 3,
+        "everything missing from the CSV counts as removed"
+    );
+    assert!(diff
+        .to_string()
+        .contains("unit changed: 0.0.1 VTMP \"Virtual temperature\" (K) -> 0.0.1 \"Virtual temperature\" (degC)"));
+
+    assert!(older.diff(&older).is_empty());
+}
+
+#[test]
+fn rejects_malformed_wmo_csvs() {
+    let csv = "SubTitle_en,CodeFlag,MeaningParameterDescription_en,UnitComments_en\nnonsense,0,Temperature,K\n";
+    assert!(matches!(
+        ParameterTable::from_wmo_csv(csv.as_bytes()),
+        Err(Error::InvalidWmoTable { line: 2, .. })
+    ));
+}
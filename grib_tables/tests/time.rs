@@ -0,0 +1,178 @@
+use hypergrib_grib_tables::{
+    error::Error,
+    idx::write_inventory,
+    message::ReferenceTime,
+    step::{StatisticalProcess, Step, TimeUnit},
+    time::ValidTime,
+    validate::{validate_grib, IssueKind},
+};
+
+fn time(s: &str) -> ReferenceTime {
+    ReferenceTime::from_idx_str(s).unwrap()
+}
+
+#[test]
+fn computes_valid_times_across_month_and_year_boundaries() {
+    let reference_time = time("d=2024022818");
+    let valid_time =
+        |step: &str| ValidTime::new(reference_time, &Step::from_idx_str(step).unwrap());
+
+    let analysis = valid_time("anl").unwrap();
+    assert_eq!(analysis.start, reference_time);
+    assert!(analysis.is_instantaneous());
+
+    // 2024 is a leap year.
+    assert_eq!(
+        valid_time("12 hour fcst").unwrap().start,
+        time("d=2024022906")
+    );
+    assert_eq!(
+        valid_time("2 day fcst").unwrap().start,
+        time("d=2024030118")
+    );
+
+    let accumulation = valid_time("6-30 hour acc fcst").unwrap();
+    assert_eq!(accumulation.start, time("d=2024022900"));
+    assert_eq!(accumulation.end, time("d=2024030100"));
+
+    let new_year = time("d=2023123123")
+        .checked_add(TimeUnit::Minute, 90)
+        .unwrap();
+    assert_eq!((new_year.year, new_year.month, new_year.day), (2024, 1, 1));
+    assert_eq!((new_year.hour, new_year.minute), (0, 30));
+}
+
+#[test]
+fn adds_calendar_units_without_overflowing_the_month() {
+    let end_of_january = time("d=2023013112");
+    let add = |time_unit, count| end_of_january.checked_add(time_unit, count).unwrap();
+    assert_eq!(add(TimeUnit::Month, 1), time("d=2023022812"));
+    assert_eq!(add(TimeUnit::Month, 13), time("d=2024022912"));
+    assert_eq!(add(TimeUnit::Year, 1), time("d=2024013112"));
+    assert_eq!(add(TimeUnit::Decade, 1), time("d=2033013112"));
+
+    assert_eq!(ReferenceTime::from_idx_str("d=2023022912"), None);
+    assert_eq!(
+        time("d=2023013112").checked_add(TimeUnit::Century, 1000),
+        None
+    );
+}
+
+#[test]
+fn rejects_steps_which_overflow() {
+    // Forecast times and time ranges come straight from the file, so may be nonsense.
+    assert_eq!(Step::new(TimeUnit::TwelveHours, u32::MAX / 2), None);
+    let step = Step::new(TimeUnit::Hour, u32::MAX - 1).unwrap();
+    assert_eq!(step.end(), Some(u32::MAX - 1));
+    assert_eq!(
+        step.with_statistics(StatisticalProcess::Accumulation, TimeUnit::Hour, 2),
+        None
+    );
+    assert_eq!(
+        Step::new(TimeUnit::Day, u32::MAX).unwrap().with_statistics(
+            StatisticalProcess::Average,
+            TimeUnit::Minute,
+            1
+        ),
+        None,
+        "the forecast time doesn't fit in a u32 when converted into minutes"
+    );
+    assert_eq!(
+        Step::from_idx_str("4294967295-4294967296 hour acc fcst"),
+        None
+    );
+}
+
+#[test]
+fn rejects_time_ranges_in_units_which_cant_be_converted() {
+    // A 1 month average which starts 6 hours after the reference time can't be represented in
+    // one unit without knowing the dates. Messages give the end of the time range as well, which
+    // `decodes_calendar_time_ranges_from_the_end_of_the_interval` uses instead.
+    let six_hours = Step::new(TimeUnit::Hour, 6).unwrap();
+    assert_eq!(
+        six_hours.with_statistics(StatisticalProcess::Average, TimeUnit::Month, 1),
+        None
+    );
+
+    // But it can if it starts at the reference time.
+    let monthly_mean = Step::new(TimeUnit::Hour, 0)
+        .unwrap()
+        .with_statistics(StatisticalProcess::Average, TimeUnit::Month, 1)
+        .unwrap();
+    assert_eq!(monthly_mean.to_string(), "0-1 month ave fcst");
+    let valid_time = ValidTime::new(time("d=2024013100"), &monthly_mean).unwrap();
+    assert_eq!(valid_time.end, time("d=2024022900"));
+
+    // Units which are multiples of each other are converted.
+    let step = six_hours
+        .with_statistics(StatisticalProcess::Accumulation, TimeUnit::Day, 1)
+        .unwrap();
+    assert_eq!(step.to_string(), "6-30 hour acc fcst");
+}
+
+fn section(section_num: u8, body: &[u8]) -> Vec<u8> {
+    let mut section = ((body.len() + 5) as u32).to_be_bytes().to_vec();
+    section.push(section_num);
+    section.extend_from_slice(body);
+    section
+}
+
+/// A GRIB2 message with reference time 2024-01-01 12:00, holding the average temperature at 2 m
+/// (template 4.8) over one month, starting 6 hours after the reference time and ending at `end`
+/// (year, month, day, hour).
+fn monthly_mean(end: (u16, u8, u8, u8)) -> Vec<u8> {
+    let mut identification = vec![0, 7, 0, 0, 33, 1, 1];
+    identification.extend_from_slice(&2024u16.to_be_bytes());
+    identification.extend_from_slice(&[1, 1, 12, 0, 0, 0, 1]);
+
+    let mut product_definition = vec![0, 0, 0, 8, 0, 0, 2, 0, 96, 0, 0, 0, 1];
+    product_definition.extend_from_slice(&6u32.to_be_bytes());
+    product_definition.extend_from_slice(&[103, 0, 0, 0, 0, 2, 255, 255, 255, 255, 255, 255]);
+    product_definition.extend_from_slice(&end.0.to_be_bytes());
+    product_definition.extend_from_slice(&[end.1, end.2, end.3, 0, 0, 1, 0, 0, 0, 0, 0, 2]);
+    product_definition.push(3);
+    product_definition.extend_from_slice(&1u32.to_be_bytes());
+    product_definition.extend_from_slice(&[255, 0, 0, 0, 0]);
+
+    let mut body = section(1, &identification);
+    body.extend(section(3, &[0; 67]));
+    body.extend(section(4, &product_definition));
+    body.extend(section(5, &[0; 16]));
+    body.extend(section(6, &[255]));
+    body.extend(section(7, &[]));
+
+    let mut message = b"GRIB\0\0\0\x02".to_vec();
+    message.extend_from_slice(&((body.len() + 20) as u64).to_be_bytes());
+    message.extend(body);
+    message.extend_from_slice(b"7777");
+    message
+}
+
+#[test]
+fn decodes_calendar_time_ranges_from_the_end_of_the_interval() {
+    // January 2024 has 31 days, so the month starting at 18:00 on 1 January is 744 hours long.
+    let month = monthly_mean((2024, 2, 1, 18));
+    let mut inventory = Vec::new();
+    write_inventory(&month, &mut inventory).unwrap();
+    assert_eq!(
+        String::from_utf8(inventory).unwrap(),
+        "1:0:d=2024010112:TMP:2 m above ground:6-750 hour ave fcst:\n"
+    );
+    let step = Step::from_idx_str("6-750 hour ave fcst").unwrap();
+    let valid_time = ValidTime::new(time("d=2024010112"), &step).unwrap();
+    assert_eq!(valid_time.start, time("d=2024010118"));
+    assert_eq!(valid_time.end, time("d=2024020118"));
+
+    // An end which doesn't exist, or which is before the start, is still an error.
+    let is_invalid = |grib: &[u8]| {
+        validate_grib(grib).issues.iter().any(|issue| {
+            matches!(
+                issue.kind,
+                IssueKind::InvalidMessage(Error::InvalidSection { section_num: 4, .. })
+            )
+        })
+    };
+    assert!(!is_invalid(&month));
+    assert!(is_invalid(&monthly_mean((2024, 2, 30, 18))));
+    assert!(is_invalid(&monthly_mean((2024, 1, 1, 0))));
+}
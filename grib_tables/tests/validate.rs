@@ -0,0 +1,141 @@
+use hypergrib_grib_tables::{
+    center_and_table_versions::CenterAndTableVersions,
+    encoder::Encoder,
+    grid::{Grid, LatLonGrid},
+    idx::write_inventory,
+    level::Level,
+    message::ReferenceTime,
+    step::{Step, TimeUnit},
+    validate::{validate_grib, validate_grib_and_idx, validate_idx, IssueKind},
+    AbbrevToProduct,
+};
+
+fn encode(abbrev: &str) -> Vec<u8> {
+    let center_and_table_versions = CenterAndTableVersions::from_numbers(7, 0, 1, 33);
+    Encoder {
+        product: center_and_table_versions.abbrev_to_product(abbrev).unwrap(),
+        center_and_table_versions: &center_and_table_versions,
+        reference_time: ReferenceTime {
+            year: 2024,
+            month: 1,
+            day: 2,
+            hour: 6,
+            minute: 0,
+            second: 0,
+        },
+        type_of_generating_process: 2,
+        grid: Grid::LatLon(LatLonGrid {
+            shape_of_earth: 6,
+            ni: 2,
+            nj: 2,
+            first_lat: 50.0,
+            first_lon: 0.0,
+            resolution_and_component_flags: 0x30,
+            last_lat: 49.0,
+            last_lon: 1.0,
+            di: 1.0,
+            dj: 1.0,
+            scanning_mode: 0,
+        }),
+        level: Level::from_idx_str("2 m above ground").unwrap(),
+        step: Step::new(TimeUnit::Hour, 6).unwrap(),
+        bits_per_value: 8,
+        decimal_scale_factor: 0,
+    }
+    .encode(&[280.0, 281.0, 282.0, 283.0])
+    .unwrap()
+}
+
+#[test]
+fn reports_unknown_parameters_and_reserved_values() {
+    let mut grib = encode("TMP");
+    grib.extend(encode("RH"));
+    let mut inventory = Vec::new();
+    write_inventory(&grib, &mut inventory).unwrap();
+    let idx = String::from_utf8(inventory).unwrap();
+    let report = validate_grib_and_idx(&grib, &idx).unwrap();
+    assert_eq!(report.num_fields, 2);
+    assert!(report.is_valid(), "{report}");
+
+    // Turn the second message's parameter into one which isn't in the tables, and make its type
+    // of data (code table 1.4, octet 21 of section 1) reserved.
+    let second = encode("TMP").len();
+    let section_4 = second + 16 + 21 + 72;
+    assert_eq!(grib[section_4 + 4], 4);
+    grib[section_4 + 10] = 150;
+    grib[second + 16 + 20] = 100;
+
+    let report = validate_grib(&grib);
+    let kinds: Vec<_> = report.issues.iter().map(|issue| &issue.kind).collect();
+    assert!(matches!(
+        kinds.as_slice(),
+        [
+            IssueKind::ReservedValue {
+                code_table: "1.4",
+                value: 100
+            },
+            IssueKind::UnknownParameter {
+                discipline: 0,
+                category: 1,
+                parameter: 150,
+                local: false
+            },
+        ]
+    ));
+    assert!(report.issues.iter().all(|issue| issue.message_num == 2));
+
+    // The `.idx` still says "RH", and lists a third message which doesn't exist.
+    let idx = format!("{idx}3:{}:d=2024010206:TMP:surface:anl:\n", grib.len());
+    let report = validate_grib_and_idx(&grib, &idx).unwrap();
+    let lines: Vec<_> = report.to_string().lines().map(String::from).collect();
+    assert_eq!(
+        lines[2..],
+        [
+            "2: abbrev is \"RH\" in the .idx but \"var discipline=0 center=7 local_table=1 parmcat=1 parm=150\" in the GRIB2",
+            "3: missing from the GRIB2 file",
+        ]
+    );
+}
+
+#[test]
+fn reports_unrecognised_idx_text() {
+    let idx = "1:0:d=2024010100:TMP:2 m above ground:anl:\n\
+               2:100:d=2024010100:NOTAPARAM:level type 190:anl:\n\
+               3:200:d=2024010100:RH:2 m above ground:sometime:\n";
+    let report = validate_idx(idx, 7, 0, 1, 33).unwrap();
+    assert_eq!(report.num_fields, 3);
+    assert_eq!(
+        report.to_string(),
+        "2: unknown abbreviation NOTAPARAM\n\
+         2: unknown level type 190\n\
+         3: unrecognised step \"sometime\"\n"
+    );
+}
+
+#[test]
+fn doesnt_report_undecodable_messages_as_missing() {
+    let mut grib = encode("TMP");
+    grib.extend(encode("RH"));
+    let mut inventory = Vec::new();
+    write_inventory(&grib, &mut inventory).unwrap();
+    let idx = String::from_utf8(inventory).unwrap();
+
+    // Give the second message a product definition template which we don't support.
+    let second = encode("TMP").len();
+    let section_4 = second + 16 + 21 + 72;
+    grib[section_4 + 7..section_4 + 9].copy_from_slice(&99u16.to_be_bytes());
+
+    let report = validate_grib_and_idx(&grib, &idx).unwrap();
+    let kinds: Vec<_> = report.issues.iter().map(|issue| &issue.kind).collect();
+    assert!(
+        matches!(
+            kinds.as_slice(),
+            [IssueKind::UnsupportedTemplate {
+                section_num: 4,
+                template_num: 99
+            }]
+        ),
+        "{report}"
+    );
+    assert_eq!(report.issues[0].message_num, 2);
+}